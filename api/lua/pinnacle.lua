@@ -38,6 +38,54 @@ function pinnacle.reload_config()
     client.unary_request(pinnacle_service.ReloadConfig, {})
 end
 
+---@enum (key) PrivilegedProtocol
+local _privileged_protocol = {
+    screencopy = 1,
+    toplevel_export = 2,
+    gamma_control = 3,
+    data_control = 4,
+    layer_shell = 5,
+    virtual_keyboard = 6,
+    virtual_pointer = 7,
+    input_method = 8,
+    session_lock = 9,
+    foreign_toplevel = 10,
+    ext_workspace = 11,
+    output_management = 12,
+}
+
+---Allow sandboxed clients to use privileged protocols.
+---
+---Clients that connect through a security context, like Flatpak apps, can't use any
+---privileged protocol by default. This allows clients matching `sandbox_engine` and `app_id`
+---to use `protocols`. Leaving either out matches all engines or apps.
+---
+---This only affects clients that connect afterwards.
+---
+---### Example
+---```lua
+--- -- Allow OBS to capture the screen
+---Pinnacle.allow_sandboxed_client({
+---    sandbox_engine = "flatpak",
+---    app_id = "com.obsproject.Studio",
+---}, { "screencopy", "toplevel_export" })
+---```
+---
+---@param client_match { sandbox_engine: string?, app_id: string? }
+---@param protocols PrivilegedProtocol[]
+function pinnacle.allow_sandboxed_client(client_match, protocols)
+    local protos = {}
+    for _, protocol in ipairs(protocols) do
+        table.insert(protos, _privileged_protocol[protocol])
+    end
+
+    client.unary_request(pinnacle_service.AllowSandboxedClient, {
+        sandbox_engine = client_match.sandbox_engine,
+        app_id = client_match.app_id,
+        protocols = protos,
+    })
+end
+
 ---Setup a Pinnacle config.
 ---
 ---You must pass in a function that takes in the `Pinnacle` table. This table is how you'll access the other config modules.
@@ -34,6 +34,28 @@ local pinnacle_v0alpha1_SetOrToggle = {
     SET_OR_TOGGLE_TOGGLE = 3,
 }
 
+---@enum pinnacle.v0alpha1.PrivilegedProtocol
+local pinnacle_v0alpha1_PrivilegedProtocol = {
+    PRIVILEGED_PROTOCOL_UNSPECIFIED = 0,
+    PRIVILEGED_PROTOCOL_SCREENCOPY = 1,
+    PRIVILEGED_PROTOCOL_TOPLEVEL_EXPORT = 2,
+    PRIVILEGED_PROTOCOL_GAMMA_CONTROL = 3,
+    PRIVILEGED_PROTOCOL_DATA_CONTROL = 4,
+    PRIVILEGED_PROTOCOL_LAYER_SHELL = 5,
+    PRIVILEGED_PROTOCOL_VIRTUAL_KEYBOARD = 6,
+    PRIVILEGED_PROTOCOL_VIRTUAL_POINTER = 7,
+    PRIVILEGED_PROTOCOL_INPUT_METHOD = 8,
+    PRIVILEGED_PROTOCOL_SESSION_LOCK = 9,
+    PRIVILEGED_PROTOCOL_FOREIGN_TOPLEVEL = 10,
+    PRIVILEGED_PROTOCOL_EXT_WORKSPACE = 11,
+    PRIVILEGED_PROTOCOL_OUTPUT_MANAGEMENT = 12,
+}
+
+---@class pinnacle.v0alpha1.AllowSandboxedClientRequest
+---@field sandbox_engine string?
+---@field app_id string?
+---@field protocols pinnacle.v0alpha1.PrivilegedProtocol[]?
+
 -- Output
 
 ---@class pinnacle.output.v0alpha1.Mode
@@ -117,6 +139,14 @@ local pinnacle_output_v0alpha1_Transform = {
 ---@field window_id integer?
 ---@field set_or_toggle pinnacle.v0alpha1.SetOrToggle?
 
+---@class pinnacle.window.v0alpha1.SetMinimizedRequest
+---@field window_id integer?
+---@field set_or_toggle pinnacle.v0alpha1.SetOrToggle?
+
+---@class pinnacle.window.v0alpha1.SetStickyRequest
+---@field window_id integer?
+---@field set_or_toggle pinnacle.v0alpha1.SetOrToggle?
+
 ---@class pinnacle.window.v0alpha1.SetFloatingRequest
 ---@field window_id integer?
 ---@field set_or_toggle pinnacle.v0alpha1.SetOrToggle?
@@ -137,6 +167,81 @@ local pinnacle_output_v0alpha1_Transform = {
 ---@class pinnacle.window.v0alpha1.RaiseRequest
 ---@field window_id integer?
 
+---@class pinnacle.window.v0alpha1.SwapRequest
+---@field window_id integer?
+---@field target_id integer?
+
+---@class pinnacle.window.v0alpha1.MoveInStackRequest
+---@field window_id integer?
+---@field delta integer?
+
+---@class pinnacle.window.v0alpha1.GroupWithRequest
+---@field window_id integer?
+---@field target_id integer?
+
+---@class pinnacle.window.v0alpha1.UngroupRequest
+---@field window_id integer?
+
+---@class pinnacle.window.v0alpha1.CycleGroupRequest
+---@field window_id integer?
+---@field delta integer?
+
+---@enum pinnacle.window.v0alpha1.DecorationMode
+local pinnacle_window_v0alpha1_DecorationMode = {
+    DECORATION_MODE_UNSPECIFIED = 0,
+    DECORATION_MODE_CLIENT_SIDE = 1,
+    DECORATION_MODE_SERVER_SIDE = 2,
+}
+
+---@enum pinnacle.window.v0alpha1.Direction
+local pinnacle_window_v0alpha1_Direction = {
+    DIRECTION_UNSPECIFIED = 0,
+    DIRECTION_LEFT = 1,
+    DIRECTION_RIGHT = 2,
+    DIRECTION_UP = 3,
+    DIRECTION_DOWN = 4,
+}
+
+---@class pinnacle.window.v0alpha1.FocusDirectionRequest
+---@field direction pinnacle.window.v0alpha1.Direction?
+
+---@class pinnacle.window.v0alpha1.MoveDirectionRequest
+---@field direction pinnacle.window.v0alpha1.Direction?
+
+---@class pinnacle.window.v0alpha1.SendToScratchpadRequest
+---@field window_id integer?
+---@field name string?
+
+---@class pinnacle.window.v0alpha1.ToggleScratchpadRequest
+---@field name string?
+
+---@class pinnacle.window.v0alpha1.SetDecorationModeRequest
+---@field window_id integer?
+---@field decoration_mode pinnacle.window.v0alpha1.DecorationMode?
+
+---@class pinnacle.window.v0alpha1.SetCaptureHiddenRequest
+---@field window_id integer?
+---@field hidden boolean?
+
+---@class pinnacle.window.v0alpha1.SetOpacityRequest
+---@field window_id integer?
+---@field opacity number?
+
+---@class pinnacle.window.v0alpha1.SetDefaultOpacityRequest
+---@field focused number?
+---@field unfocused number?
+
+---@enum pinnacle.window.v0alpha1.ActivationPolicy
+local pinnacle_window_v0alpha1_ActivationPolicy = {
+    ACTIVATION_POLICY_UNSPECIFIED = 0,
+    ACTIVATION_POLICY_FOCUS = 1,
+    ACTIVATION_POLICY_FOCUS_SAME_OUTPUT = 2,
+    ACTIVATION_POLICY_URGENT = 3,
+}
+
+---@class pinnacle.window.v0alpha1.SetActivationPolicyRequest
+---@field policy pinnacle.window.v0alpha1.ActivationPolicy?
+
 ---@class pinnacle.window.v0alpha1.MoveGrabRequest
 ---@field button integer?
 
@@ -159,6 +264,17 @@ local pinnacle_output_v0alpha1_Transform = {
 ---@field floating boolean?
 ---@field fullscreen_or_maximized pinnacle.window.v0alpha1.FullscreenOrMaximized?
 ---@field tag_ids integer[]?
+---@field minimized boolean?
+---@field sticky boolean?
+---@field urgent boolean?
+
+---@class pinnacle.window.v0alpha1.ScreenshotRequest
+---@field window_id integer?
+
+---@class pinnacle.window.v0alpha1.ScreenshotResponse
+---@field width integer?
+---@field height integer?
+---@field pixels string?
 
 ---@enum pinnacle.window.v0alpha1.FullscreenOrMaximized
 local pinnacle_window_v0alpha1_FullscreenOrMaximized = {
@@ -188,6 +304,11 @@ local pinnacle_window_v0alpha1_FullscreenOrMaximized = {
 ---@field y integer?
 ---@field width integer?
 ---@field height integer?
+---@field decoration_mode pinnacle.window.v0alpha1.DecorationMode?
+---@field capture_hidden boolean?
+---@field sticky boolean?
+---@field opacity number?
+---@field can_swallow boolean?
 
 -- Tag
 
@@ -221,6 +342,7 @@ local pinnacle_window_v0alpha1_FullscreenOrMaximized = {
 ---@field name string?
 ---@field output_name string?
 ---@field window_ids integer[]?
+---@field urgent boolean?
 
 -- Input
 
@@ -264,6 +386,13 @@ local pinnacle_input_v0alpha1_SetMousebindRequest_MouseEdge = {
 ---@field rate integer?
 ---@field delay integer?
 
+---@class SetIdleTimeoutRequest
+---@field timeout_ms integer?
+
+---@class SetXcursorRequest
+---@field theme string?
+---@field size integer?
+
 ---@enum pinnacle.input.v0alpha1.SetLibinputSettingRequest.AccelProfile
 local pinnacle_input_v0alpha1_SetLibinputSettingRequest_AccelProfile = {
     ACCEL_PROFILE_UNSPECIFIED = 0,
@@ -369,6 +498,29 @@ local pinnacle_render_v0alpha1_Filter = {
 ---@class pinnacle.render.v0alpha1.SetDownscaleFilterRequest
 ---@field filter pinnacle.render.v0alpha1.Filter?
 
+---@class pinnacle.render.v0alpha1.Color
+---@field r number?
+---@field g number?
+---@field b number?
+---@field a number?
+
+---@class pinnacle.render.v0alpha1.SetDecorationColorsRequest
+---@field focused pinnacle.render.v0alpha1.Color?
+---@field unfocused pinnacle.render.v0alpha1.Color?
+
+---@class pinnacle.render.v0alpha1.SetBordersRequest
+---@field width integer?
+---@field focused pinnacle.render.v0alpha1.Color?
+---@field unfocused pinnacle.render.v0alpha1.Color?
+---@field urgent pinnacle.render.v0alpha1.Color?
+
+---@class pinnacle.render.v0alpha1.SetTabBarRequest
+---@field height integer?
+---@field focused pinnacle.render.v0alpha1.Color?
+---@field unfocused pinnacle.render.v0alpha1.Color?
+---@field inactive pinnacle.render.v0alpha1.Color?
+---@field urgent pinnacle.render.v0alpha1.Color?
+
 -- Signal
 
 ---@enum pinnacle.signal.v0alpha1.StreamControl
@@ -418,6 +570,20 @@ local pinnacle_signal_v0alpha1_StreamControl = {
 ---@class pinnacle.signal.v0alpha1.WindowPointerLeaveResponse
 ---@field window_id integer?
 
+---@class pinnacle.signal.v0alpha1.WindowUrgentRequest
+---@field control pinnacle.signal.v0alpha1.StreamControl?
+
+---@class pinnacle.signal.v0alpha1.WindowUrgentResponse
+---@field window_id integer?
+---@field urgent boolean?
+
+---@class pinnacle.signal.v0alpha1.WindowMinimizedRequest
+---@field control pinnacle.signal.v0alpha1.StreamControl?
+
+---@class pinnacle.signal.v0alpha1.WindowMinimizedResponse
+---@field window_id integer?
+---@field minimized boolean?
+
 ---@class pinnacle.signal.v0alpha1.TagActiveRequest
 ---@field control pinnacle.signal.v0alpha1.StreamControl?
 
@@ -425,9 +591,20 @@ local pinnacle_signal_v0alpha1_StreamControl = {
 ---@field tag_id integer?
 ---@field active boolean?
 
+---@class pinnacle.signal.v0alpha1.IdleStartRequest
+---@field control pinnacle.signal.v0alpha1.StreamControl?
+
+---@class pinnacle.signal.v0alpha1.IdleStartResponse
+
+---@class pinnacle.signal.v0alpha1.IdleEndRequest
+---@field control pinnacle.signal.v0alpha1.StreamControl?
+
+---@class pinnacle.signal.v0alpha1.IdleEndResponse
+
 defs.pinnacle = {
     v0alpha1 = {
         SetOrToggle = util.bijective_table(pinnacle_v0alpha1_SetOrToggle),
+        PrivilegedProtocol = util.bijective_table(pinnacle_v0alpha1_PrivilegedProtocol),
         PinnacleService = {
             ---@type GrpcRequestArgs
             Quit = {
@@ -450,6 +627,13 @@ defs.pinnacle = {
                 request = "pinnacle.v0alpha1.PingRequest",
                 response = "pinnacle.v0alpha1.PingResponse",
             },
+            ---@type GrpcRequestArgs
+            AllowSandboxedClient = {
+                service = "pinnacle.v0alpha1.PinnacleService",
+                method = "AllowSandboxedClient",
+                request = "pinnacle.v0alpha1.AllowSandboxedClientRequest",
+                response = "google.protobuf.Empty",
+            },
         },
     },
     output = {
@@ -506,6 +690,9 @@ defs.pinnacle = {
             FullscreenOrMaximized = util.bijective_table(
                 pinnacle_window_v0alpha1_FullscreenOrMaximized
             ),
+            DecorationMode = util.bijective_table(pinnacle_window_v0alpha1_DecorationMode),
+            ActivationPolicy = util.bijective_table(pinnacle_window_v0alpha1_ActivationPolicy),
+            Direction = util.bijective_table(pinnacle_window_v0alpha1_Direction),
             WindowService = {
                 ---@type GrpcRequestArgs
                 Close = {
@@ -536,6 +723,20 @@ defs.pinnacle = {
                     response = "google.protobuf.Empty",
                 },
                 ---@type GrpcRequestArgs
+                SetMinimized = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetMinimized",
+                    request = "pinnacle.window.v0alpha1.SetMinimizedRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetSticky = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetSticky",
+                    request = "pinnacle.window.v0alpha1.SetStickyRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
                 SetFloating = {
                     service = "pinnacle.window.v0alpha1.WindowService",
                     method = "SetFloating",
@@ -571,6 +772,104 @@ defs.pinnacle = {
                     response = "google.protobuf.Empty",
                 },
                 ---@type GrpcRequestArgs
+                Swap = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "Swap",
+                    request = "pinnacle.window.v0alpha1.SwapRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                MoveInStack = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "MoveInStack",
+                    request = "pinnacle.window.v0alpha1.MoveInStackRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                GroupWith = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "GroupWith",
+                    request = "pinnacle.window.v0alpha1.GroupWithRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                Ungroup = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "Ungroup",
+                    request = "pinnacle.window.v0alpha1.UngroupRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                CycleGroup = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "CycleGroup",
+                    request = "pinnacle.window.v0alpha1.CycleGroupRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                FocusDirection = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "FocusDirection",
+                    request = "pinnacle.window.v0alpha1.FocusDirectionRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                MoveDirection = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "MoveDirection",
+                    request = "pinnacle.window.v0alpha1.MoveDirectionRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SendToScratchpad = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SendToScratchpad",
+                    request = "pinnacle.window.v0alpha1.SendToScratchpadRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                ToggleScratchpad = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "ToggleScratchpad",
+                    request = "pinnacle.window.v0alpha1.ToggleScratchpadRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetDecorationMode = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetDecorationMode",
+                    request = "pinnacle.window.v0alpha1.SetDecorationModeRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetCaptureHidden = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetCaptureHidden",
+                    request = "pinnacle.window.v0alpha1.SetCaptureHiddenRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetOpacity = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetOpacity",
+                    request = "pinnacle.window.v0alpha1.SetOpacityRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetDefaultOpacity = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetDefaultOpacity",
+                    request = "pinnacle.window.v0alpha1.SetDefaultOpacityRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetActivationPolicy = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "SetActivationPolicy",
+                    request = "pinnacle.window.v0alpha1.SetActivationPolicyRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
                 MoveGrab = {
                     service = "pinnacle.window.v0alpha1.WindowService",
                     method = "MoveGrab",
@@ -599,6 +898,13 @@ defs.pinnacle = {
                     response = "pinnacle.window.v0alpha1.GetPropertiesResponse",
                 },
                 ---@type GrpcRequestArgs
+                Screenshot = {
+                    service = "pinnacle.window.v0alpha1.WindowService",
+                    method = "Screenshot",
+                    request = "pinnacle.window.v0alpha1.ScreenshotRequest",
+                    response = "pinnacle.window.v0alpha1.ScreenshotResponse",
+                },
+                ---@type GrpcRequestArgs
                 AddWindowRule = {
                     service = "pinnacle.window.v0alpha1.WindowService",
                     method = "AddWindowRule",
@@ -708,6 +1014,20 @@ defs.pinnacle = {
                     response = "google.protobuf.Empty",
                 },
                 ---@type GrpcRequestArgs
+                SetIdleTimeout = {
+                    service = "pinnacle.input.v0alpha1.InputService",
+                    method = "SetIdleTimeout",
+                    request = "pinnacle.input.v0alpha1.SetIdleTimeoutRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetXcursor = {
+                    service = "pinnacle.input.v0alpha1.InputService",
+                    method = "SetXcursor",
+                    request = "pinnacle.input.v0alpha1.SetXcursorRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
                 SetLibinputSetting = {
                     service = "pinnacle.input.v0alpha1.InputService",
                     method = "SetLibinputSetting",
@@ -768,6 +1088,27 @@ defs.pinnacle = {
                     request = "pinnacle.render.v0alpha1.SetDownscaleFilterRequest",
                     response = "google.protobuf.Empty",
                 },
+                ---@type GrpcRequestArgs
+                SetDecorationColors = {
+                    service = "pinnacle.render.v0alpha1.RenderService",
+                    method = "SetDecorationColors",
+                    request = "pinnacle.render.v0alpha1.SetDecorationColorsRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetBorders = {
+                    service = "pinnacle.render.v0alpha1.RenderService",
+                    method = "SetBorders",
+                    request = "pinnacle.render.v0alpha1.SetBordersRequest",
+                    response = "google.protobuf.Empty",
+                },
+                ---@type GrpcRequestArgs
+                SetTabBar = {
+                    service = "pinnacle.render.v0alpha1.RenderService",
+                    method = "SetTabBar",
+                    request = "pinnacle.render.v0alpha1.SetTabBarRequest",
+                    response = "google.protobuf.Empty",
+                },
             },
         },
     },
@@ -819,12 +1160,40 @@ defs.pinnacle = {
                     response = "pinnacle.signal.v0alpha1.WindowPointerLeaveResponse",
                 },
                 ---@type GrpcRequestArgs
+                WindowUrgent = {
+                    service = "pinnacle.signal.v0alpha1.SignalService",
+                    method = "WindowUrgent",
+                    request = "pinnacle.signal.v0alpha1.WindowUrgentRequest",
+                    response = "pinnacle.signal.v0alpha1.WindowUrgentResponse",
+                },
+                ---@type GrpcRequestArgs
+                WindowMinimized = {
+                    service = "pinnacle.signal.v0alpha1.SignalService",
+                    method = "WindowMinimized",
+                    request = "pinnacle.signal.v0alpha1.WindowMinimizedRequest",
+                    response = "pinnacle.signal.v0alpha1.WindowMinimizedResponse",
+                },
+                ---@type GrpcRequestArgs
                 TagActive = {
                     service = "pinnacle.signal.v0alpha1.SignalService",
                     method = "TagActive",
                     request = "pinnacle.signal.v0alpha1.TagActiveRequest",
                     response = "pinnacle.signal.v0alpha1.TagActiveResponse",
                 },
+                ---@type GrpcRequestArgs
+                IdleStart = {
+                    service = "pinnacle.signal.v0alpha1.SignalService",
+                    method = "IdleStart",
+                    request = "pinnacle.signal.v0alpha1.IdleStartRequest",
+                    response = "pinnacle.signal.v0alpha1.IdleStartResponse",
+                },
+                ---@type GrpcRequestArgs
+                IdleEnd = {
+                    service = "pinnacle.signal.v0alpha1.SignalService",
+                    method = "IdleEnd",
+                    request = "pinnacle.signal.v0alpha1.IdleEndRequest",
+                    response = "pinnacle.signal.v0alpha1.IdleEndResponse",
+                },
             },
         },
     },
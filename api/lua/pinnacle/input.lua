@@ -207,6 +207,107 @@ function input.set_repeat_rate(rate, delay)
     })
 end
 
+---Set how long the seat must go without input before it becomes idle.
+---
+---Once the timeout passes, the `idle_start` signal fires, and `idle_end` fires on the next input.
+---Pass in `nil` to disable the idle timeout.
+---
+---The seat will not become idle while a visible window inhibits idling,
+---like a video player in fullscreen.
+---
+---### Example
+---```lua
+---Input.set_idle_timeout(300000) -- Become idle after 5 minutes without input
+---```
+---
+---@param timeout_ms integer? The timeout in milliseconds
+function input.set_idle_timeout(timeout_ms)
+    client.unary_request(input_service.SetIdleTimeout, {
+        timeout_ms = timeout_ms,
+    })
+end
+
+---Set the xcursor theme.
+---
+---This changes the theme of the compositor's cursor and any cursors
+---clients request by name. Newly spawned clients will use this theme as well.
+---
+---### Example
+---```lua
+---Input.set_xcursor_theme("Adwaita")
+---```
+---
+---@param theme string
+function input.set_xcursor_theme(theme)
+    client.unary_request(input_service.SetXcursor, {
+        theme = theme,
+    })
+end
+
+---Set the xcursor size in logical pixels.
+---
+---The cursor is scaled up on outputs with a higher scale.
+---Newly spawned clients will use this size as well.
+---
+---### Example
+---```lua
+---Input.set_xcursor_size(32)
+---```
+---
+---@param size integer
+function input.set_xcursor_size(size)
+    client.unary_request(input_service.SetXcursor, {
+        size = size,
+    })
+end
+
+---@type table<string, SignalServiceMethod>
+local signal_name_to_SignalName = {
+    idle_start = "IdleStart",
+    idle_end = "IdleEnd",
+}
+
+---@class InputSignal Signals related to input events.
+---@field idle_start fun()? The seat became idle.
+---@field idle_end fun()? The seat is no longer idle.
+
+---Connect to an input signal.
+---
+---The compositor sends signals about various events. Use this function to run a callback when
+---some input signal occurs.
+---
+---This function returns a table of signal handles with each handle stored at the same key used
+---to connect to the signal. See `SignalHandles` for more information.
+---
+---# Example
+---```lua
+---Input.connect_signal({
+---    idle_start = function()
+---        Process.spawn("swaylock")
+---    end
+---})
+---```
+---
+---@param signals InputSignal The signal you want to connect to
+---
+---@return SignalHandles signal_handles Handles to every signal you connected to wrapped in a table, with keys being the same as the connected signal.
+---
+---@see SignalHandles.disconnect_all - To disconnect from these signals
+function input.connect_signal(signals)
+    ---@diagnostic disable-next-line: invisible
+    local handles = require("pinnacle.signal").handles.new({})
+
+    for signal, callback in pairs(signals) do
+        require("pinnacle.signal").add_callback(signal_name_to_SignalName[signal], callback)
+        local handle =
+            ---@diagnostic disable-next-line: invisible
+            require("pinnacle.signal").handle.new(signal_name_to_SignalName[signal], callback)
+        handles[signal] = handle
+    end
+
+    return handles
+end
+
 local accel_profile_values = {
     flat = 1,
     adaptive = 2,
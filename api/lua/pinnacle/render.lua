@@ -40,4 +40,102 @@ function render.set_downscale_filter(filter)
     )
 end
 
+---@param color number[]
+---@return pinnacle.render.v0alpha1.Color
+local function to_color(color)
+    return { r = color[1], g = color[2], b = color[3], a = color[4] }
+end
+
+---Set the colors of the border drawn around windows.
+---
+---This is deprecated; use `Render.set_borders`, which can also set the width and urgent color.
+---
+---Colors are RGBA tables with components from 0.0 to 1.0.
+---
+---### Example
+---```lua
+----- Blue border for the focused window, gray for the rest
+---Render.set_decoration_colors({ 0.4, 0.6, 0.9, 1.0 }, { 0.3, 0.3, 0.3, 1.0 })
+---```
+---
+---@param focused number[] The border color of the focused window
+---@param unfocused number[] The border color of unfocused windows
+---@deprecated
+function render.set_decoration_colors(focused, unfocused)
+    client.unary_request(
+        render_service.SetDecorationColors,
+        { focused = to_color(focused), unfocused = to_color(unfocused) }
+    )
+end
+
+---@class Borders
+---@field width integer? The width of borders in logical pixels, up to 100
+---@field focused number[]? The border color of the focused window
+---@field unfocused number[]? The border color of unfocused windows
+---@field urgent number[]? The border color of unfocused windows that need attention
+
+---Set the width and colors of the borders drawn around windows.
+---
+---Windows are shrunk to fit their border in the layout.
+---Server-side decorated windows always have a border at least 2 pixels wide.
+---Fields that aren't set are left unchanged.
+---
+---Colors are RGBA tables with components from 0.0 to 1.0.
+---
+---### Example
+---```lua
+---Render.set_borders({
+---    width = 3,
+---    focused = { 0.4, 0.6, 0.9, 1.0 },
+---    unfocused = { 0.3, 0.3, 0.3, 1.0 },
+---    urgent = { 0.9, 0.4, 0.3, 1.0 },
+---})
+---```
+---
+---@param borders Borders
+function render.set_borders(borders)
+    client.unary_request(render_service.SetBorders, {
+        width = borders.width,
+        focused = borders.focused and to_color(borders.focused),
+        unfocused = borders.unfocused and to_color(borders.unfocused),
+        urgent = borders.urgent and to_color(borders.urgent),
+    })
+end
+
+---@class TabBar
+---@field height integer? The height of the tab strip in logical pixels, up to 100
+---@field focused number[]? The tab color of a group's shown window when it's focused
+---@field unfocused number[]? The tab color of a group's shown window when it isn't focused
+---@field inactive number[]? The tab color of a group's hidden windows
+---@field urgent number[]? The tab color of a group's hidden windows that need attention
+
+---Set the height and colors of the tab strip drawn above window groups.
+---
+---Grouped windows are shrunk to fit the strip in the layout.
+---Fields that aren't set are left unchanged.
+---
+---Colors are RGBA tables with components from 0.0 to 1.0.
+---
+---### Example
+---```lua
+---Render.set_tab_bar({
+---    height = 12,
+---    focused = { 0.4, 0.6, 0.9, 1.0 },
+---    unfocused = { 0.3, 0.3, 0.3, 1.0 },
+---    inactive = { 0.18, 0.18, 0.18, 1.0 },
+---    urgent = { 0.9, 0.4, 0.3, 1.0 },
+---})
+---```
+---
+---@param tab_bar TabBar
+function render.set_tab_bar(tab_bar)
+    client.unary_request(render_service.SetTabBar, {
+        height = tab_bar.height,
+        focused = tab_bar.focused and to_color(tab_bar.focused),
+        unfocused = tab_bar.unfocused and to_color(tab_bar.unfocused),
+        inactive = tab_bar.inactive and to_color(tab_bar.inactive),
+        urgent = tab_bar.urgent and to_color(tab_bar.urgent),
+    })
+end
+
 return render
@@ -82,6 +82,28 @@ local signals = {
         ---@type fun(response: table)
         on_response = nil,
     },
+    WindowUrgent = {
+        ---@nodoc
+        ---@type H2Stream?
+        sender = nil,
+        ---@nodoc
+        ---@type (fun(window: WindowHandle, urgent: boolean))[]
+        callbacks = {},
+        ---@nodoc
+        ---@type fun(response: table)
+        on_response = nil,
+    },
+    WindowMinimized = {
+        ---@nodoc
+        ---@type H2Stream?
+        sender = nil,
+        ---@nodoc
+        ---@type (fun(window: WindowHandle, minimized: boolean))[]
+        callbacks = {},
+        ---@nodoc
+        ---@type fun(response: table)
+        on_response = nil,
+    },
     TagActive = {
         ---@nodoc
         ---@type H2Stream?
@@ -93,6 +115,28 @@ local signals = {
         ---@type fun(response: table)
         on_response = nil,
     },
+    IdleStart = {
+        ---@nodoc
+        ---@type H2Stream?
+        sender = nil,
+        ---@nodoc
+        ---@type (fun())[]
+        callbacks = {},
+        ---@nodoc
+        ---@type fun(response: table)
+        on_response = nil,
+    },
+    IdleEnd = {
+        ---@nodoc
+        ---@type H2Stream?
+        sender = nil,
+        ---@nodoc
+        ---@type (fun())[]
+        callbacks = {},
+        ---@nodoc
+        ---@type fun(response: table)
+        on_response = nil,
+    },
 }
 
 signals.OutputConnect.on_response = function(response)
@@ -145,6 +189,24 @@ signals.WindowPointerLeave.on_response = function(response)
     end
 end
 
+signals.WindowUrgent.on_response = function(response)
+    ---@diagnostic disable-next-line: invisible
+    local window_handle = require("pinnacle.window").handle.new(response.window_id)
+
+    for _, callback in ipairs(signals.WindowUrgent.callbacks) do
+        callback(window_handle, response.urgent)
+    end
+end
+
+signals.WindowMinimized.on_response = function(response)
+    ---@diagnostic disable-next-line: invisible
+    local window_handle = require("pinnacle.window").handle.new(response.window_id)
+
+    for _, callback in ipairs(signals.WindowMinimized.callbacks) do
+        callback(window_handle, response.minimized)
+    end
+end
+
 signals.TagActive.on_response = function(response)
     ---@diagnostic disable-next-line: invisible
     local tag_handle = require("pinnacle.tag").handle.new(response.tag_id)
@@ -154,6 +216,18 @@ signals.TagActive.on_response = function(response)
     end
 end
 
+signals.IdleStart.on_response = function(_)
+    for _, callback in ipairs(signals.IdleStart.callbacks) do
+        callback()
+    end
+end
+
+signals.IdleEnd.on_response = function(_)
+    for _, callback in ipairs(signals.IdleEnd.callbacks) do
+        callback()
+    end
+end
+
 -----------------------------------------------------------------------------
 
 ---@nodoc
@@ -292,6 +292,7 @@ end
 ---@field name string? The name of the tag
 ---@field output OutputHandle? The output the tag is on
 ---@field windows WindowHandle[] The windows that have this tag
+---@field urgent boolean? Whether or not any window on this tag needs attention
 
 ---Get all properties of this tag.
 ---
@@ -307,6 +308,7 @@ function TagHandle:props()
             and require("pinnacle.output").handle.new(response.output_name),
         ---@diagnostic disable-next-line: invisible
         windows = require("pinnacle.window").handle.new_from_table(response.window_ids or {}),
+        urgent = response.urgent,
     }
 end
 
@@ -346,6 +348,15 @@ function TagHandle:windows()
     return self:props().windows
 end
 
+---Get whether or not any window on this tag needs attention.
+---
+---Shorthand for `handle:props().urgent`.
+---
+---@return boolean?
+function TagHandle:urgent()
+    return self:props().urgent
+end
+
 ---@nodoc
 ---Create a new `TagHandle` from an id.
 ---@param tag_id integer
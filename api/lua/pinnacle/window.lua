@@ -142,6 +142,11 @@ end
 ---@field y integer?
 ---@field width integer?
 ---@field height integer?
+---@field decoration_mode DecorationMode?
+---@field capture_hidden boolean?
+---@field sticky boolean?
+---@field opacity number?
+---@field can_swallow boolean? Let windows started by this window's process swallow it
 
 ---@enum (key) FullscreenOrMaximized
 local _fullscreen_or_maximized = {
@@ -156,6 +161,12 @@ local _fullscreen_or_maximized_keys = {
     [3] = "maximized",
 }
 
+---@enum (key) DecorationMode
+local _decoration_mode = {
+    client_side = 1,
+    server_side = 2,
+}
+
 ---@param rule WindowRule
 local function process_window_rule(rule)
     if rule.output then
@@ -174,6 +185,11 @@ local function process_window_rule(rule)
     if rule.fullscreen_or_maximized then
         rule.fullscreen_or_maximized = _fullscreen_or_maximized[rule.fullscreen_or_maximized]
     end
+
+    if rule.decoration_mode then
+        ---@diagnostic disable-next-line: assign-type-mismatch
+        rule.decoration_mode = _decoration_mode[rule.decoration_mode]
+    end
 end
 
 ---@param cond WindowRuleCondition
@@ -299,14 +315,122 @@ function window.add_window_rule(rule)
     })
 end
 
+---@enum (key) ActivationPolicy
+local _activation_policy = {
+    focus = 1,
+    focus_same_output = 2,
+    urgent = 3,
+}
+
+---Set what happens when a window requests activation.
+---
+---Apps request activation when, for example, a launcher starts them or a notification
+---is clicked. Processes spawned with `Process.spawn` are given an activation token automatically.
+---
+---Windows that aren't focused are marked urgent, which fires the `urgent` window signal.
+---
+---### Example
+---```lua
+---Window.set_activation_policy("focus_same_output")
+---```
+---
+---`policy` can be one of:
+--- - `"focus"`: Always focus the window
+--- - `"focus_same_output"`: Focus the window only if it was launched from the focused output, otherwise mark it urgent
+--- - `"urgent"`: Mark the window urgent without focusing it
+---
+---@param policy ActivationPolicy
+function window.set_activation_policy(policy)
+    client.unary_request(window_service.SetActivationPolicy, {
+        policy = _activation_policy[policy],
+    })
+end
+
+---@enum (key) Direction
+local _direction = {
+    left = 1,
+    right = 2,
+    up = 3,
+    down = 4,
+}
+
+---Focus the closest window in the given direction from the focused window.
+---
+---Windows on other outputs are considered too, following the output layout.
+---If there is no window in that direction, the output in that direction is focused.
+---
+---### Example
+---```lua
+---Window.focus_direction("left")
+---```
+---
+---@param direction Direction
+function window.focus_direction(direction)
+    client.unary_request(window_service.FocusDirection, { direction = _direction[direction] })
+end
+
+---Move the focused window in the given direction.
+---
+---Tiled windows swap places with the closest tiled window in that direction.
+---If there is none, or the window isn't tiled, it is moved to the output in that direction.
+---
+---### Example
+---```lua
+---Window.move_direction("right")
+---```
+---
+---@param direction Direction
+function window.move_direction(direction)
+    client.unary_request(window_service.MoveDirection, { direction = _direction[direction] })
+end
+
+---Set the opacity of windows that don't have their own, from 0.0 to 1.0.
+---
+---The focused window uses `focused` and all other windows use `unfocused`.
+---
+---### Example
+---```lua
+--- -- Dim unfocused windows
+---Window.set_default_opacity(1.0, 0.85)
+---```
+---
+---@param focused number
+---@param unfocused number
+function window.set_default_opacity(focused, unfocused)
+    client.unary_request(
+        window_service.SetDefaultOpacity,
+        { focused = focused, unfocused = unfocused }
+    )
+end
+
+---Show or hide the windows in the scratchpad called `name`.
+---
+---If they are shown on the focused output, they will be hidden. Otherwise, they will
+---float centered on the focused output above whatever tags are active and be focused.
+---
+---### Example
+---```lua
+--- -- Summon the drop-down terminal
+---Window.toggle_scratchpad("terminal")
+---```
+---
+---@param name string
+function window.toggle_scratchpad(name)
+    client.unary_request(window_service.ToggleScratchpad, { name = name })
+end
+
 local signal_name_to_SignalName = {
     pointer_enter = "WindowPointerEnter",
     pointer_leave = "WindowPointerLeave",
+    urgent = "WindowUrgent",
+    minimized = "WindowMinimized",
 }
 
 ---@class WindowSignal Signals related to compositor events.
 ---@field pointer_enter fun(window: WindowHandle)? The pointer entered a window.
 ---@field pointer_leave fun(window: WindowHandle)? The pointer left a window.
+---@field urgent fun(window: WindowHandle, urgent: boolean)? A window's urgency changed.
+---@field minimized fun(window: WindowHandle, minimized: boolean)? A window was minimized or restored.
 
 ---Connect to a window signal.
 ---
@@ -458,6 +582,80 @@ function WindowHandle:toggle_maximized()
     )
 end
 
+---Minimize or restore this window.
+---
+---Minimized windows are hidden and aren't tiled or focused until they are restored.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:set_minimized(true)
+---end
+---```
+---
+---@param minimized boolean
+function WindowHandle:set_minimized(minimized)
+    client.unary_request(
+        window_service.SetMinimized,
+        { window_id = self.id, set_or_toggle = set_or_toggle[minimized] }
+    )
+end
+
+---Toggle this window to and from minimized.
+---
+---### Example
+---```lua
+--- -- Restore all minimized windows
+---for _, win in ipairs(Window.get_all()) do
+---    if win:minimized() then
+---        win:toggle_minimized()
+---    end
+---end
+---```
+function WindowHandle:toggle_minimized()
+    client.unary_request(
+        window_service.SetMinimized,
+        { window_id = self.id, set_or_toggle = set_or_toggle.TOGGLE }
+    )
+end
+
+---Set this window to sticky or not.
+---
+---Sticky windows are shown on every active tag of their output.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:set_sticky(true) -- Keep the window visible when switching tags
+---end
+---```
+---
+---@param sticky boolean
+function WindowHandle:set_sticky(sticky)
+    client.unary_request(
+        window_service.SetSticky,
+        { window_id = self.id, set_or_toggle = set_or_toggle[sticky] }
+    )
+end
+
+---Toggle this window to and from sticky.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:toggle_sticky()
+---end
+---```
+function WindowHandle:toggle_sticky()
+    client.unary_request(
+        window_service.SetSticky,
+        { window_id = self.id, set_or_toggle = set_or_toggle.TOGGLE }
+    )
+end
+
 ---Set this window to floating or not.
 ---
 ---### Example
@@ -610,6 +808,202 @@ function WindowHandle:raise()
     client.unary_request(window_service.Raise, { window_id = self.id })
 end
 
+---Swap the positions of this window and `other`.
+---
+---For tiled windows, this swaps their places in the layout.
+---
+---### Example
+---```lua
+---local windows = Window.get_all()
+---windows[1]:swap(windows[2])
+---```
+---
+---@param other WindowHandle
+function WindowHandle:swap(other)
+    client.unary_request(window_service.Swap, { window_id = self.id, target_id = other.id })
+end
+
+---Move this window `delta` places through the tiled windows on its output.
+---
+---Positive values move it towards the end of the layout, negative values towards the start.
+---The window stops at either end.
+---
+---### Example
+---```lua
+----- Move the focused window one place back
+---local focused = Window.get_focused()
+---if focused then
+---    focused:move_in_stack(-1)
+---end
+---```
+---
+---@param delta integer
+function WindowHandle:move_in_stack(delta)
+    client.unary_request(window_service.MoveInStack, { window_id = self.id, delta = delta })
+end
+
+---Add this window to `other`'s tabbed group, creating one if `other` isn't in a group.
+---
+---Windows in a group share one tiled slot, and only one of them is shown at a time
+---with a tab strip above it. This window takes on `other`'s tags and becomes the
+---shown window of the group.
+---
+---### Example
+---```lua
+---local windows = Window.get_all()
+---windows[2]:group_with(windows[1])
+---```
+---
+---@param other WindowHandle
+function WindowHandle:group_with(other)
+    client.unary_request(window_service.GroupWith, { window_id = self.id, target_id = other.id })
+end
+
+---Take this window out of its group and tile it on its own.
+---
+---If only one window is left in the group, the group is dissolved.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:ungroup()
+---end
+---```
+function WindowHandle:ungroup()
+    client.unary_request(window_service.Ungroup, { window_id = self.id })
+end
+
+---Show the window `delta` tabs away from the shown one in this window's group and focus it.
+---
+---This wraps around at either end. This does nothing if this window isn't in a group.
+---
+---### Example
+---```lua
+------ Show the next tab
+---local focused = Window.get_focused()
+---if focused then
+---    focused:cycle_group(1)
+---end
+---```
+---
+---@param delta integer
+function WindowHandle:cycle_group(delta)
+    client.unary_request(window_service.CycleGroup, { window_id = self.id, delta = delta })
+end
+
+---Send this window to the scratchpad called `name`.
+---
+---The window loses its tags and is hidden until the scratchpad is shown with
+---`Window.toggle_scratchpad`. Moving it to a tag takes it out of the scratchpad.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:send_to_scratchpad("terminal")
+---end
+---```
+---
+---@param name string
+function WindowHandle:send_to_scratchpad(name)
+    client.unary_request(window_service.SendToScratchpad, { window_id = self.id, name = name })
+end
+
+---Set whether this window draws its own decorations or Pinnacle draws them.
+---
+---This only affects Wayland windows that support server-side decorations.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:set_decoration_mode("server_side") -- Have Pinnacle draw a border around the window
+---end
+---```
+---
+---@param mode DecorationMode
+function WindowHandle:set_decoration_mode(mode)
+    client.unary_request(
+        window_service.SetDecorationMode,
+        { window_id = self.id, decoration_mode = _decoration_mode[mode] }
+    )
+end
+
+---Set whether or not this window is hidden from screen capture.
+---
+---Capture-hidden windows are replaced with a solid placeholder in screencopy frames
+---but are still shown normally on your monitors.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:set_capture_hidden(true) -- Keep the window out of screen shares
+---end
+---```
+---
+---@param hidden boolean
+function WindowHandle:set_capture_hidden(hidden)
+    client.unary_request(window_service.SetCaptureHidden, { window_id = self.id, hidden = hidden })
+end
+
+---Set the opacity of this window, from 0.0 to 1.0.
+---
+---This overrides the default set with `Window.set_default_opacity`.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    focused:set_opacity(0.8) -- Make the window translucent
+---end
+---```
+---
+---@param opacity number
+function WindowHandle:set_opacity(opacity)
+    client.unary_request(window_service.SetOpacity, { window_id = self.id, opacity = opacity })
+end
+
+---@class Screenshot
+---@field width integer The width of the screenshot in pixels
+---@field height integer The height of the screenshot in pixels
+---@field pixels string The screenshot's pixels as 8-bit RGBA, row by row
+
+---Take a screenshot of this window.
+---
+---The window and its popups are rendered at the scale of the output it's on.
+---This works even if the window isn't currently visible, for example
+---when it's on an inactive tag.
+---
+---Returns nil if the window doesn't exist or couldn't be captured.
+---
+---### Example
+---```lua
+---local focused = Window.get_focused()
+---if focused then
+---    local screenshot = focused:screenshot()
+---    if screenshot then
+---        print(screenshot.width, screenshot.height)
+---    end
+---end
+---```
+---
+---@return Screenshot | nil
+function WindowHandle:screenshot()
+    local response = client.unary_request(window_service.Screenshot, { window_id = self.id })
+
+    if not (response.width and response.height and response.pixels) then
+        return nil
+    end
+
+    return {
+        width = response.width,
+        height = response.height,
+        pixels = response.pixels,
+    }
+end
+
 ---@class WindowProperties
 ---@field geometry { x: integer?, y: integer?, width: integer?, height: integer? }? The location and size of the window
 ---@field class string? The window's class
@@ -618,6 +1012,9 @@ end
 ---@field floating boolean? Whether or not the window is floating
 ---@field fullscreen_or_maximized FullscreenOrMaximized? Whether the window is fullscreen, maximized, or neither
 ---@field tags TagHandle[]? The tags the window has
+---@field minimized boolean? Whether or not the window is minimized
+---@field sticky boolean? Whether or not the window is sticky
+---@field urgent boolean? Whether or not the window needs attention
 
 ---Get all the properties of this window.
 ---
@@ -690,6 +1087,35 @@ function WindowHandle:fullscreen_or_maximized()
     return self:props().fullscreen_or_maximized
 end
 
+---Get whether or not this window is minimized.
+---
+---Shorthand for `handle:props().minimized`.
+---
+---@return boolean?
+function WindowHandle:minimized()
+    return self:props().minimized
+end
+
+---Get whether or not this window is sticky.
+---
+---Shorthand for `handle:props().sticky`.
+---
+---@return boolean?
+function WindowHandle:sticky()
+    return self:props().sticky
+end
+
+---Get whether or not this window needs attention.
+---
+---Windows stop needing attention when they are focused.
+---
+---Shorthand for `handle:props().urgent`.
+---
+---@return boolean?
+function WindowHandle:urgent()
+    return self:props().urgent
+end
+
 ---Get all tags on this window.
 ---
 ---Shorthand for `handle:props().tags`.
@@ -8,6 +8,8 @@
 //! methods for setting key- and mousebinds, changing xkeyboard settings, and more.
 //! View the struct's documentation for more information.
 
+use std::{sync::OnceLock, time::Duration};
+
 use futures::{future::BoxFuture, FutureExt, StreamExt};
 use num_enum::TryFromPrimitive;
 use pinnacle_api_defs::pinnacle::input::{
@@ -15,15 +17,19 @@ use pinnacle_api_defs::pinnacle::input::{
     v0alpha1::{
         input_service_client::InputServiceClient,
         set_libinput_setting_request::{CalibrationMatrix, Setting},
-        SetKeybindRequest, SetLibinputSettingRequest, SetMousebindRequest, SetRepeatRateRequest,
-        SetXkbConfigRequest,
+        SetIdleTimeoutRequest, SetKeybindRequest, SetLibinputSettingRequest, SetMousebindRequest,
+        SetRepeatRateRequest, SetXcursorRequest, SetXkbConfigRequest,
     },
 };
 use tokio::sync::mpsc::UnboundedSender;
 use tonic::transport::Channel;
 use xkbcommon::xkb::Keysym;
 
-use crate::block_on_tokio;
+use crate::{
+    block_on_tokio,
+    signal::{InputSignal, SignalHandle},
+    ApiModules,
+};
 
 use self::libinput::LibinputSetting;
 
@@ -97,6 +103,7 @@ pub struct XkbConfig {
 pub struct Input {
     channel: Channel,
     fut_sender: UnboundedSender<BoxFuture<'static, ()>>,
+    api: OnceLock<ApiModules>,
 }
 
 impl Input {
@@ -107,9 +114,14 @@ impl Input {
         Self {
             channel,
             fut_sender,
+            api: OnceLock::new(),
         }
     }
 
+    pub(crate) fn finish_init(&self, api: ApiModules) {
+        self.api.set(api).unwrap();
+    }
+
     fn create_input_client(&self) -> InputServiceClient<Channel> {
         InputServiceClient::new(self.channel.clone())
     }
@@ -296,6 +308,100 @@ impl Input {
         .unwrap();
     }
 
+    /// Set how long the seat must go without input before it becomes idle.
+    ///
+    /// Once the timeout passes, [`InputSignal::IdleStart`] fires, and
+    /// [`InputSignal::IdleEnd`] fires on the next input.
+    /// Pass in `None` to disable the idle timeout.
+    ///
+    /// The seat will not become idle while a visible window inhibits idling,
+    /// like a video player in fullscreen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// // Become idle after 5 minutes without input
+    /// input.set_idle_timeout(Duration::from_secs(300));
+    /// ```
+    pub fn set_idle_timeout(&self, timeout: impl Into<Option<Duration>>) {
+        let mut client = self.create_input_client();
+
+        block_on_tokio(
+            client.set_idle_timeout(SetIdleTimeoutRequest {
+                timeout_ms: timeout
+                    .into()
+                    .map(|timeout| timeout.as_millis().try_into().unwrap_or(u32::MAX)),
+            }),
+        )
+        .unwrap();
+    }
+
+    /// Set the xcursor theme.
+    ///
+    /// This changes the theme of the compositor's cursor and any cursors
+    /// clients request by name. Newly spawned clients will use this theme as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// input.set_xcursor_theme("Adwaita");
+    /// ```
+    pub fn set_xcursor_theme(&self, theme: impl ToString) {
+        let mut client = self.create_input_client();
+
+        block_on_tokio(client.set_xcursor(SetXcursorRequest {
+            theme: Some(theme.to_string()),
+            size: None,
+        }))
+        .unwrap();
+    }
+
+    /// Set the xcursor size in logical pixels.
+    ///
+    /// The cursor is scaled up on outputs with a higher scale.
+    /// Newly spawned clients will use this size as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// input.set_xcursor_size(32);
+    /// ```
+    pub fn set_xcursor_size(&self, size: u32) {
+        let mut client = self.create_input_client();
+
+        block_on_tokio(client.set_xcursor(SetXcursorRequest {
+            theme: None,
+            size: Some(size),
+        }))
+        .unwrap();
+    }
+
+    /// Connect to an input signal.
+    ///
+    /// The compositor will fire off signals that your config can listen for and act upon.
+    /// You can pass in an [`InputSignal`] along with a callback and it will get run
+    /// with the necessary arguments every time a signal of that type is received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::signal::InputSignal;
+    ///
+    /// input.connect_signal(InputSignal::IdleStart(Box::new(|| {
+    ///     process.spawn(["swaylock"]);
+    /// })));
+    /// ```
+    pub fn connect_signal(&self, signal: InputSignal) -> SignalHandle {
+        let mut signal_state = block_on_tokio(self.api.get().unwrap().signal.write());
+
+        match signal {
+            InputSignal::IdleStart(f) => signal_state.idle_start.add_callback(f),
+            InputSignal::IdleEnd(f) => signal_state.idle_end.add_callback(f),
+        }
+    }
+
     /// Set a libinput setting.
     ///
     /// From [freedesktop.org](https://www.freedesktop.org/wiki/Software/libinput/):
@@ -193,6 +193,7 @@ pub async fn connect(
         signal: signal.clone(),
     };
 
+    input.finish_init(modules.clone());
     window.finish_init(modules.clone());
     output.finish_init(modules.clone());
     tag.finish_init(modules.clone());
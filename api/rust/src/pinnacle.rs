@@ -4,13 +4,15 @@
 
 //! Compositor management.
 //!
-//! This module provides [`Pinnacle`], which allows you to quit the compositor.
+//! This module provides [`Pinnacle`], which allows you to quit the compositor
+//! and control what sandboxed clients can do.
 
 use std::time::Duration;
 
+use num_enum::TryFromPrimitive;
 use pinnacle_api_defs::pinnacle::v0alpha1::{
-    pinnacle_service_client::PinnacleServiceClient, PingRequest, QuitRequest, ReloadConfigRequest,
-    ShutdownWatchRequest, ShutdownWatchResponse,
+    pinnacle_service_client::PinnacleServiceClient, AllowSandboxedClientRequest, PingRequest,
+    QuitRequest, ReloadConfigRequest, ShutdownWatchRequest, ShutdownWatchResponse,
 };
 use rand::RngCore;
 use tonic::{transport::Channel, Request, Streaming};
@@ -51,6 +53,47 @@ impl Pinnacle {
         let _ = block_on_tokio(client.reload_config(ReloadConfigRequest {}));
     }
 
+    /// Allow sandboxed clients to use privileged protocols.
+    ///
+    /// Clients that connect through a security context, like Flatpak apps, can't use any
+    /// [`PrivilegedProtocol`] by default. This allows clients from `sandbox_engine` with
+    /// `app_id` to use `protocols`. Passing `None` for either matches all engines or apps.
+    ///
+    /// This only affects clients that connect afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::pinnacle::PrivilegedProtocol;
+    ///
+    /// // Allow OBS to capture the screen
+    /// pinnacle.allow_sandboxed_client(
+    ///     Some("flatpak"),
+    ///     Some("com.obsproject.Studio"),
+    ///     [PrivilegedProtocol::Screencopy, PrivilegedProtocol::ToplevelExport],
+    /// );
+    /// ```
+    pub fn allow_sandboxed_client(
+        &self,
+        sandbox_engine: Option<&str>,
+        app_id: Option<&str>,
+        protocols: impl IntoIterator<Item = PrivilegedProtocol>,
+    ) {
+        let mut client = self.client.clone();
+
+        block_on_tokio(
+            client.allow_sandboxed_client(AllowSandboxedClientRequest {
+                sandbox_engine: sandbox_engine.map(String::from),
+                app_id: app_id.map(String::from),
+                protocols: protocols
+                    .into_iter()
+                    .map(|protocol| protocol as i32)
+                    .collect(),
+            }),
+        )
+        .unwrap();
+    }
+
     pub(crate) async fn shutdown_watch(&self) -> Streaming<ShutdownWatchResponse> {
         let mut client = self.client.clone();
         client
@@ -81,3 +124,35 @@ impl Pinnacle {
             .ok_or("timed out".to_string())
     }
 }
+
+/// A protocol that sandboxed clients can't use unless allowed.
+///
+/// See [`Pinnacle::allow_sandboxed_client`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, TryFromPrimitive)]
+pub enum PrivilegedProtocol {
+    /// `wlr-screencopy-unstable-v1`
+    Screencopy = 1,
+    /// `hyprland-toplevel-export-v1`
+    ToplevelExport,
+    /// `wlr-gamma-control-unstable-v1`
+    GammaControl,
+    /// `wlr-data-control-unstable-v1`
+    DataControl,
+    /// `wlr-layer-shell-unstable-v1`
+    LayerShell,
+    /// `virtual-keyboard-unstable-v1`
+    VirtualKeyboard,
+    /// `wlr-virtual-pointer-unstable-v1`
+    VirtualPointer,
+    /// `input-method-unstable-v2`
+    InputMethod,
+    /// `ext-session-lock-v1`
+    SessionLock,
+    /// `wlr-foreign-toplevel-management-unstable-v1`
+    ForeignToplevel,
+    /// `ext-workspace-v1`
+    ExtWorkspace,
+    /// `wlr-output-management-unstable-v1`
+    OutputManagement,
+}
@@ -1,7 +1,9 @@
 //! Rendering management.
 
 use pinnacle_api_defs::pinnacle::render::v0alpha1::{
-    render_service_client::RenderServiceClient, SetDownscaleFilterRequest, SetUpscaleFilterRequest,
+    render_service_client::RenderServiceClient, Color, SetBordersRequest,
+    SetDecorationColorsRequest, SetDownscaleFilterRequest, SetTabBarRequest,
+    SetUpscaleFilterRequest,
 };
 use tonic::transport::Channel;
 
@@ -65,4 +67,109 @@ impl Render {
         }))
         .unwrap();
     }
+
+    /// Set the colors of the border drawn around windows.
+    ///
+    /// Colors are RGBA with components from 0.0 to 1.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Blue border for the focused window, gray for the rest
+    /// render.set_decoration_colors([0.4, 0.6, 0.9, 1.0], [0.3, 0.3, 0.3, 1.0]);
+    /// ```
+    #[deprecated = "use `set_borders`, which can also set the width and urgent color"]
+    pub fn set_decoration_colors(&self, focused: [f32; 4], unfocused: [f32; 4]) {
+        let mut client = self.client.clone();
+
+        block_on_tokio(client.set_decoration_colors(SetDecorationColorsRequest {
+            focused: Some(to_color(focused)),
+            unfocused: Some(to_color(unfocused)),
+        }))
+        .unwrap();
+    }
+
+    /// Set the width and colors of the borders drawn around windows.
+    ///
+    /// `width` is in logical pixels and at most 100, and windows are shrunk to fit their border in the layout.
+    /// The `urgent` color is used for unfocused windows that need attention.
+    /// Server-side decorated windows always have a border at least 2 pixels wide.
+    ///
+    /// Colors are RGBA with components from 0.0 to 1.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// render.set_borders(
+    ///     3,
+    ///     [0.4, 0.6, 0.9, 1.0],
+    ///     [0.3, 0.3, 0.3, 1.0],
+    ///     [0.9, 0.4, 0.3, 1.0],
+    /// );
+    /// ```
+    pub fn set_borders(
+        &self,
+        width: u32,
+        focused: [f32; 4],
+        unfocused: [f32; 4],
+        urgent: [f32; 4],
+    ) {
+        let mut client = self.client.clone();
+
+        block_on_tokio(client.set_borders(SetBordersRequest {
+            width: Some(width),
+            focused: Some(to_color(focused)),
+            unfocused: Some(to_color(unfocused)),
+            urgent: Some(to_color(urgent)),
+        }))
+        .unwrap();
+    }
+
+    /// Set the height and colors of the tab strip drawn above window groups.
+    ///
+    /// `height` is in logical pixels and at most 100, and grouped windows are shrunk to fit
+    /// the strip in the layout. The shown window's tab is `focused` or `unfocused`,
+    /// and the tabs of hidden windows are `inactive`, or `urgent` if they need attention.
+    ///
+    /// Colors are RGBA with components from 0.0 to 1.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// render.set_tab_bar(
+    ///     12,
+    ///     [0.4, 0.6, 0.9, 1.0],
+    ///     [0.3, 0.3, 0.3, 1.0],
+    ///     [0.18, 0.18, 0.18, 1.0],
+    ///     [0.9, 0.4, 0.3, 1.0],
+    /// );
+    /// ```
+    pub fn set_tab_bar(
+        &self,
+        height: u32,
+        focused: [f32; 4],
+        unfocused: [f32; 4],
+        inactive: [f32; 4],
+        urgent: [f32; 4],
+    ) {
+        let mut client = self.client.clone();
+
+        block_on_tokio(client.set_tab_bar(SetTabBarRequest {
+            height: Some(height),
+            focused: Some(to_color(focused)),
+            unfocused: Some(to_color(unfocused)),
+            inactive: Some(to_color(inactive)),
+            urgent: Some(to_color(urgent)),
+        }))
+        .unwrap();
+    }
+}
+
+fn to_color([r, g, b, a]: [f32; 4]) -> Color {
+    Color {
+        r: Some(r),
+        g: Some(g),
+        b: Some(b),
+        a: Some(a),
+    }
 }
@@ -230,6 +230,40 @@ signals! {
                 }
             },
         }
+        /// A window was marked urgent or stopped being urgent.
+        ///
+        /// Callbacks receive the window and whether it now needs attention.
+        WindowUrgent = {
+            enum_name = Urgent,
+            callback_type = Box<dyn FnMut(&WindowHandle, bool) + Send + 'static>,
+            client_request = window_urgent,
+            on_response = |response, callbacks, api| {
+                if let Some(window_id) = response.window_id {
+                    let handle = api.window.new_handle(window_id);
+
+                    for callback in callbacks {
+                        callback(&handle, response.urgent());
+                    }
+                }
+            },
+        }
+        /// A window was minimized or restored.
+        ///
+        /// Callbacks receive the window and whether it is now minimized.
+        WindowMinimized = {
+            enum_name = Minimized,
+            callback_type = Box<dyn FnMut(&WindowHandle, bool) + Send + 'static>,
+            client_request = window_minimized,
+            on_response = |response, callbacks, api| {
+                if let Some(window_id) = response.window_id {
+                    let handle = api.window.new_handle(window_id);
+
+                    for callback in callbacks {
+                        callback(&handle, response.minimized());
+                    }
+                }
+            },
+        }
     }
     /// Signals relating to tag events.
     TagSignal => {
@@ -249,6 +283,35 @@ signals! {
             },
         }
     }
+    /// Signals relating to input events.
+    InputSignal => {
+        /// The seat became idle.
+        ///
+        /// This fires once the idle timeout set with
+        /// [`Input::set_idle_timeout`][crate::input::Input::set_idle_timeout] passes
+        /// without any input.
+        IdleStart = {
+            enum_name = IdleStart,
+            callback_type = Box<dyn FnMut() + Send + 'static>,
+            client_request = idle_start,
+            on_response = |_response, callbacks, _api| {
+                for callback in callbacks {
+                    callback();
+                }
+            },
+        }
+        /// The seat is no longer idle.
+        IdleEnd = {
+            enum_name = IdleEnd,
+            callback_type = Box<dyn FnMut() + Send + 'static>,
+            client_request = idle_end,
+            on_response = |_response, callbacks, _api| {
+                for callback in callbacks {
+                    callback();
+                }
+            },
+        }
+    }
 }
 
 pub(crate) type SingleOutputFn = Box<dyn FnMut(&OutputHandle) + Send + 'static>;
@@ -262,8 +325,13 @@ pub(crate) struct SignalState {
 
     pub(crate) window_pointer_enter: SignalData<WindowPointerEnter>,
     pub(crate) window_pointer_leave: SignalData<WindowPointerLeave>,
+    pub(crate) window_urgent: SignalData<WindowUrgent>,
+    pub(crate) window_minimized: SignalData<WindowMinimized>,
 
     pub(crate) tag_active: SignalData<TagActive>,
+
+    pub(crate) idle_start: SignalData<IdleStart>,
+    pub(crate) idle_end: SignalData<IdleEnd>,
 }
 
 impl std::fmt::Debug for SignalState {
@@ -285,7 +353,11 @@ impl SignalState {
             output_move: SignalData::new(client.clone(), fut_sender.clone()),
             window_pointer_enter: SignalData::new(client.clone(), fut_sender.clone()),
             window_pointer_leave: SignalData::new(client.clone(), fut_sender.clone()),
+            window_urgent: SignalData::new(client.clone(), fut_sender.clone()),
+            window_minimized: SignalData::new(client.clone(), fut_sender.clone()),
             tag_active: SignalData::new(client.clone(), fut_sender.clone()),
+            idle_start: SignalData::new(client.clone(), fut_sender.clone()),
+            idle_end: SignalData::new(client.clone(), fut_sender.clone()),
         }
     }
 
@@ -296,7 +368,11 @@ impl SignalState {
         self.output_move.api.set(api.clone()).unwrap();
         self.window_pointer_enter.api.set(api.clone()).unwrap();
         self.window_pointer_leave.api.set(api.clone()).unwrap();
+        self.window_urgent.api.set(api.clone()).unwrap();
+        self.window_minimized.api.set(api.clone()).unwrap();
         self.tag_active.api.set(api.clone()).unwrap();
+        self.idle_start.api.set(api.clone()).unwrap();
+        self.idle_end.api.set(api.clone()).unwrap();
     }
 
     pub(crate) fn shutdown(&mut self) {
@@ -306,7 +382,11 @@ impl SignalState {
         self.output_move.reset();
         self.window_pointer_enter.reset();
         self.window_pointer_leave.reset();
+        self.window_urgent.reset();
+        self.window_minimized.reset();
         self.tag_active.reset();
+        self.idle_start.reset();
+        self.idle_end.reset();
     }
 }
 
@@ -416,6 +416,7 @@ impl TagHandle {
                 .into_iter()
                 .map(|id| window.new_handle(id))
                 .collect(),
+            urgent: response.urgent,
         }
     }
 
@@ -466,6 +467,18 @@ impl TagHandle {
     pub async fn windows_async(&self) -> Vec<WindowHandle> {
         self.props_async().await.windows
     }
+
+    /// Get whether or not any window on this tag needs attention.
+    ///
+    /// Shorthand for `self.props().urgent`.
+    pub fn urgent(&self) -> Option<bool> {
+        self.props().urgent
+    }
+
+    /// The async version of [`TagHandle::urgent`].
+    pub async fn urgent_async(&self) -> Option<bool> {
+        self.props_async().await.urgent
+    }
 }
 
 /// Properties of a tag.
@@ -479,4 +492,6 @@ pub struct TagProperties {
     pub output: Option<OutputHandle>,
     /// The windows that have this tag
     pub windows: Vec<WindowHandle>,
+    /// Whether or not any window on this tag needs attention
+    pub urgent: Option<bool>,
 }
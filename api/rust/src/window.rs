@@ -22,9 +22,13 @@ use pinnacle_api_defs::pinnacle::{
         self,
         v0alpha1::{
             window_service_client::WindowServiceClient, AddWindowRuleRequest, CloseRequest,
-            GetRequest, MoveGrabRequest, MoveToTagRequest, RaiseRequest, ResizeGrabRequest,
-            SetFloatingRequest, SetFocusedRequest, SetFullscreenRequest, SetMaximizedRequest,
-            SetTagRequest,
+            CycleGroupRequest, FocusDirectionRequest, GetRequest, GroupWithRequest,
+            MoveDirectionRequest, MoveGrabRequest, MoveInStackRequest, MoveToTagRequest,
+            RaiseRequest, ResizeGrabRequest, ScreenshotRequest, SendToScratchpadRequest,
+            SetActivationPolicyRequest, SetCaptureHiddenRequest, SetDecorationModeRequest,
+            SetDefaultOpacityRequest, SetFloatingRequest, SetFocusedRequest, SetFullscreenRequest,
+            SetMaximizedRequest, SetMinimizedRequest, SetOpacityRequest, SetStickyRequest,
+            SetTagRequest, SwapRequest, ToggleScratchpadRequest, UngroupRequest,
         },
     },
 };
@@ -183,6 +187,112 @@ impl Window {
         .unwrap();
     }
 
+    /// Set what happens when a window requests activation.
+    ///
+    /// Apps request activation when, for example, a launcher starts them or a
+    /// notification is clicked. Processes spawned with [`Process`][crate::process::Process]
+    /// are given an activation token automatically.
+    ///
+    /// Windows that aren't focused are marked urgent, which fires [`WindowSignal::Urgent`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::ActivationPolicy;
+    ///
+    /// window.set_activation_policy(ActivationPolicy::FocusSameOutput);
+    /// ```
+    pub fn set_activation_policy(&self, policy: ActivationPolicy) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.set_activation_policy(SetActivationPolicyRequest {
+            policy: Some(policy as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Focus the closest window in the given direction from the focused window.
+    ///
+    /// Windows on other outputs are considered too, following the output layout.
+    /// If there is no window in that direction, the output in that direction is focused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::Direction;
+    ///
+    /// window.focus_direction(Direction::Left);
+    /// ```
+    pub fn focus_direction(&self, direction: Direction) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.focus_direction(FocusDirectionRequest {
+            direction: Some(direction as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Move the focused window in the given direction.
+    ///
+    /// Tiled windows swap places with the closest tiled window in that direction.
+    /// If there is none, or the window isn't tiled, it is moved to the output in that direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::Direction;
+    ///
+    /// window.move_direction(Direction::Right);
+    /// ```
+    pub fn move_direction(&self, direction: Direction) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.move_direction(MoveDirectionRequest {
+            direction: Some(direction as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Set the opacity of windows that don't have their own, from 0.0 to 1.0.
+    ///
+    /// The focused window uses `focused` and all other windows use `unfocused`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Dim unfocused windows
+    /// window.set_default_opacity(1.0, 0.85);
+    /// ```
+    pub fn set_default_opacity(&self, focused: f32, unfocused: f32) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.set_default_opacity(SetDefaultOpacityRequest {
+            focused: Some(focused),
+            unfocused: Some(unfocused),
+        }))
+        .unwrap();
+    }
+
+    /// Show or hide the windows in the scratchpad called `name`.
+    ///
+    /// If they are shown on the focused output, they will be hidden. Otherwise, they will
+    /// float centered on the focused output above whatever tags are active and be focused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Summon the drop-down terminal
+    /// window.toggle_scratchpad("terminal");
+    /// ```
+    pub fn toggle_scratchpad(&self, name: impl Into<String>) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.toggle_scratchpad(ToggleScratchpadRequest {
+            name: Some(name.into()),
+        }))
+        .unwrap();
+    }
+
     /// Connect to a window signal.
     ///
     /// The compositor will fire off signals that your config can listen for and act upon.
@@ -194,6 +304,8 @@ impl Window {
         match signal {
             WindowSignal::PointerEnter(f) => signal_state.window_pointer_enter.add_callback(f),
             WindowSignal::PointerLeave(f) => signal_state.window_pointer_leave.add_callback(f),
+            WindowSignal::Urgent(f) => signal_state.window_urgent.add_callback(f),
+            WindowSignal::Minimized(f) => signal_state.window_minimized.add_callback(f),
         }
     }
 }
@@ -234,6 +346,42 @@ pub enum FullscreenOrMaximized {
     Maximized,
 }
 
+/// Whether a window draws its own decorations or Pinnacle draws them.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, TryFromPrimitive)]
+pub enum DecorationMode {
+    /// The window draws its own decorations, like titlebars
+    ClientSide = 1,
+    /// Pinnacle draws a border around the window
+    ServerSide,
+}
+
+/// A direction on screen.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, TryFromPrimitive)]
+pub enum Direction {
+    /// Towards the left of the screen
+    Left = 1,
+    /// Towards the right of the screen
+    Right,
+    /// Towards the top of the screen
+    Up,
+    /// Towards the bottom of the screen
+    Down,
+}
+
+/// What Pinnacle does when a window requests activation.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, TryFromPrimitive)]
+pub enum ActivationPolicy {
+    /// Always focus the window
+    Focus = 1,
+    /// Focus the window only if it was launched from the focused output, otherwise mark it urgent
+    FocusSameOutput,
+    /// Mark the window urgent without focusing it
+    Urgent,
+}
+
 /// Properties of a window.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct WindowProperties {
@@ -254,6 +402,23 @@ pub struct WindowProperties {
     pub fullscreen_or_maximized: Option<FullscreenOrMaximized>,
     /// All the tags on the window
     pub tags: Vec<TagHandle>,
+    /// Whether the window is minimized or not
+    pub minimized: Option<bool>,
+    /// Whether the window is sticky or not
+    pub sticky: Option<bool>,
+    /// Whether the window needs attention or not
+    pub urgent: Option<bool>,
+}
+
+/// A screenshot of a window.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Screenshot {
+    /// The width of the screenshot in pixels
+    pub width: u32,
+    /// The height of the screenshot in pixels
+    pub height: u32,
+    /// The screenshot's pixels as 8-bit RGBA, row by row
+    pub pixels: Vec<u8>,
 }
 
 impl WindowHandle {
@@ -357,6 +522,87 @@ impl WindowHandle {
         .unwrap();
     }
 
+    /// Minimize or restore this window.
+    ///
+    /// Minimized windows are hidden and aren't tiled or focused until they are restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Minimize the focused window.
+    /// window.get_focused()?.set_minimized(true);
+    /// ```
+    pub fn set_minimized(&self, set: bool) {
+        let mut client = self.window_client.clone();
+        block_on_tokio(client.set_minimized(SetMinimizedRequest {
+            window_id: Some(self.id),
+            set_or_toggle: Some(match set {
+                true => SetOrToggle::Set,
+                false => SetOrToggle::Unset,
+            } as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Toggle this window between minimized and not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Restore all minimized windows.
+    /// for win in window.get_all() {
+    ///     if win.minimized() == Some(true) {
+    ///         win.toggle_minimized();
+    ///     }
+    /// }
+    /// ```
+    pub fn toggle_minimized(&self) {
+        let mut client = self.window_client.clone();
+        block_on_tokio(client.set_minimized(SetMinimizedRequest {
+            window_id: Some(self.id),
+            set_or_toggle: Some(SetOrToggle::Toggle as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Set this window to sticky or not.
+    ///
+    /// Sticky windows are shown on every active tag of their output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Keep the focused window visible when switching tags.
+    /// window.get_focused()?.set_sticky(true);
+    /// ```
+    pub fn set_sticky(&self, set: bool) {
+        let mut client = self.window_client.clone();
+        block_on_tokio(client.set_sticky(SetStickyRequest {
+            window_id: Some(self.id),
+            set_or_toggle: Some(match set {
+                true => SetOrToggle::Set,
+                false => SetOrToggle::Unset,
+            } as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Toggle this window between sticky and not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// window.get_focused()?.toggle_sticky();
+    /// ```
+    pub fn toggle_sticky(&self) {
+        let mut client = self.window_client.clone();
+        block_on_tokio(client.set_sticky(SetStickyRequest {
+            window_id: Some(self.id),
+            set_or_toggle: Some(SetOrToggle::Toggle as i32),
+        }))
+        .unwrap();
+    }
+
     /// Set this window to floating or not.
     ///
     /// Floating windows will not be tiled and can be moved around and resized freely.
@@ -531,6 +777,233 @@ impl WindowHandle {
         .unwrap();
     }
 
+    /// Swap the positions of this window and `other`.
+    ///
+    /// For tiled windows, this swaps their places in the layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let windows = window.get_all();
+    /// windows[0].swap(&windows[1]);
+    /// ```
+    pub fn swap(&self, other: &WindowHandle) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.swap(SwapRequest {
+            window_id: Some(self.id),
+            target_id: Some(other.id),
+        }))
+        .unwrap();
+    }
+
+    /// Move this window `delta` places through the tiled windows on its output.
+    ///
+    /// Positive values move it towards the end of the layout, negative values towards the start.
+    /// The window stops at either end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Move the focused window one place back
+    /// window.get_focused()?.move_in_stack(-1);
+    /// ```
+    pub fn move_in_stack(&self, delta: i32) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.move_in_stack(MoveInStackRequest {
+            window_id: Some(self.id),
+            delta: Some(delta),
+        }))
+        .unwrap();
+    }
+
+    /// Add this window to `other`'s tabbed group, creating one if `other` isn't in a group.
+    ///
+    /// Windows in a group share one tiled slot, and only one of them is shown at a time
+    /// with a tab strip above it. This window takes on `other`'s tags and becomes the
+    /// shown window of the group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let windows = window.get_all();
+    /// windows[1].group_with(&windows[0]);
+    /// ```
+    pub fn group_with(&self, other: &WindowHandle) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.group_with(GroupWithRequest {
+            window_id: Some(self.id),
+            target_id: Some(other.id),
+        }))
+        .unwrap();
+    }
+
+    /// Take this window out of its group and tile it on its own.
+    ///
+    /// If only one window is left in the group, the group is dissolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// window.get_focused()?.ungroup();
+    /// ```
+    pub fn ungroup(&self) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.ungroup(UngroupRequest {
+            window_id: Some(self.id),
+        }))
+        .unwrap();
+    }
+
+    /// Show the window `delta` tabs away from the shown one in this window's group
+    /// and focus it.
+    ///
+    /// This wraps around at either end. This does nothing if this window isn't in a group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Show the next tab
+    /// window.get_focused()?.cycle_group(1);
+    /// ```
+    pub fn cycle_group(&self, delta: i32) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.cycle_group(CycleGroupRequest {
+            window_id: Some(self.id),
+            delta: Some(delta),
+        }))
+        .unwrap();
+    }
+
+    /// Send this window to the scratchpad called `name`.
+    ///
+    /// The window loses its tags and is hidden until the scratchpad is shown with
+    /// [`Window::toggle_scratchpad`]. Moving it to a tag takes it out of the scratchpad.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// window.get_focused()?.send_to_scratchpad("terminal");
+    /// ```
+    pub fn send_to_scratchpad(&self, name: impl Into<String>) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.send_to_scratchpad(SendToScratchpadRequest {
+            window_id: Some(self.id),
+            name: Some(name.into()),
+        }))
+        .unwrap();
+    }
+
+    /// Set whether this window should draw its own decorations or have Pinnacle draw them.
+    ///
+    /// This only affects Wayland windows that support server-side decorations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::DecorationMode;
+    ///
+    /// // Have Pinnacle draw a border around the focused window
+    /// window.get_focused()?.set_decoration_mode(DecorationMode::ServerSide);
+    /// ```
+    pub fn set_decoration_mode(&self, mode: DecorationMode) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.set_decoration_mode(SetDecorationModeRequest {
+            window_id: Some(self.id),
+            decoration_mode: Some(mode as i32),
+        }))
+        .unwrap();
+    }
+
+    /// Set whether or not this window is hidden from screen capture.
+    ///
+    /// Capture-hidden windows are replaced with a solid placeholder in screencopy frames
+    /// but are still shown normally on your monitors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Keep the focused window out of screen shares
+    /// window.get_focused()?.set_capture_hidden(true);
+    /// ```
+    pub fn set_capture_hidden(&self, hidden: bool) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.set_capture_hidden(SetCaptureHiddenRequest {
+            window_id: Some(self.id),
+            hidden: Some(hidden),
+        }))
+        .unwrap();
+    }
+
+    /// Set the opacity of this window, from 0.0 to 1.0.
+    ///
+    /// This overrides the default set with [`Window::set_default_opacity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Make the focused window translucent
+    /// window.get_focused()?.set_opacity(0.8);
+    /// ```
+    pub fn set_opacity(&self, opacity: f32) {
+        let mut client = self.window_client.clone();
+
+        block_on_tokio(client.set_opacity(SetOpacityRequest {
+            window_id: Some(self.id),
+            opacity: Some(opacity),
+        }))
+        .unwrap();
+    }
+
+    /// Take a screenshot of this window.
+    ///
+    /// The window and its popups are rendered at the scale of the output it's on.
+    /// This works even if the window isn't currently visible, for example
+    /// when it's on an inactive tag.
+    ///
+    /// Returns `None` if the window doesn't exist or couldn't be captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let screenshot = window.get_focused()?.screenshot()?;
+    /// println!("{}x{}", screenshot.width, screenshot.height);
+    /// ```
+    pub fn screenshot(&self) -> Option<Screenshot> {
+        block_on_tokio(self.screenshot_async())
+    }
+
+    /// The async version of [`screenshot`][Self::screenshot].
+    pub async fn screenshot_async(&self) -> Option<Screenshot> {
+        let mut client = self.window_client.clone();
+
+        let response = match client
+            .screenshot(ScreenshotRequest {
+                window_id: Some(self.id),
+            })
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                eprintln!("ERROR: {status}");
+                return None;
+            }
+        };
+
+        Some(Screenshot {
+            width: response.width?,
+            height: response.height?,
+            pixels: response.pixels?,
+        })
+    }
+
     /// Get all properties of this window.
     ///
     /// # Examples
@@ -594,6 +1067,9 @@ impl WindowHandle {
                 .into_iter()
                 .map(|id| self.api.tag.new_handle(id))
                 .collect(),
+            minimized: response.minimized,
+            sticky: response.sticky,
+            urgent: response.urgent,
         }
     }
 
@@ -680,4 +1156,42 @@ impl WindowHandle {
     pub async fn tags_async(&self) -> Vec<TagHandle> {
         self.props_async().await.tags
     }
+
+    /// Get whether or not this window is minimized.
+    ///
+    /// Shorthand for `self.props().minimized`.
+    pub fn minimized(&self) -> Option<bool> {
+        self.props().minimized
+    }
+
+    /// The async version of [`minimized`][Self::minimized].
+    pub async fn minimized_async(&self) -> Option<bool> {
+        self.props_async().await.minimized
+    }
+
+    /// Get whether or not this window is sticky.
+    ///
+    /// Shorthand for `self.props().sticky`.
+    pub fn sticky(&self) -> Option<bool> {
+        self.props().sticky
+    }
+
+    /// The async version of [`sticky`][Self::sticky].
+    pub async fn sticky_async(&self) -> Option<bool> {
+        self.props_async().await.sticky
+    }
+
+    /// Get whether or not this window needs attention.
+    ///
+    /// Windows stop needing attention when they are focused.
+    ///
+    /// Shorthand for `self.props().urgent`.
+    pub fn urgent(&self) -> Option<bool> {
+        self.props().urgent
+    }
+
+    /// The async version of [`urgent`][Self::urgent].
+    pub async fn urgent_async(&self) -> Option<bool> {
+        self.props_async().await.urgent
+    }
 }
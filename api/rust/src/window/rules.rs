@@ -209,12 +209,27 @@
 //!
 //! Note: this only applies to floating windows; tiled windows' geometry will be overridden by
 //! layouting.
+//!
+//! ### [`WindowRule::decoration_mode`]
+//! This will cause the window to use client-side or server-side decorations.
+//!
+//! ### [`WindowRule::capture_hidden`]
+//! This will cause the window to be replaced with a placeholder in screen captures.
+//!
+//! ### [`WindowRule::sticky`]
+//! This will cause the window to be shown on every active tag of its output.
+//!
+//! ### [`WindowRule::opacity`]
+//! This will cause the window to be drawn with the given opacity.
+//!
+//! ### [`WindowRule::can_swallow`]
+//! This will cause the window to be hidden while a window started by its process is open.
 
 use pinnacle_api_defs::pinnacle::window;
 
 use crate::{output::OutputHandle, tag::TagHandle};
 
-use super::FullscreenOrMaximized;
+use super::{DecorationMode, FullscreenOrMaximized};
 
 /// A condition for a [`WindowRule`] to apply to a window.
 ///
@@ -522,4 +537,90 @@ impl WindowRule {
         self.0.height = Some(height as i32);
         self
     }
+
+    /// This rule will force windows to use either client-side or server-side decorations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::rules::WindowRule;
+    /// use pinnacle_api::window::DecorationMode;
+    ///
+    /// // Force the window to let Pinnacle draw its decorations
+    /// let rule = WindowRule::new().decoration_mode(DecorationMode::ServerSide);
+    /// ```
+    pub fn decoration_mode(mut self, mode: DecorationMode) -> Self {
+        self.0.decoration_mode = Some(mode as i32);
+        self
+    }
+
+    /// This rule will hide windows from screen capture.
+    ///
+    /// Capture-hidden windows are replaced with a solid placeholder in screencopy frames
+    /// but are still shown normally on your monitors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::rules::WindowRule;
+    ///
+    /// // Keep the window out of screen shares
+    /// let rule = WindowRule::new().capture_hidden(true);
+    /// ```
+    pub fn capture_hidden(mut self, hidden: bool) -> Self {
+        self.0.capture_hidden = Some(hidden);
+        self
+    }
+
+    /// This rule will make windows sticky.
+    ///
+    /// Sticky windows are shown on every active tag of their output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::rules::WindowRule;
+    ///
+    /// // Keep picture-in-picture videos visible when switching tags
+    /// let rule = WindowRule::new().sticky(true);
+    /// ```
+    pub fn sticky(mut self, sticky: bool) -> Self {
+        self.0.sticky = Some(sticky);
+        self
+    }
+
+    /// This rule will draw windows with the given opacity, from 0.0 to 1.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::rules::WindowRule;
+    ///
+    /// let rule = WindowRule::new().opacity(0.9);
+    /// ```
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.0.opacity = Some(opacity);
+        self
+    }
+
+    /// This rule will let windows be swallowed by windows their process starts.
+    ///
+    /// A swallowed window is hidden and the new window takes its place in the layout
+    /// until the new window closes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pinnacle_api::window::rules::{WindowRule, WindowRuleCondition};
+    ///
+    /// // Let videos and images opened from a terminal take its place
+    /// window.add_window_rule(
+    ///     WindowRuleCondition::new().classes(["Alacritty"]),
+    ///     WindowRule::new().can_swallow(true),
+    /// );
+    /// ```
+    pub fn can_swallow(mut self, can_swallow: bool) -> Self {
+        self.0.can_swallow = Some(can_swallow);
+        self
+    }
 }
@@ -68,7 +68,10 @@ pub mod pinnacle {
                 OutputMoveRequest,
                 WindowPointerEnterRequest,
                 WindowPointerLeaveRequest,
-                TagActiveRequest
+                WindowUrgentRequest,
+                TagActiveRequest,
+                IdleStartRequest,
+                IdleEndRequest
             );
         }
     }
@@ -2,15 +2,16 @@ pub mod layout;
 pub mod signal;
 pub mod window;
 
-use std::{ffi::OsString, pin::Pin, process::Stdio};
+use std::{ffi::OsString, pin::Pin, process::Stdio, time::Duration};
 
 use pinnacle_api_defs::pinnacle::{
     input::v0alpha1::{
         input_service_server,
         set_libinput_setting_request::{AccelProfile, ClickMethod, ScrollMethod, TapButtonMap},
         set_mousebind_request::MouseEdge,
-        SetKeybindRequest, SetKeybindResponse, SetLibinputSettingRequest, SetMousebindRequest,
-        SetMousebindResponse, SetRepeatRateRequest, SetXkbConfigRequest,
+        SetIdleTimeoutRequest, SetKeybindRequest, SetKeybindResponse, SetLibinputSettingRequest,
+        SetMousebindRequest, SetMousebindResponse, SetRepeatRateRequest, SetXcursorRequest,
+        SetXkbConfigRequest,
     },
     output::{
         self,
@@ -21,7 +22,8 @@ use pinnacle_api_defs::pinnacle::{
     },
     process::v0alpha1::{process_service_server, SetEnvRequest, SpawnRequest, SpawnResponse},
     render::v0alpha1::{
-        render_service_server, Filter, SetDownscaleFilterRequest, SetUpscaleFilterRequest,
+        render_service_server, Color, Filter, SetBordersRequest, SetDecorationColorsRequest,
+        SetDownscaleFilterRequest, SetTabBarRequest, SetUpscaleFilterRequest,
     },
     tag::{
         self,
@@ -31,13 +33,13 @@ use pinnacle_api_defs::pinnacle::{
         },
     },
     v0alpha1::{
-        pinnacle_service_server, PingRequest, PingResponse, QuitRequest, ReloadConfigRequest,
-        SetOrToggle, ShutdownWatchRequest, ShutdownWatchResponse,
+        pinnacle_service_server, AllowSandboxedClientRequest, PingRequest, PingResponse,
+        PrivilegedProtocol, QuitRequest, ReloadConfigRequest, SetOrToggle, ShutdownWatchRequest,
+        ShutdownWatchResponse,
     },
 };
 use smithay::{
     backend::renderer::TextureFilter,
-    input::keyboard::XkbConfig,
     output::Scale,
     reexports::{calloop, input as libinput},
 };
@@ -54,8 +56,10 @@ use tracing::{debug, error, info, trace, warn};
 use crate::{
     backend::BackendData,
     config::ConnectorSavedState,
-    input::ModifierMask,
+    input::{ModifierMask, XkbSettings},
     output::OutputName,
+    render::{MAX_BORDER_WIDTH, MAX_TAB_BAR_HEIGHT},
+    security::{PrivilegedGlobal, SandboxRule},
     state::{State, WithState},
     tag::{Tag, TagId},
 };
@@ -223,6 +227,43 @@ impl pinnacle_service_server::PinnacleService for PinnacleService {
             state.config.shutdown_sender.replace(sender);
         })
     }
+
+    async fn allow_sandboxed_client(
+        &self,
+        request: Request<AllowSandboxedClientRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let globals = request
+            .protocols()
+            .filter_map(|protocol| match protocol {
+                PrivilegedProtocol::Unspecified => None,
+                PrivilegedProtocol::Screencopy => Some(PrivilegedGlobal::Screencopy),
+                PrivilegedProtocol::ToplevelExport => Some(PrivilegedGlobal::ToplevelExport),
+                PrivilegedProtocol::GammaControl => Some(PrivilegedGlobal::GammaControl),
+                PrivilegedProtocol::DataControl => Some(PrivilegedGlobal::DataControl),
+                PrivilegedProtocol::LayerShell => Some(PrivilegedGlobal::LayerShell),
+                PrivilegedProtocol::VirtualKeyboard => Some(PrivilegedGlobal::VirtualKeyboard),
+                PrivilegedProtocol::VirtualPointer => Some(PrivilegedGlobal::VirtualPointer),
+                PrivilegedProtocol::InputMethod => Some(PrivilegedGlobal::InputMethod),
+                PrivilegedProtocol::SessionLock => Some(PrivilegedGlobal::SessionLock),
+                PrivilegedProtocol::ForeignToplevel => Some(PrivilegedGlobal::ForeignToplevel),
+                PrivilegedProtocol::ExtWorkspace => Some(PrivilegedGlobal::ExtWorkspace),
+                PrivilegedProtocol::OutputManagement => Some(PrivilegedGlobal::OutputManagement),
+            })
+            .collect();
+
+        let rule = SandboxRule {
+            sandbox_engine: request.sandbox_engine,
+            app_id: request.app_id,
+            globals,
+        };
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.config.sandbox_allowlist.add_rule(rule);
+        })
+        .await
+    }
 }
 
 pub struct InputService {
@@ -347,15 +388,17 @@ impl input_service_server::InputService for InputService {
         let request = request.into_inner();
 
         run_unary_no_response(&self.sender, move |state| {
-            let new_config = XkbConfig {
-                rules: request.rules(),
-                variant: request.variant(),
-                model: request.model(),
-                layout: request.layout(),
+            let new_settings = XkbSettings {
+                rules: request.rules().to_string(),
+                variant: request.variant().to_string(),
+                model: request.model().to_string(),
+                layout: request.layout().to_string(),
                 options: request.options.clone(),
             };
+            state.input_state.xkb_settings = new_settings.clone();
+            state.input_state.virtual_keymap = None;
             if let Some(kb) = state.seat.get_keyboard() {
-                if let Err(err) = kb.set_xkb_config(state, new_config) {
+                if let Err(err) = kb.set_xkb_config(state, new_settings.as_xkb_config()) {
                     error!("Failed to set xkbconfig: {err}");
                 }
             }
@@ -384,6 +427,41 @@ impl input_service_server::InputService for InputService {
         .await
     }
 
+    async fn set_idle_timeout(
+        &self,
+        request: Request<SetIdleTimeoutRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let timeout = request
+            .timeout_ms
+            .map(|ms| Duration::from_millis(ms as u64));
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.set_idle_timeout(timeout);
+        })
+        .await
+    }
+
+    async fn set_xcursor(
+        &self,
+        request: Request<SetXcursorRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let theme = request.theme;
+        let size = request.size;
+
+        if size == Some(0) {
+            return Err(Status::invalid_argument("cursor size must be nonzero"));
+        }
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.set_xcursor(theme, size);
+        })
+        .await
+    }
+
     async fn set_libinput_setting(
         &self,
         request: Request<SetLibinputSettingRequest>,
@@ -580,7 +658,11 @@ impl process_service_server::ProcessService for ProcessService {
                 }
             }
 
+            let activation_token = state.create_spawn_activation_token();
+
             let Ok(mut child) = tokio::process::Command::new(OsString::from(arg0.clone()))
+                .env("XDG_ACTIVATION_TOKEN", &activation_token)
+                .env("DESKTOP_STARTUP_ID", &activation_token)
                 .stdin(match has_callback {
                     true => Stdio::piped(),
                     false => Stdio::null(),
@@ -732,22 +814,14 @@ impl tag_service_server::TagService for TagService {
                 return;
             };
 
-            match set_or_toggle {
-                SetOrToggle::Set => tag.set_active(true, state),
-                SetOrToggle::Unset => tag.set_active(false, state),
-                SetOrToggle::Toggle => tag.set_active(!tag.active(), state),
+            let active = match set_or_toggle {
+                SetOrToggle::Set => true,
+                SetOrToggle::Unset => false,
+                SetOrToggle::Toggle => !tag.active(),
                 SetOrToggle::Unspecified => unreachable!(),
-            }
-
-            let Some(output) = tag.output(state) else {
-                return;
             };
 
-            state.fixup_xwayland_internal_z_indices();
-
-            state.request_layout(&output);
-            state.update_focus(&output);
-            state.schedule_render(&output);
+            state.set_tag_active(&tag, active);
         })
         .await
     }
@@ -763,20 +837,7 @@ impl tag_service_server::TagService for TagService {
 
         run_unary_no_response(&self.sender, move |state| {
             let Some(tag) = tag_id.tag(state) else { return };
-            let Some(output) = tag.output(state) else { return };
-
-            output.with_state_mut(|op_state| {
-                for op_tag in op_state.tags.iter_mut() {
-                    op_tag.set_active(false, state);
-                }
-                tag.set_active(true, state);
-            });
-
-            state.fixup_xwayland_internal_z_indices();
-
-            state.request_layout(&output);
-            state.update_focus(&output);
-            state.schedule_render(&output);
+            state.switch_to_tag(&tag);
         })
         .await
     }
@@ -812,6 +873,9 @@ impl tag_service_server::TagService for TagService {
                 .extend(new_tags.clone());
 
             if let Some(output) = output_name.output(state) {
+                for tag in new_tags.iter() {
+                    tag.set_output(&output);
+                }
                 output.with_state_mut(|state| {
                     state.tags.extend(new_tags.clone());
                     debug!("tags added, are now {:?}", state.tags);
@@ -904,6 +968,14 @@ impl tag_service_server::TagService for TagService {
                 .map(|output| output.name());
             let active = tag.as_ref().map(|tag| tag.active());
             let name = tag.as_ref().map(|tag| tag.name());
+            let urgent = tag.as_ref().map(|tag| state.is_tag_urgent(tag));
+            // Sticky windows are on every active tag of their output
+            let output_tags = tag
+                .as_ref()
+                .filter(|tag| tag.active())
+                .and_then(|tag| tag.output(state))
+                .map(|output| output.with_state(|state| state.tags.clone()))
+                .unwrap_or_default();
             let window_ids = tag
                 .as_ref()
                 .map(|tag| {
@@ -912,7 +984,10 @@ impl tag_service_server::TagService for TagService {
                         .iter()
                         .filter_map(|win| {
                             win.with_state(|win_state| {
-                                win_state.tags.contains(tag).then_some(win_state.id.0)
+                                let sticky_here = win_state.sticky
+                                    && win_state.tags.iter().any(|tg| output_tags.contains(tg));
+                                (win_state.tags.contains(tag) || sticky_here)
+                                    .then_some(win_state.id.0)
                             })
                         })
                         .collect()
@@ -924,6 +999,7 @@ impl tag_service_server::TagService for TagService {
                 name,
                 output_name,
                 window_ids,
+                urgent,
             }
         })
         .await
@@ -1299,4 +1375,101 @@ impl render_service_server::RenderService for RenderService {
         })
         .await
     }
+
+    async fn set_decoration_colors(
+        &self,
+        request: Request<SetDecorationColorsRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        // Deprecated in favor of `SetBorders`, which this is a subset of
+        self.set_borders(Request::new(SetBordersRequest {
+            width: None,
+            focused: request.focused,
+            unfocused: request.unfocused,
+            urgent: None,
+        }))
+        .await
+    }
+
+    async fn set_borders(
+        &self,
+        request: Request<SetBordersRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let width = request.width;
+        let focused = request.focused.map(color_to_rgba);
+        let unfocused = request.unfocused.map(color_to_rgba);
+        let urgent = request.urgent.map(color_to_rgba);
+
+        run_unary_no_response(&self.sender, move |state| {
+            let borders = &mut state.config.borders;
+            if let Some(width) = width {
+                borders.width = width.min(MAX_BORDER_WIDTH) as i32;
+            }
+            if let Some(focused) = focused {
+                borders.focused = focused;
+            }
+            if let Some(unfocused) = unfocused {
+                borders.unfocused = unfocused;
+            }
+            if let Some(urgent) = urgent {
+                borders.urgent = urgent;
+            }
+
+            for output in state.space.outputs().cloned().collect::<Vec<_>>() {
+                state.request_layout(&output);
+                state.schedule_render(&output);
+            }
+        })
+        .await
+    }
+
+    async fn set_tab_bar(
+        &self,
+        request: Request<SetTabBarRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let height = request.height;
+        let focused = request.focused.map(color_to_rgba);
+        let unfocused = request.unfocused.map(color_to_rgba);
+        let inactive = request.inactive.map(color_to_rgba);
+        let urgent = request.urgent.map(color_to_rgba);
+
+        run_unary_no_response(&self.sender, move |state| {
+            let tab_bar = &mut state.config.tab_bar;
+            if let Some(height) = height {
+                tab_bar.height = height.min(MAX_TAB_BAR_HEIGHT) as i32;
+            }
+            if let Some(focused) = focused {
+                tab_bar.focused = focused;
+            }
+            if let Some(unfocused) = unfocused {
+                tab_bar.unfocused = unfocused;
+            }
+            if let Some(inactive) = inactive {
+                tab_bar.inactive = inactive;
+            }
+            if let Some(urgent) = urgent {
+                tab_bar.urgent = urgent;
+            }
+
+            for output in state.space.outputs().cloned().collect::<Vec<_>>() {
+                state.request_layout(&output);
+                state.schedule_render(&output);
+            }
+        })
+        .await
+    }
+}
+
+fn color_to_rgba(color: Color) -> [f32; 4] {
+    [
+        color.r.unwrap_or_default(),
+        color.g.unwrap_or_default(),
+        color.b.unwrap_or_default(),
+        color.a.unwrap_or(1.0),
+    ]
 }
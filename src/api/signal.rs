@@ -1,11 +1,13 @@
 use std::collections::VecDeque;
 
 use pinnacle_api_defs::pinnacle::signal::v0alpha1::{
-    signal_service_server, OutputConnectRequest, OutputConnectResponse, OutputDisconnectRequest,
-    OutputDisconnectResponse, OutputMoveRequest, OutputMoveResponse, OutputResizeRequest,
-    OutputResizeResponse, SignalRequest, StreamControl, TagActiveRequest, TagActiveResponse,
-    WindowPointerEnterRequest, WindowPointerEnterResponse, WindowPointerLeaveRequest,
-    WindowPointerLeaveResponse,
+    signal_service_server, IdleEndRequest, IdleEndResponse, IdleStartRequest, IdleStartResponse,
+    OutputConnectRequest, OutputConnectResponse, OutputDisconnectRequest, OutputDisconnectResponse,
+    OutputMoveRequest, OutputMoveResponse, OutputResizeRequest, OutputResizeResponse,
+    SignalRequest, StreamControl, TagActiveRequest, TagActiveResponse, WindowMinimizedRequest,
+    WindowMinimizedResponse, WindowPointerEnterRequest, WindowPointerEnterResponse,
+    WindowPointerLeaveRequest, WindowPointerLeaveResponse, WindowUrgentRequest,
+    WindowUrgentResponse,
 };
 use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
 use tonic::{Request, Response, Status, Streaming};
@@ -28,9 +30,15 @@ pub struct SignalState {
         SignalData<WindowPointerEnterResponse, VecDeque<WindowPointerEnterResponse>>,
     pub window_pointer_leave:
         SignalData<WindowPointerLeaveResponse, VecDeque<WindowPointerLeaveResponse>>,
+    pub window_urgent: SignalData<WindowUrgentResponse, VecDeque<WindowUrgentResponse>>,
+    pub window_minimized: SignalData<WindowMinimizedResponse, VecDeque<WindowMinimizedResponse>>,
 
     // Tag
     pub tag_active: SignalData<TagActiveResponse, VecDeque<TagActiveResponse>>,
+
+    // Idle
+    pub idle_start: SignalData<IdleStartResponse, VecDeque<IdleStartResponse>>,
+    pub idle_end: SignalData<IdleEndResponse, VecDeque<IdleEndResponse>>,
 }
 
 impl SignalState {
@@ -41,6 +49,10 @@ impl SignalState {
         self.output_move.disconnect();
         self.window_pointer_enter.disconnect();
         self.window_pointer_leave.disconnect();
+        self.window_urgent.disconnect();
+        self.window_minimized.disconnect();
+        self.idle_start.disconnect();
+        self.idle_end.disconnect();
     }
 }
 
@@ -191,9 +203,14 @@ impl signal_service_server::SignalService for SignalService {
 
     type WindowPointerEnterStream = ResponseStream<WindowPointerEnterResponse>;
     type WindowPointerLeaveStream = ResponseStream<WindowPointerLeaveResponse>;
+    type WindowUrgentStream = ResponseStream<WindowUrgentResponse>;
+    type WindowMinimizedStream = ResponseStream<WindowMinimizedResponse>;
 
     type TagActiveStream = ResponseStream<TagActiveResponse>;
 
+    type IdleStartStream = ResponseStream<IdleStartResponse>;
+    type IdleEndStream = ResponseStream<IdleEndResponse>;
+
     async fn output_connect(
         &self,
         request: Request<Streaming<OutputConnectRequest>>,
@@ -260,6 +277,28 @@ impl signal_service_server::SignalService for SignalService {
         })
     }
 
+    async fn window_urgent(
+        &self,
+        request: Request<Streaming<WindowUrgentRequest>>,
+    ) -> Result<Response<Self::WindowUrgentStream>, Status> {
+        let in_stream = request.into_inner();
+
+        start_signal_stream(self.sender.clone(), in_stream, |state| {
+            &mut state.signal_state.window_urgent
+        })
+    }
+
+    async fn window_minimized(
+        &self,
+        request: Request<Streaming<WindowMinimizedRequest>>,
+    ) -> Result<Response<Self::WindowMinimizedStream>, Status> {
+        let in_stream = request.into_inner();
+
+        start_signal_stream(self.sender.clone(), in_stream, |state| {
+            &mut state.signal_state.window_minimized
+        })
+    }
+
     async fn tag_active(
         &self,
         request: Request<Streaming<TagActiveRequest>>,
@@ -270,4 +309,26 @@ impl signal_service_server::SignalService for SignalService {
             &mut state.signal_state.tag_active
         })
     }
+
+    async fn idle_start(
+        &self,
+        request: Request<Streaming<IdleStartRequest>>,
+    ) -> Result<Response<Self::IdleStartStream>, Status> {
+        let in_stream = request.into_inner();
+
+        start_signal_stream(self.sender.clone(), in_stream, |state| {
+            &mut state.signal_state.idle_start
+        })
+    }
+
+    async fn idle_end(
+        &self,
+        request: Request<Streaming<IdleEndRequest>>,
+    ) -> Result<Response<Self::IdleEndStream>, Status> {
+        let in_stream = request.into_inner();
+
+        start_signal_stream(self.sender.clone(), in_stream, |state| {
+            &mut state.signal_state.idle_end
+        })
+    }
 }
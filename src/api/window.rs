@@ -5,24 +5,33 @@ use pinnacle_api_defs::pinnacle::{
     window::{
         self,
         v0alpha1::{
-            window_service_server, AddWindowRuleRequest, CloseRequest, FullscreenOrMaximized,
-            MoveGrabRequest, MoveToTagRequest, RaiseRequest, ResizeGrabRequest, SetFloatingRequest,
-            SetFocusedRequest, SetFullscreenRequest, SetGeometryRequest, SetMaximizedRequest,
-            SetTagRequest, WindowRule, WindowRuleCondition,
+            window_service_server, ActivationPolicy, AddWindowRuleRequest, CloseRequest,
+            CycleGroupRequest, DecorationMode, FocusDirectionRequest, FullscreenOrMaximized,
+            GroupWithRequest, MoveDirectionRequest, MoveGrabRequest, MoveInStackRequest,
+            MoveToTagRequest, RaiseRequest, ResizeGrabRequest, ScreenshotRequest,
+            ScreenshotResponse, SendToScratchpadRequest, SetActivationPolicyRequest,
+            SetCaptureHiddenRequest, SetDecorationModeRequest, SetDefaultOpacityRequest,
+            SetFloatingRequest, SetFocusedRequest, SetFullscreenRequest, SetGeometryRequest,
+            SetMaximizedRequest, SetMinimizedRequest, SetOpacityRequest, SetStickyRequest,
+            SetTagRequest, SwapRequest, ToggleScratchpadRequest, UngroupRequest, WindowRule,
+            WindowRuleCondition,
         },
     },
 };
 use smithay::{
-    desktop::{space::SpaceElement, WindowSurface},
+    desktop::space::SpaceElement,
     reexports::wayland_protocols::xdg::shell::server,
     utils::{Point, Rectangle, SERIAL_COUNTER},
     wayland::seat::WaylandFocus,
 };
 use tonic::{Request, Response, Status};
-use tracing::{error, warn};
+use tracing::warn;
 
 use crate::{
-    focus::keyboard::KeyboardFocusTarget, output::OutputName, state::WithState, tag::TagId,
+    focus::{keyboard::KeyboardFocusTarget, Direction},
+    output::OutputName,
+    state::WithState,
+    tag::TagId,
     window::window_state::WindowId,
 };
 
@@ -52,18 +61,7 @@ impl window_service_server::WindowService for WindowService {
         run_unary_no_response(&self.sender, move |state| {
             let Some(window) = window_id.window(state) else { return };
 
-            match window.underlying_surface() {
-                WindowSurface::Wayland(toplevel) => toplevel.send_close(),
-                WindowSurface::X11(surface) => {
-                    if !surface.is_override_redirect() {
-                        if let Err(err) = surface.close() {
-                            error!("failed to close x11 window: {err}");
-                        }
-                    } else {
-                        warn!("tried to close OR window");
-                    }
-                }
-            }
+            window.close();
         })
         .await
     }
@@ -214,6 +212,73 @@ impl window_service_server::WindowService for WindowService {
         .await
     }
 
+    async fn set_minimized(
+        &self,
+        request: Request<SetMinimizedRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let set_or_toggle = request.set_or_toggle();
+
+        if set_or_toggle == SetOrToggle::Unspecified {
+            return Err(Status::invalid_argument("unspecified set or toggle"));
+        }
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            let minimized = match set_or_toggle {
+                SetOrToggle::Set => true,
+                SetOrToggle::Unset => false,
+                SetOrToggle::Toggle => !window.with_state(|state| state.minimized),
+                SetOrToggle::Unspecified => unreachable!(),
+            };
+
+            state.set_window_minimized(&window, minimized);
+        })
+        .await
+    }
+
+    async fn set_sticky(&self, request: Request<SetStickyRequest>) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let set_or_toggle = request.set_or_toggle();
+
+        if set_or_toggle == SetOrToggle::Unspecified {
+            return Err(Status::invalid_argument("unspecified set or toggle"));
+        }
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            let sticky = match set_or_toggle {
+                SetOrToggle::Set => true,
+                SetOrToggle::Unset => false,
+                SetOrToggle::Toggle => !window.with_state(|state| state.sticky),
+                SetOrToggle::Unspecified => unreachable!(),
+            };
+
+            state.set_window_sticky(&window, sticky);
+        })
+        .await
+    }
+
     async fn set_floating(
         &self,
         request: Request<SetFloatingRequest>,
@@ -299,6 +364,7 @@ impl window_service_server::WindowService for WindowService {
 
             match set_or_toggle {
                 SetOrToggle::Set => {
+                    state.show_in_group(&window);
                     window.set_activate(true);
                     output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
                     state.output_focus_stack.set_focus(output.clone());
@@ -325,6 +391,7 @@ impl window_service_server::WindowService for WindowService {
                             keyboard.set_focus(state, None, SERIAL_COUNTER.next_serial());
                         }
                     } else {
+                        state.show_in_group(&window);
                         window.set_activate(true);
                         output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
                         state.output_focus_stack.set_focus(output.clone());
@@ -372,9 +439,11 @@ impl window_service_server::WindowService for WindowService {
         run_unary_no_response(&self.sender, move |state| {
             let Some(window) = window_id.window(state) else { return };
             let Some(tag) = tag_id.tag(state) else { return };
+            state.remove_from_group(&window);
             window.with_state_mut(|state| {
                 state.tags = vec![tag.clone()];
             });
+            state.remove_from_scratchpad(&window);
             let Some(output) = tag.output(state) else { return };
             state.request_layout(&output);
             state.schedule_render(&output);
@@ -426,6 +495,10 @@ impl window_service_server::WindowService for WindowService {
                 SetOrToggle::Unspecified => unreachable!(),
             }
 
+            if window.with_state(|state| !state.tags.is_empty()) {
+                state.remove_from_scratchpad(&window);
+            }
+
             let Some(output) = tag.output(state) else { return };
             state.request_layout(&output);
             state.schedule_render(&output);
@@ -453,6 +526,346 @@ impl window_service_server::WindowService for WindowService {
         .await
     }
 
+    async fn swap(&self, request: Request<SwapRequest>) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let target_id = WindowId(
+            request
+                .target_id
+                .ok_or_else(|| Status::invalid_argument("no target window specified"))?,
+        );
+
+        run_unary_no_response(&self.sender, move |state| {
+            let (Some(window), Some(target)) = (window_id.window(state), target_id.window(state))
+            else {
+                return;
+            };
+
+            state.swap_window_positions(&window, &target);
+        })
+        .await
+    }
+
+    async fn move_in_stack(
+        &self,
+        request: Request<MoveInStackRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let delta = request
+            .delta
+            .ok_or_else(|| Status::invalid_argument("no delta specified"))?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            state.move_window_in_stack(&window, delta);
+        })
+        .await
+    }
+
+    async fn group_with(&self, request: Request<GroupWithRequest>) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let target_id = WindowId(
+            request
+                .target_id
+                .ok_or_else(|| Status::invalid_argument("no target window specified"))?,
+        );
+
+        run_unary_no_response(&self.sender, move |state| {
+            let (Some(window), Some(target)) = (window_id.window(state), target_id.window(state))
+            else {
+                return;
+            };
+
+            state.group_window_with(&window, &target);
+        })
+        .await
+    }
+
+    async fn ungroup(&self, request: Request<UngroupRequest>) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            state.ungroup_window(&window);
+        })
+        .await
+    }
+
+    async fn cycle_group(
+        &self,
+        request: Request<CycleGroupRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let delta = request
+            .delta
+            .ok_or_else(|| Status::invalid_argument("no delta specified"))?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            state.cycle_group(&window, delta);
+        })
+        .await
+    }
+
+    async fn focus_direction(
+        &self,
+        request: Request<FocusDirectionRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let direction = direction_from_api(request.direction())?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.focus_direction(direction);
+        })
+        .await
+    }
+
+    async fn move_direction(
+        &self,
+        request: Request<MoveDirectionRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let direction = direction_from_api(request.direction())?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.move_direction(direction);
+        })
+        .await
+    }
+
+    async fn send_to_scratchpad(
+        &self,
+        request: Request<SendToScratchpadRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let name = request
+            .name
+            .ok_or_else(|| Status::invalid_argument("no scratchpad specified"))?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            state.send_to_scratchpad(&window, name);
+        })
+        .await
+    }
+
+    async fn toggle_scratchpad(
+        &self,
+        request: Request<ToggleScratchpadRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let name = request
+            .name
+            .ok_or_else(|| Status::invalid_argument("no scratchpad specified"))?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.toggle_scratchpad(&name);
+        })
+        .await
+    }
+
+    async fn set_decoration_mode(
+        &self,
+        request: Request<SetDecorationModeRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let mode = match request.decoration_mode() {
+            DecorationMode::Unspecified => {
+                return Err(Status::invalid_argument("unspecified decoration mode"));
+            }
+            DecorationMode::ClientSide => crate::window::window_state::DecorationMode::ClientSide,
+            DecorationMode::ServerSide => crate::window::window_state::DecorationMode::ServerSide,
+        };
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            window.set_decoration_mode(mode);
+
+            let Some(output) = window.output(state) else {
+                return;
+            };
+
+            state.schedule_render(&output);
+        })
+        .await
+    }
+
+    async fn set_capture_hidden(
+        &self,
+        request: Request<SetCaptureHiddenRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let hidden = request
+            .hidden
+            .ok_or_else(|| Status::invalid_argument("no hidden specified"))?;
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            window.with_state_mut(|state| state.capture_hidden = hidden);
+
+            let Some(output) = window.output(state) else {
+                return;
+            };
+
+            state.schedule_render(&output);
+        })
+        .await
+    }
+
+    async fn set_opacity(
+        &self,
+        request: Request<SetOpacityRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        let opacity = request
+            .opacity
+            .ok_or_else(|| Status::invalid_argument("no opacity specified"))?
+            .clamp(0.0, 1.0);
+
+        run_unary_no_response(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return;
+            };
+
+            window.with_state_mut(|state| state.opacity = Some(opacity));
+
+            let Some(output) = window.output(state) else {
+                return;
+            };
+
+            state.schedule_render(&output);
+        })
+        .await
+    }
+
+    async fn set_default_opacity(
+        &self,
+        request: Request<SetDefaultOpacityRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let focused = request.focused.map(|opacity| opacity.clamp(0.0, 1.0));
+        let unfocused = request.unfocused.map(|opacity| opacity.clamp(0.0, 1.0));
+
+        run_unary_no_response(&self.sender, move |state| {
+            let window_opacity = &mut state.config.window_opacity;
+            if let Some(focused) = focused {
+                window_opacity.focused = focused;
+            }
+            if let Some(unfocused) = unfocused {
+                window_opacity.unfocused = unfocused;
+            }
+
+            for output in state.space.outputs().cloned().collect::<Vec<_>>() {
+                state.schedule_render(&output);
+            }
+        })
+        .await
+    }
+
+    async fn set_activation_policy(
+        &self,
+        request: Request<SetActivationPolicyRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+
+        let policy = match request.policy() {
+            ActivationPolicy::Unspecified => {
+                return Err(Status::invalid_argument("unspecified activation policy"));
+            }
+            ActivationPolicy::Focus => crate::focus::ActivationPolicy::Focus,
+            ActivationPolicy::FocusSameOutput => crate::focus::ActivationPolicy::FocusSameOutput,
+            ActivationPolicy::Urgent => crate::focus::ActivationPolicy::Urgent,
+        };
+
+        run_unary_no_response(&self.sender, move |state| {
+            state.config.activation_policy = policy;
+        })
+        .await
+    }
+
     async fn move_grab(&self, request: Request<MoveGrabRequest>) -> Result<Response<()>, Status> {
         let request = request.into_inner();
 
@@ -652,6 +1065,18 @@ impl window_service_server::WindowService for WindowService {
                     }
                 } as i32);
 
+            let minimized = window
+                .as_ref()
+                .map(|win| win.with_state(|state| state.minimized));
+
+            let sticky = window
+                .as_ref()
+                .map(|win| win.with_state(|state| state.sticky));
+
+            let urgent = window
+                .as_ref()
+                .map(|win| win.with_state(|state| state.urgent));
+
             let tag_ids = window
                 .as_ref()
                 .map(|win| {
@@ -669,11 +1094,46 @@ impl window_service_server::WindowService for WindowService {
                 floating,
                 fullscreen_or_maximized,
                 tag_ids,
+                minimized,
+                sticky,
+                urgent,
             }
         })
         .await
     }
 
+    async fn screenshot(
+        &self,
+        request: Request<ScreenshotRequest>,
+    ) -> Result<Response<ScreenshotResponse>, Status> {
+        let request = request.into_inner();
+
+        let window_id = WindowId(
+            request
+                .window_id
+                .ok_or_else(|| Status::invalid_argument("no window specified"))?,
+        );
+
+        run_unary(&self.sender, move |state| {
+            let Some(window) = window_id.window(state) else {
+                return Err(Status::not_found("window does not exist"));
+            };
+
+            let (size, pixels) = state
+                .capture_window(&window)
+                .map_err(|err| Status::internal(format!("failed to capture window: {err}")))?;
+
+            Ok(ScreenshotResponse {
+                width: Some(size.w as u32),
+                height: Some(size.h as u32),
+                pixels: Some(pixels),
+            })
+        })
+        .await?
+        .into_inner()
+        .map(Response::new)
+    }
+
     async fn add_window_rule(
         &self,
         request: Request<AddWindowRuleRequest>,
@@ -697,6 +1157,18 @@ impl window_service_server::WindowService for WindowService {
     }
 }
 
+fn direction_from_api(direction: window::v0alpha1::Direction) -> Result<Direction, Status> {
+    match direction {
+        window::v0alpha1::Direction::Unspecified => {
+            Err(Status::invalid_argument("unspecified direction"))
+        }
+        window::v0alpha1::Direction::Left => Ok(Direction::Left),
+        window::v0alpha1::Direction::Right => Ok(Direction::Right),
+        window::v0alpha1::Direction::Up => Ok(Direction::Up),
+        window::v0alpha1::Direction::Down => Ok(Direction::Down),
+    }
+}
+
 impl From<WindowRuleCondition> for crate::window::rules::WindowRuleCondition {
     fn from(cond: WindowRuleCondition) -> Self {
         let cond_any = match cond.any.is_empty() {
@@ -776,6 +1248,15 @@ impl From<WindowRule> for crate::window::rules::WindowRule {
             })
         });
         let location = rule.x.and_then(|x| rule.y.map(|y| (x, y)));
+        let decoration_mode = match rule.decoration_mode() {
+            DecorationMode::Unspecified => None,
+            DecorationMode::ClientSide => {
+                Some(crate::window::window_state::DecorationMode::ClientSide)
+            }
+            DecorationMode::ServerSide => {
+                Some(crate::window::window_state::DecorationMode::ServerSide)
+            }
+        };
 
         crate::window::rules::WindowRule {
             output,
@@ -784,6 +1265,11 @@ impl From<WindowRule> for crate::window::rules::WindowRule {
             fullscreen_or_maximized,
             size,
             location,
+            decoration_mode,
+            capture_hidden: rule.capture_hidden,
+            sticky: rule.sticky,
+            opacity: rule.opacity,
+            can_swallow: rule.can_swallow,
         }
     }
 }
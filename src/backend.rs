@@ -10,6 +10,7 @@ use smithay::{
                 default_primary_scanout_output_compare, utils::select_dmabuf_feedback,
                 RenderElementStates,
             },
+            gles::GlesRenderer,
             ImportDma, Renderer, TextureFilter,
         },
     },
@@ -100,6 +101,28 @@ impl Backend {
         }
     }
 
+    /// Run `f` with the renderer used for offscreen rendering.
+    ///
+    /// On udev this is the primary GPU's renderer.
+    /// Returns `None` if there is no renderer available.
+    pub fn with_gles_renderer<T>(&mut self, f: impl FnOnce(&mut GlesRenderer) -> T) -> Option<T> {
+        match self {
+            Backend::Winit(winit) => Some(f(winit.backend.renderer())),
+            Backend::Udev(udev) => {
+                let mut renderer = match udev.gpu_manager.single_renderer(&udev.primary_gpu) {
+                    Ok(renderer) => renderer,
+                    Err(err) => {
+                        error!("Failed to get primary gpu renderer: {err}");
+                        return None;
+                    }
+                };
+                Some(f(renderer.as_mut()))
+            }
+            #[cfg(feature = "testing")]
+            Backend::Dummy(_) => None,
+        }
+    }
+
     /// Returns `true` if the backend is [`Winit`].
     ///
     /// [`Winit`]: Backend::Winit
@@ -52,7 +52,7 @@ use smithay::{
         SwapBuffersError,
     },
     desktop::utils::{send_frames_surface_tree, OutputPresentationFeedback},
-    input::pointer::CursorImageStatus,
+    input::pointer::{CursorIcon, CursorImageStatus},
     output::{Output, PhysicalProperties, Subpixel},
     reexports::{
         ash::vk::ExtPhysicalDeviceDrmFn,
@@ -89,7 +89,7 @@ use crate::{
     output::OutputName,
     render::{
         pointer::PointerElement, pointer_render_elements, take_presentation_feedback,
-        OutputRenderElement,
+        OutputRenderElement, RenderOptions,
     },
     state::{State, SurfaceDmabufFeedback, WithState},
 };
@@ -142,7 +142,6 @@ pub struct Udev {
     backends: HashMap<DrmNode, UdevBackendData>,
     pointer_images: Vec<(xcursor::parser::Image, TextureBuffer<MultiTexture>)>,
     pointer_element: PointerElement<MultiTexture>,
-    pointer_image: crate::cursor::Cursor,
 
     pub(super) upscale_filter: TextureFilter,
     pub(super) downscale_filter: TextureFilter,
@@ -182,6 +181,28 @@ impl Udev {
             }
         }
     }
+
+    /// Turn off the crtc driving `output`.
+    pub fn disable_output(&mut self, output: &Output) {
+        let Some(surface) = render_surface_for_output(output, &mut self.backends) else {
+            return;
+        };
+
+        if let Err(err) = surface.compositor.surface().clear_state() {
+            warn!("Failed to turn off output {}: {err}", output.name());
+        }
+    }
+
+    /// Make the next frame on `output` do a full modeset after it was disabled.
+    pub fn enable_output(&mut self, output: &Output) {
+        let Some(surface) = render_surface_for_output(output, &mut self.backends) else {
+            return;
+        };
+
+        if let Err(err) = surface.compositor.reset_state() {
+            warn!("Failed to reset state of output {}: {err}", output.name());
+        }
+    }
 }
 
 impl State {
@@ -244,10 +265,12 @@ impl State {
 
     /// Resize the output with the given mode.
     ///
+    /// Returns whether or not the output was successfully resized.
+    ///
     /// TODO: This is in udev.rs but is also used in winit.rs.
     /// |     I've got no clue how to make things public without making a mess.
-    pub fn resize_output(&mut self, output: &Output, mode: smithay::output::Mode) {
-        if let Backend::Udev(udev) = &mut self.backend {
+    pub fn resize_output(&mut self, output: &Output, mode: smithay::output::Mode) -> bool {
+        let resized = if let Backend::Udev(udev) = &mut self.backend {
             let drm_mode = udev.backends.iter().find_map(|(_, backend)| {
                 backend
                     .drm_scanner
@@ -266,23 +289,34 @@ impl State {
                     .copied()
             });
 
-            if let Some(drm_mode) = drm_mode {
-                if let Some(render_surface) = render_surface_for_output(output, &mut udev.backends)
-                {
-                    match render_surface.compositor.use_mode(drm_mode) {
-                        Ok(()) => {
-                            self.change_output_state(output, Some(mode), None, None, None);
-                        }
-                        Err(err) => error!("Failed to resize output: {err}"),
-                    }
+            let Some(drm_mode) = drm_mode else {
+                warn!("Output {} does not support mode {mode:?}", output.name());
+                return false;
+            };
+
+            let Some(render_surface) = render_surface_for_output(output, &mut udev.backends) else {
+                return false;
+            };
+
+            match render_surface.compositor.use_mode(drm_mode) {
+                Ok(()) => {
+                    self.change_output_state(output, Some(mode), None, None, None);
+                    true
+                }
+                Err(err) => {
+                    error!("Failed to resize output: {err}");
+                    false
                 }
             }
         } else {
             self.change_output_state(output, Some(mode), None, None, None);
-        }
+            true
+        };
 
         self.schedule_render(output);
         self.request_layout(output);
+
+        resized
     }
 }
 
@@ -387,7 +421,6 @@ pub fn setup_udev(
         gpu_manager,
         allocator: None,
         backends: HashMap::new(),
-        pointer_image: crate::cursor::Cursor::load(),
         pointer_images: Vec::new(),
         pointer_element: PointerElement::default(),
 
@@ -1130,6 +1163,9 @@ impl State {
             .get(&OutputName(output.name()))
         {
             let ConnectorSavedState { loc, tags, scale } = saved_state;
+            for tag in tags {
+                tag.set_output(&output);
+            }
             output.with_state_mut(|state| state.tags = tags.clone());
             self.change_output_state(&output, None, None, *scale, Some(*loc));
         } else {
@@ -1163,6 +1199,7 @@ impl State {
         let output = self
             .space
             .outputs()
+            .chain(self.disabled_outputs.iter())
             .find(|o| {
                 o.user_data()
                     .get::<UdevOutputData>()
@@ -1182,6 +1219,7 @@ impl State {
                 },
             );
             self.space.unmap_output(&output);
+            self.disabled_outputs.retain(|op| op != &output);
             self.gamma_control_manager_state.output_removed(&output);
 
             self.signal_state.output_disconnect.signal(|buffer| {
@@ -1350,6 +1388,9 @@ impl State {
     /// Render to the [`RenderSurface`] associated with the given `output`.
     #[tracing::instrument(level = "debug", skip(self), fields(output = output.name()))]
     fn render_surface(&mut self, output: &Output) {
+        let has_capture_hidden_windows = self.has_capture_hidden_windows(output);
+        let render_options = self.render_options();
+
         let udev = self.backend.udev_mut();
 
         let Some(surface) = render_surface_for_output(output, &mut udev.backends) else {
@@ -1358,12 +1399,20 @@ impl State {
 
         assert!(matches!(surface.render_state, RenderState::Scheduled(_)));
 
-        // TODO get scale from the rendersurface when supporting HiDPI
-        let frame = udev.pointer_image.get_image(
-            1,
-            // output.current_scale().integer_scale() as u32,
-            self.clock.now().into(),
-        );
+        // The output was disabled after this render was scheduled
+        if self.disabled_outputs.contains(output) {
+            surface.render_state = RenderState::Idle;
+            return;
+        }
+
+        let cursor_icon = match &self.cursor_status {
+            CursorImageStatus::Named(icon) => *icon,
+            _ => CursorIcon::Default,
+        };
+        let cursor_scale = output.current_scale().fractional_scale().ceil() as u32;
+        let frame = self
+            .cursor
+            .get_image(cursor_icon, cursor_scale, self.clock.now().into());
 
         let render_node = surface.render_node;
         let primary_gpu = udev.primary_gpu;
@@ -1379,6 +1428,8 @@ impl State {
         let _ = renderer.upscale_filter(udev.upscale_filter);
         let _ = renderer.downscale_filter(udev.downscale_filter);
 
+        let frame_hotspot = (frame.xhot, frame.yhot);
+
         let pointer_images = &mut udev.pointer_images;
         let pointer_image = pointer_images
             .iter()
@@ -1417,6 +1468,8 @@ impl State {
 
         // set cursor
         udev.pointer_element.set_texture(pointer_image.clone());
+        udev.pointer_element
+            .set_hotspot((frame_hotspot.0 as i32, frame_hotspot.1 as i32).into());
 
         // draw the cursor as relevant and
         // reset the cursor if the surface is no longer alive
@@ -1487,8 +1540,43 @@ impl State {
             &mut renderer,
             &self.space,
             &windows,
+            &render_options,
         ));
 
+        // Capture-hidden windows can't just be blitted from the rendered frame,
+        // so screencopies render their own elements with those windows replaced.
+        let capture_render_elements = match pending_screencopy_with_cursor {
+            Some(include_cursor) if has_capture_hidden_windows => {
+                let mut capture_render_elements = Vec::new();
+                if include_cursor {
+                    udev.pointer_element
+                        .set_element_kind(element::Kind::Unspecified);
+                    capture_render_elements.extend(pointer_render_elements(
+                        output,
+                        &mut renderer,
+                        &self.space,
+                        pointer_location,
+                        &mut self.cursor_status,
+                        self.dnd_icon.as_ref(),
+                        &udev.pointer_element,
+                    ));
+                    udev.pointer_element.set_element_kind(element::Kind::Cursor);
+                }
+                capture_render_elements.extend(crate::render::output_render_elements(
+                    output,
+                    &mut renderer,
+                    &self.space,
+                    &windows,
+                    &RenderOptions {
+                        for_capture: true,
+                        ..render_options
+                    },
+                ));
+                Some(capture_render_elements)
+            }
+            _ => None,
+        };
+
         let result = (|| -> Result<bool, SwapBuffersError> {
             let render_frame_result = render_frame(
                 &mut surface.compositor,
@@ -1508,6 +1596,7 @@ impl State {
                 output,
                 surface,
                 &render_frame_result,
+                capture_render_elements.as_deref(),
                 &self.loop_handle,
             );
 
@@ -1559,11 +1648,49 @@ fn render_surface_for_output<'a>(
         .and_then(|device| device.surfaces.get_mut(crtc))
 }
 
+/// Draw the whole output into the currently bound framebuffer for a screencopy.
+///
+/// This blits what was just rendered to the output unless `capture_render_elements`
+/// are provided, in which case those are rendered instead.
+fn draw_screencopy_source<'a>(
+    renderer: &mut UdevRenderer<'a>,
+    output: &Output,
+    render_frame_result: &UdevRenderFrameResult<'a>,
+    capture_render_elements: Option<
+        &[OutputRenderElement<UdevRenderer<'a>, WaylandSurfaceRenderElement<UdevRenderer<'a>>>],
+    >,
+) -> anyhow::Result<SyncPoint> {
+    if let Some(elements) = capture_render_elements {
+        let mut damage_tracker = damage::OutputDamageTracker::from_output(output);
+        let result = damage_tracker
+            .render_output(renderer, 0, elements, [0.6, 0.6, 0.6, 1.0])
+            .map_err(|err| anyhow!("{err}"))?;
+        return Ok(result.sync);
+    }
+
+    let untransformed_output_size = output.current_mode().expect("output no mode").size;
+
+    Ok(render_frame_result.blit_frame_result(
+        untransformed_output_size,
+        Transform::Normal,
+        output.current_scale().fractional_scale(),
+        renderer,
+        [Rectangle::from_loc_and_size(
+            Point::from((0, 0)),
+            untransformed_output_size,
+        )],
+        [],
+    )?)
+}
+
 fn handle_pending_screencopy<'a>(
     renderer: &mut UdevRenderer<'a>,
     output: &Output,
     surface: &mut RenderSurface,
     render_frame_result: &UdevRenderFrameResult<'a>,
+    capture_render_elements: Option<
+        &[OutputRenderElement<UdevRenderer<'a>, WaylandSurfaceRenderElement<UdevRenderer<'a>>>],
+    >,
     loop_handle: &LoopHandle<'static, State>,
 ) {
     let Some(mut screencopy) = output.with_state_mut(|state| state.screencopy.take()) else {
@@ -1682,13 +1809,11 @@ fn handle_pending_screencopy<'a>(
                 // just blit the whole output
                 renderer.bind(dmabuf)?;
 
-                Ok(Some(render_frame_result.blit_frame_result(
-                    screencopy.physical_region().size,
-                    Transform::Normal,
-                    output.current_scale().fractional_scale(),
+                Ok(Some(draw_screencopy_source(
                     renderer,
-                    [screencopy.physical_region()],
-                    [],
+                    output,
+                    render_frame_result,
+                    capture_render_elements,
                 )?))
             } else {
                 // `RenderFrameResult::blit_frame_result` doesn't expose a way to
@@ -1706,16 +1831,11 @@ fn handle_pending_screencopy<'a>(
 
                 renderer.bind(offscreen.clone())?;
 
-                let sync_point = render_frame_result.blit_frame_result(
-                    untransformed_output_size,
-                    Transform::Normal,
-                    output.current_scale().fractional_scale(),
+                let sync_point = draw_screencopy_source(
                     renderer,
-                    [Rectangle::from_loc_and_size(
-                        Point::from((0, 0)),
-                        untransformed_output_size,
-                    )],
-                    [],
+                    output,
+                    render_frame_result,
+                    capture_render_elements,
                 )?;
 
                 // ayo are we supposed to wait this here (granted it doesn't do anything
@@ -1787,16 +1907,11 @@ fn handle_pending_screencopy<'a>(
 
                 // Blit the entire output to `offscreen`.
                 // Only the needed region will be copied below
-                let sync_point = render_frame_result.blit_frame_result(
-                    untransformed_output_size,
-                    Transform::Normal,
-                    output.current_scale().fractional_scale(),
+                let sync_point = draw_screencopy_source(
                     renderer,
-                    [Rectangle::from_loc_and_size(
-                        Point::from((0, 0)),
-                        untransformed_output_size,
-                    )],
-                    [],
+                    output,
+                    render_frame_result,
+                    capture_render_elements,
                 )?;
 
                 // Can someone explain to me why it feels like some things are
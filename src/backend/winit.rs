@@ -5,11 +5,14 @@ use std::{ffi::OsString, path::PathBuf, time::Duration};
 use anyhow::{anyhow, ensure};
 use smithay::{
     backend::{
+        allocator::Fourcc,
         egl::EGLDevice,
         renderer::{
             self, buffer_type,
             damage::{self, OutputDamageTracker, RenderOutputResult},
+            element::surface::WaylandSurfaceRenderElement,
             gles::{GlesRenderbuffer, GlesRenderer, GlesTexture},
+            sync::SyncPoint,
             Bind, Blit, BufferType, ExportMem, ImportDma, ImportEgl, ImportMemWl, Offscreen,
             TextureFilter,
         },
@@ -40,7 +43,10 @@ use smithay::{
 use tracing::{error, trace, warn};
 
 use crate::{
-    render::{pointer::PointerElement, pointer_render_elements, take_presentation_feedback},
+    render::{
+        pointer::PointerElement, pointer_render_elements, take_presentation_feedback,
+        OutputRenderElement, RenderOptions,
+    },
     state::{State, WithState},
 };
 
@@ -262,6 +268,9 @@ pub fn setup_winit(
 
 impl State {
     fn render_winit_window(&mut self, output: &Output) {
+        let has_capture_hidden_windows = self.has_capture_hidden_windows(output);
+        let render_options = self.render_options();
+
         let winit = self.backend.winit_mut();
 
         let full_redraw = &mut winit.full_redraw;
@@ -274,6 +283,11 @@ impl State {
         }
 
         let cursor_visible = !matches!(self.cursor_status, CursorImageStatus::Surface(_));
+        // Named cursors are shown with the host's cursor
+        let cursor_icon = match &self.cursor_status {
+            CursorImageStatus::Named(icon) => Some(*icon),
+            _ => None,
+        };
 
         let mut pointer_element = PointerElement::<GlesTexture>::new();
 
@@ -292,18 +306,18 @@ impl State {
                 .is_some_and(|sc| !sc.overlay_cursor())
         });
 
+        let pointer_location = self
+            .seat
+            .get_pointer()
+            .map(|ptr| ptr.current_location())
+            .unwrap_or((0.0, 0.0).into());
+
         // If there isn't a pending screencopy that doesn't want to overlay the cursor,
         // render it.
         //
         // This will cause the cursor to disappear for a frame if there is one though,
         // but it shouldn't meaningfully affect anything.
         if !pending_screencopy_without_cursor {
-            let pointer_location = self
-                .seat
-                .get_pointer()
-                .map(|ptr| ptr.current_location())
-                .unwrap_or((0.0, 0.0).into());
-
             let pointer_render_elements = pointer_render_elements(
                 output,
                 winit.backend.renderer(),
@@ -321,8 +335,39 @@ impl State {
             winit.backend.renderer(),
             &self.space,
             &windows,
+            &render_options,
         ));
 
+        // Capture-hidden windows can't just be blitted from the rendered frame,
+        // so screencopies render their own elements with those windows replaced.
+        let pending_screencopy = output.with_state(|state| state.screencopy.is_some());
+        let capture_render_elements =
+            (pending_screencopy && has_capture_hidden_windows).then(|| {
+                let mut capture_render_elements = Vec::new();
+                if !pending_screencopy_without_cursor {
+                    capture_render_elements.extend(pointer_render_elements(
+                        output,
+                        winit.backend.renderer(),
+                        &self.space,
+                        pointer_location,
+                        &mut self.cursor_status,
+                        self.dnd_icon.as_ref(),
+                        &pointer_element,
+                    ));
+                }
+                capture_render_elements.extend(crate::render::output_render_elements(
+                    output,
+                    winit.backend.renderer(),
+                    &self.space,
+                    &windows,
+                    &RenderOptions {
+                        for_capture: true,
+                        ..render_options
+                    },
+                ));
+                capture_render_elements
+            });
+
         let render_res = winit.backend.bind().and_then(|_| {
             let age = if *full_redraw > 0 {
                 0
@@ -347,6 +392,7 @@ impl State {
                     &mut winit.backend,
                     output,
                     &render_output_result,
+                    capture_render_elements.as_deref(),
                     &self.loop_handle,
                 );
 
@@ -358,6 +404,9 @@ impl State {
                 }
 
                 winit.backend.window().set_cursor_visible(cursor_visible);
+                if let Some(cursor_icon) = cursor_icon {
+                    winit.backend.window().set_cursor_icon(cursor_icon);
+                }
 
                 let time = self.clock.now();
 
@@ -395,10 +444,35 @@ impl State {
 }
 
 impl Winit {
+    /// Render `elements` to an offscreen buffer the size of the output and bind it.
+    fn render_screencopy_source(
+        renderer: &mut GlesRenderer,
+        output: &Output,
+        elements: &[OutputRenderElement<GlesRenderer, WaylandSurfaceRenderElement<GlesRenderer>>],
+    ) -> anyhow::Result<SyncPoint> {
+        let size = output
+            .current_mode()
+            .expect("winit output has no mode")
+            .size
+            .to_logical(1)
+            .to_buffer(1, Transform::Normal);
+
+        let offscreen: GlesRenderbuffer = renderer.create_buffer(Fourcc::Abgr8888, size)?;
+        renderer.bind(offscreen)?;
+
+        let mut damage_tracker = OutputDamageTracker::from_output(output);
+        let result = damage_tracker.render_output(renderer, 0, elements, [0.6, 0.6, 0.6, 1.0])?;
+
+        Ok(result.sync)
+    }
+
     fn handle_pending_screencopy(
         backend: &mut WinitGraphicsBackend<GlesRenderer>,
         output: &Output,
         render_output_result: &RenderOutputResult,
+        capture_render_elements: Option<
+            &[OutputRenderElement<GlesRenderer, WaylandSurfaceRenderElement<GlesRenderer>>],
+        >,
         loop_handle: &LoopHandle<'static, State>,
     ) {
         let Some(mut screencopy) = output.with_state_mut(|state| state.screencopy.take()) else {
@@ -417,6 +491,24 @@ impl Winit {
             }
         }
 
+        // If there are capture elements, render them offscreen and copy from there
+        // instead of from what was rendered to the window.
+        let source_sync = match capture_render_elements {
+            Some(elements) => {
+                match Winit::render_screencopy_source(backend.renderer(), output, elements) {
+                    Ok(sync_point) => sync_point,
+                    Err(err) => {
+                        error!("Failed to render screencopy: {err}");
+                        if let Err(err) = backend.bind() {
+                            error!("Failed to rebind EGL surface after screencopy: {err}");
+                        }
+                        return;
+                    }
+                }
+            }
+            None => render_output_result.sync.clone(),
+        };
+
         let sync_point = if let Ok(dmabuf) = dmabuf::get_dmabuf(screencopy.buffer()) {
             trace!("Dmabuf screencopy");
 
@@ -431,7 +523,7 @@ impl Winit {
                     ),
                     TextureFilter::Nearest,
                 )
-                .map(|_| render_output_result.sync.clone())
+                .map(|_| source_sync.clone())
                 .map_err(|err| anyhow!("{err}"))
         } else if !matches!(
             renderer::buffer_type(screencopy.buffer()),
@@ -520,7 +612,7 @@ impl Winit {
 
                 res
             }
-            .map(|_| render_output_result.sync.clone());
+            .map(|_| source_sync.clone());
 
             // We must rebind to the underlying EGL surface for buffer swapping
             // as it is bound to a `GlesRenderbuffer` above.
@@ -531,6 +623,13 @@ impl Winit {
             sync_point
         };
 
+        // The offscreen buffer capture elements were rendered to is still bound
+        if capture_render_elements.is_some() {
+            if let Err(err) = backend.bind() {
+                error!("Failed to rebind EGL surface after screencopy: {err}");
+            }
+        }
+
         match sync_point {
             Ok(sync_point) if !sync_point.is_reached() => {
                 let Some(sync_fd) = sync_point.export() else {
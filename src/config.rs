@@ -3,8 +3,11 @@ use crate::{
         layout::LayoutService, signal::SignalService, window::WindowService, InputService,
         OutputService, PinnacleService, ProcessService, RenderService, TagService,
     },
+    focus::ActivationPolicy,
     input::ModifierMask,
     output::OutputName,
+    render::{Borders, TabBar, WindowOpacity},
+    security::SandboxAllowlist,
     tag::Tag,
     window::rules::{WindowRule, WindowRuleCondition},
 };
@@ -65,6 +68,10 @@ pub struct Metaconfig {
     pub envs: Option<Table>,
     pub reload_keybind: Keybind,
     pub kill_keybind: Keybind,
+    /// The keybind that toggles the focused window's keyboard shortcuts inhibitor.
+    ///
+    /// Like `kill_keybind`, this can't be inhibited.
+    pub inhibit_escape_keybind: Option<Keybind>,
     pub socket_dir: Option<String>,
 }
 
@@ -182,6 +189,16 @@ pub struct Config {
     pub window_rules: Vec<(WindowRuleCondition, WindowRule)>,
     /// Saved states when outputs are disconnected
     pub connector_saved_states: HashMap<OutputName, ConnectorSavedState>,
+    /// The borders drawn around windows
+    pub borders: Borders,
+    /// The tab strip drawn above window groups
+    pub tab_bar: TabBar,
+    /// The opacity of windows that don't have their own
+    pub window_opacity: WindowOpacity,
+    /// What to do when a window requests activation
+    pub activation_policy: ActivationPolicy,
+    /// Which privileged globals sandboxed clients can see
+    pub sandbox_allowlist: SandboxAllowlist,
 
     pub config_join_handle: Option<JoinHandle<()>>,
     config_reload_on_crash_token: Option<RegistrationToken>,
@@ -211,6 +228,11 @@ impl Config {
     fn clear(&mut self, loop_handle: &LoopHandle<State>) {
         self.window_rules.clear();
         self.connector_saved_states.clear();
+        self.borders = Borders::default();
+        self.tab_bar = TabBar::default();
+        self.window_opacity = WindowOpacity::default();
+        self.activation_policy = ActivationPolicy::default();
+        self.sandbox_allowlist.clear();
         if let Some(join_handle) = self.config_join_handle.take() {
             join_handle.abort();
         }
@@ -287,6 +309,8 @@ impl State {
 
         self.signal_state.clear();
 
+        self.set_idle_timeout(None);
+
         let config_dir_clone = config_dir.as_ref().map(|dir| dir.as_ref().to_path_buf());
         let load_default_config = |state: &mut State, reason: &str| {
             match &config_dir_clone {
@@ -327,6 +351,13 @@ impl State {
 
         self.input_state.reload_keybind = Some(reload_keybind);
         self.input_state.kill_keybind = Some(kill_keybind);
+        self.input_state.inhibit_escape_keybind =
+            metaconfig.inhibit_escape_keybind.map(|keybind| {
+                (
+                    ModifierMask::from(keybind.modifiers),
+                    Keysym::from(keybind.key as u32),
+                )
+            });
 
         if self.config.no_config {
             info!("`--no-config` was set, not spawning config");
@@ -675,6 +706,7 @@ mod tests {
 
             reload_keybind = { modifiers = ["Ctrl", "Alt"], key = "r" }
             kill_keybind = { modifiers = ["Ctrl", "Alt", "Shift"], key = "escape" }
+            inhibit_escape_keybind = { modifiers = ["Super"], key = "escape" }
 
             socket_dir = "/path/to/socket/dir"
 
@@ -703,6 +735,10 @@ mod tests {
                 modifiers: vec![Modifier::Ctrl, Modifier::Alt, Modifier::Shift],
                 key: Key::Escape,
             },
+            inhibit_escape_keybind: Some(Keybind {
+                modifiers: vec![Modifier::Super],
+                key: Key::Escape,
+            }),
             socket_dir: Some("/path/to/socket/dir".to_string()),
         };
 
@@ -740,6 +776,7 @@ mod tests {
                 modifiers: vec![Modifier::Ctrl, Modifier::Alt, Modifier::Shift],
                 key: Key::Escape,
             },
+            inhibit_escape_keybind: None,
             socket_dir: None,
         };
 
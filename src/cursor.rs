@@ -1,17 +1,29 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{io::Read, time::Duration};
+use std::{collections::HashMap, io::Read, time::Duration};
 
+use smithay::{
+    input::pointer::CursorIcon,
+    utils::{Point, Size},
+};
 use xcursor::{parser::Image, CursorTheme};
 
+use crate::state::State;
+
 static FALLBACK_CURSOR_DATA: &[u8] = include_bytes!("../resources/cursor.rgba");
 
+/// The default cursor size when `XCURSOR_SIZE` isn't set.
+const DEFAULT_CURSOR_SIZE: u32 = 24;
+
 pub struct Cursor {
-    icons: Vec<Image>,
+    theme: CursorTheme,
     size: u32,
+    /// Loaded images for each cursor icon, loaded lazily from the theme
+    icons: HashMap<CursorIcon, Vec<Image>>,
 }
 
 impl Cursor {
+    /// Load the cursor theme and size from `XCURSOR_THEME` and `XCURSOR_SIZE`.
     pub fn load() -> Self {
         let name = std::env::var("XCURSOR_THEME")
             .ok()
@@ -19,30 +31,97 @@ impl Cursor {
         let size = std::env::var("XCURSOR_SIZE")
             .ok()
             .and_then(|s| s.parse().ok())
-            .unwrap_or(24);
-
-        let theme = CursorTheme::load(&name);
-        let icons = load_icon(&theme)
-            .map_err(|err| tracing::warn!("Unable to load xcursor: {}, using fallback cursor", err))
-            .unwrap_or_else(|_| {
-                vec![Image {
-                    size: 32,
-                    width: 64,
-                    height: 64,
-                    xhot: 1,
-                    yhot: 1,
-                    delay: 1,
-                    pixels_rgba: Vec::from(FALLBACK_CURSOR_DATA),
-                    pixels_argb: vec![], //unused
-                }]
-            });
-
-        Cursor { icons, size }
+            .unwrap_or(DEFAULT_CURSOR_SIZE);
+
+        Cursor {
+            theme: CursorTheme::load(&name),
+            size,
+            icons: HashMap::new(),
+        }
     }
 
-    pub fn get_image(&self, scale: u32, time: Duration) -> Image {
+    /// Set the xcursor theme.
+    ///
+    /// This also sets `XCURSOR_THEME` so spawned clients use the same theme.
+    pub fn set_theme(&mut self, name: &str) {
+        std::env::set_var("XCURSOR_THEME", name);
+        self.theme = CursorTheme::load(name);
+        self.icons.clear();
+    }
+
+    /// Set the cursor size in logical pixels.
+    ///
+    /// This also sets `XCURSOR_SIZE` so spawned clients use the same size.
+    pub fn set_size(&mut self, size: u32) {
+        std::env::set_var("XCURSOR_SIZE", size.to_string());
+        self.size = size;
+    }
+
+    /// Get the image of `icon` at the given `scale` for the current point in its animation.
+    pub fn get_image(&mut self, icon: CursorIcon, scale: u32, time: Duration) -> Image {
         let size = self.size * scale;
-        frame(time.as_millis() as u32, size, &self.icons)
+        let theme = &self.theme;
+        let icons = self.icons.entry(icon).or_insert_with(|| {
+            load_icon(theme, icon)
+                .or_else(|err| {
+                    tracing::warn!("Unable to load xcursor {}: {err}", icon.name());
+                    load_icon(theme, CursorIcon::Default)
+                })
+                .unwrap_or_else(|err| {
+                    tracing::warn!("Unable to load xcursor: {err}, using fallback cursor");
+                    vec![Image {
+                        size: 32,
+                        width: 64,
+                        height: 64,
+                        xhot: 1,
+                        yhot: 1,
+                        delay: 1,
+                        pixels_rgba: Vec::from(FALLBACK_CURSOR_DATA),
+                        pixels_argb: vec![], //unused
+                    }]
+                })
+        });
+
+        frame(time.as_millis() as u32, size, icons)
+    }
+}
+
+impl State {
+    /// Set the xcursor theme and size.
+    ///
+    /// `None` leaves the current value unchanged.
+    pub fn set_xcursor(&mut self, theme: Option<String>, size: Option<u32>) {
+        if let Some(theme) = theme {
+            self.cursor.set_theme(&theme);
+        }
+        if let Some(size) = size {
+            self.cursor.set_size(size);
+        }
+
+        self.update_xwayland_cursor();
+
+        for output in self.space.outputs().cloned().collect::<Vec<_>>() {
+            self.schedule_render(&output);
+        }
+    }
+
+    /// Set Xwayland's default cursor to the current theme's default cursor.
+    pub fn update_xwayland_cursor(&mut self) {
+        let image = self
+            .cursor
+            .get_image(CursorIcon::Default, 1, Duration::ZERO);
+
+        let Some(xwm) = self.xwm.as_mut() else {
+            return;
+        };
+
+        if let Err(err) = xwm.set_cursor(
+            &image.pixels_rgba,
+            Size::from((image.width as u16, image.height as u16)),
+            Point::from((image.xhot as u16, image.yhot as u16)),
+        ) {
+            tracing::error!("Failed to set xwayland default cursor: {err}");
+        }
     }
 }
 
@@ -74,18 +153,59 @@ fn frame(mut millis: u32, size: u32, images: &[Image]) -> Image {
 
 #[derive(thiserror::Error, Debug)]
 enum Error {
-    #[error("Theme has no default cursor")]
-    NoDefaultCursor,
+    #[error("Theme has no cursor named {0}")]
+    NoCursor(&'static str),
     #[error("Error opening xcursor file: {0}")]
     File(#[from] std::io::Error),
     #[error("Failed to parse XCursor file")]
     Parse,
 }
 
-fn load_icon(theme: &CursorTheme) -> Result<Vec<Image>, Error> {
-    let icon_path = theme.load_icon("default").ok_or(Error::NoDefaultCursor)?;
+fn load_icon(theme: &CursorTheme, icon: CursorIcon) -> Result<Vec<Image>, Error> {
+    let icon_path = std::iter::once(&icon.name())
+        .chain(icon.alt_names())
+        .find_map(|name| theme.load_icon(name))
+        .ok_or(Error::NoCursor(icon.name()))?;
     let mut cursor_file = std::fs::File::open(icon_path)?;
     let mut cursor_data = Vec::new();
     cursor_file.read_to_end(&mut cursor_data)?;
     xcursor::parser::parse_xcursor(&cursor_data).ok_or(Error::Parse)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(size: u32, delay: u32) -> Image {
+        Image {
+            size,
+            width: size,
+            height: size,
+            xhot: 0,
+            yhot: 0,
+            delay,
+            pixels_rgba: vec![],
+            pixels_argb: vec![],
+        }
+    }
+
+    #[test]
+    fn frame_uses_the_nearest_size() {
+        let images = [image(24, 1), image(48, 1), image(96, 1)];
+
+        // A 24px cursor on a scale 2 output
+        assert_eq!(frame(0, 24 * 2, &images).size, 48);
+        assert_eq!(frame(0, 60, &images).size, 48);
+        assert_eq!(frame(0, 80, &images).size, 96);
+    }
+
+    #[test]
+    fn frame_follows_the_animation() {
+        let images = [image(24, 10), image(24, 20), image(48, 5)];
+
+        assert_eq!(frame(5, 24, &images).delay, 10);
+        assert_eq!(frame(15, 24, &images).delay, 20);
+        // Wraps around after the total delay of the nearest images
+        assert_eq!(frame(35, 24, &images).delay, 10);
+    }
+}
@@ -1,11 +1,15 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use smithay::{output::Output, utils::SERIAL_COUNTER};
+use pinnacle_api_defs::pinnacle::signal::v0alpha1::WindowUrgentResponse;
+use smithay::{
+    output::Output,
+    utils::{Logical, Rectangle, SERIAL_COUNTER},
+};
 use tracing::warn;
 
 use crate::{
     state::{State, WithState},
-    window::WindowElement,
+    window::{window_state::FloatingOrTiled, WindowElement},
 };
 
 pub mod keyboard;
@@ -19,29 +23,24 @@ impl State {
         // TODO: see if the below is necessary
         // output.with_state(|state| state.focus_stack.stack.retain(|win| win.alive()));
 
-        output
-            .with_state(|state| {
-                state.focus_stack.focused.then(|| {
-                    state
-                        .focus_stack
-                        .stack
-                        .iter()
-                        .rev()
-                        .filter(|win| win.is_on_active_tag())
-                        .find(|win| !win.is_x11_override_redirect())
-                        .cloned()
-                })
-            })
-            .flatten()
+        output.with_state(|state| state.focus_stack.current_focus())
     }
 
     /// Update the keyboard focus.
     pub fn update_focus(&mut self, output: &Output) {
+        // Keyboard focus belongs to the lock surface while the session is locked
+        if self.lock_state.is_locked() {
+            self.focus_lock_surface();
+            return;
+        }
+
         let current_focus = self.focused_window(output);
 
         if let Some(win) = &current_focus {
             assert!(!win.is_x11_override_redirect());
 
+            self.unset_window_urgent(win);
+
             if let Some(toplevel) = win.toplevel() {
                 toplevel.send_configure();
             }
@@ -77,6 +76,82 @@ impl State {
         self.fixup_xwayland_internal_z_indices();
     }
 
+    /// Focus `window` on its output and raise it.
+    ///
+    /// This does nothing if the window isn't on an active tag.
+    /// Minimized windows are restored first, and hidden windows in a group are shown.
+    pub fn activate_window(&mut self, window: WindowElement) {
+        if window.is_x11_override_redirect() || !window.is_on_active_tag() {
+            return;
+        }
+
+        self.set_window_minimized(&window, false);
+        self.show_in_group(&window);
+
+        let Some(output) = window.output(self) else {
+            return;
+        };
+
+        for win in self.space.elements() {
+            win.set_activate(false);
+        }
+
+        window.set_activate(true);
+        output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
+        self.output_focus_stack.set_focus(output.clone());
+        self.raise_window(window, true);
+        self.update_focus(&output);
+
+        self.schedule_render(&output);
+    }
+
+    /// Mark `window` as needing attention.
+    ///
+    /// Windows stop being urgent when they are focused.
+    pub fn set_window_urgent(&mut self, window: &WindowElement) {
+        let already_urgent =
+            window.with_state_mut(|state| std::mem::replace(&mut state.urgent, true));
+
+        if already_urgent {
+            return;
+        }
+
+        let window_id = Some(window.with_state(|state| state.id.0));
+
+        self.signal_state.window_urgent.signal(|buffer| {
+            buffer.push_back(WindowUrgentResponse {
+                window_id,
+                urgent: Some(true),
+            })
+        });
+
+        if let Some(output) = window.output(self) {
+            self.schedule_render(&output);
+        }
+    }
+
+    /// Stop `window` from needing attention.
+    pub fn unset_window_urgent(&mut self, window: &WindowElement) {
+        let was_urgent = window.with_state_mut(|state| std::mem::replace(&mut state.urgent, false));
+
+        if !was_urgent {
+            return;
+        }
+
+        let window_id = Some(window.with_state(|state| state.id.0));
+
+        self.signal_state.window_urgent.signal(|buffer| {
+            buffer.push_back(WindowUrgentResponse {
+                window_id,
+                urgent: Some(false),
+            })
+        });
+
+        if let Some(output) = window.output(self) {
+            self.schedule_render(&output);
+        }
+    }
+
     /// Get the currently focused output, or the first mapped output if there is none, or None.
     pub fn focused_output(&self) -> Option<&Output> {
         self.output_focus_stack
@@ -84,6 +159,210 @@ impl State {
             .last()
             .or_else(|| self.space.outputs().next())
     }
+
+    /// Get the output next to `output` in `direction` in the output layout.
+    pub fn output_in_direction(&self, output: &Output, direction: Direction) -> Option<Output> {
+        let from = self.space.output_geometry(output)?;
+
+        let candidates = self
+            .space
+            .outputs()
+            .filter(|op| op != &output)
+            .filter_map(|op| Some((op.clone(), self.space.output_geometry(op)?)));
+
+        closest_in_direction(from, direction, candidates)
+    }
+
+    /// Focus the closest window in `direction` from the focused window.
+    ///
+    /// Windows on other outputs are considered too. If there is no window in that direction,
+    /// the output in that direction is focused instead.
+    pub fn focus_direction(&mut self, direction: Direction) {
+        let Some(output) = self.focused_output().cloned() else {
+            return;
+        };
+
+        let focused = self.focused_window(&output);
+
+        let from = match focused.as_ref() {
+            Some(win) => self.space.element_geometry(win),
+            None => self.space.output_geometry(&output),
+        };
+
+        let Some(from) = from else {
+            return;
+        };
+
+        let candidates = self
+            .space
+            .elements()
+            .filter(|win| Some(*win) != focused.as_ref())
+            .filter(|win| !win.is_x11_override_redirect())
+            .filter(|win| win.is_on_active_tag())
+            .filter_map(|win| Some((win.clone(), self.space.element_geometry(win)?)))
+            .collect::<Vec<_>>();
+
+        if let Some(target) = closest_in_direction(from, direction, candidates) {
+            self.activate_window(target);
+            return;
+        }
+
+        if let Some(target_output) = self.output_in_direction(&output, direction) {
+            self.output_focus_stack.set_focus(target_output.clone());
+            self.update_focus(&target_output);
+            self.schedule_render(&target_output);
+        }
+    }
+
+    /// Move the focused window in `direction`.
+    ///
+    /// Tiled windows swap places with the closest tiled window in that direction on the same
+    /// output. If there is none, or the window isn't tiled, it is moved to the output
+    /// in that direction.
+    pub fn move_direction(&mut self, direction: Direction) {
+        let Some(output) = self.focused_output().cloned() else {
+            return;
+        };
+
+        let Some(window) = self.focused_window(&output) else {
+            return;
+        };
+
+        let Some(from) = self.space.element_geometry(&window) else {
+            return;
+        };
+
+        let is_tiled = |win: &WindowElement| {
+            win.with_state(|state| {
+                state.floating_or_tiled.is_tiled() && state.fullscreen_or_maximized.is_neither()
+            })
+        };
+
+        if is_tiled(&window) {
+            let candidates = self
+                .space
+                .elements()
+                .filter(|win| *win != &window && is_tiled(win))
+                .filter(|win| win.is_on_active_tag())
+                .filter(|win| win.output(self).as_ref() == Some(&output))
+                .filter_map(|win| Some((win.clone(), self.space.element_geometry(win)?)))
+                .collect::<Vec<_>>();
+
+            if let Some(target) = closest_in_direction(from, direction, candidates) {
+                self.swap_window_positions(&window, &target);
+                return;
+            }
+        }
+
+        let Some(target_output) = self.output_in_direction(&output, direction) else {
+            return;
+        };
+
+        self.move_window_to_output(&window, &output, &target_output);
+    }
+
+    /// Move `window` from `output` to the focused tags of `target_output` and keep it focused.
+    ///
+    /// Floating windows keep their position relative to the output.
+    fn move_window_to_output(
+        &mut self,
+        window: &WindowElement,
+        output: &Output,
+        target_output: &Output,
+    ) {
+        let tags =
+            target_output.with_state(|state| state.focused_tags().cloned().collect::<Vec<_>>());
+
+        if tags.is_empty() {
+            return;
+        }
+
+        let (Some(output_geo), Some(target_geo)) = (
+            self.space.output_geometry(output),
+            self.space.output_geometry(target_output),
+        ) else {
+            return;
+        };
+
+        self.remove_from_scratchpad(window);
+
+        window.with_state_mut(|state| {
+            if let FloatingOrTiled::Floating(rect) = &mut state.floating_or_tiled {
+                rect.loc += target_geo.loc - output_geo.loc;
+            }
+            state.tags = tags;
+        });
+
+        self.request_layout(output);
+        self.request_layout(target_output);
+
+        self.activate_window(window.clone());
+        self.update_focus(output);
+        self.schedule_render(output);
+    }
+}
+
+/// A direction on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Find the candidate closest to `from` in `direction`.
+///
+/// Candidates whose centers aren't past the center of `from` are ignored. Ones that overlap
+/// `from` on the perpendicular axis are preferred, then the closest along the direction.
+fn closest_in_direction<T>(
+    from: Rectangle<i32, Logical>,
+    direction: Direction,
+    candidates: impl IntoIterator<Item = (T, Rectangle<i32, Logical>)>,
+) -> Option<T> {
+    let center = |rect: Rectangle<i32, Logical>| {
+        (rect.loc.x + rect.size.w / 2, rect.loc.y + rect.size.h / 2)
+    };
+
+    let (from_x, from_y) = center(from);
+
+    let overlaps_vertically = |rect: Rectangle<i32, Logical>| {
+        rect.loc.y < from.loc.y + from.size.h && from.loc.y < rect.loc.y + rect.size.h
+    };
+    let overlaps_horizontally = |rect: Rectangle<i32, Logical>| {
+        rect.loc.x < from.loc.x + from.size.w && from.loc.x < rect.loc.x + rect.size.w
+    };
+
+    candidates
+        .into_iter()
+        .filter_map(|(item, rect)| {
+            let (x, y) = center(rect);
+
+            let (distance, offset, overlaps) = match direction {
+                Direction::Left => (from_x - x, (from_y - y).abs(), overlaps_vertically(rect)),
+                Direction::Right => (x - from_x, (from_y - y).abs(), overlaps_vertically(rect)),
+                Direction::Up => (from_y - y, (from_x - x).abs(), overlaps_horizontally(rect)),
+                Direction::Down => (y - from_y, (from_x - x).abs(), overlaps_horizontally(rect)),
+            };
+
+            (distance > 0).then_some(((!overlaps, distance, offset), item))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, item)| item)
+}
+
+/// What to do when a window requests activation, for example through xdg-activation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationPolicy {
+    /// Always focus the window.
+    #[default]
+    Focus,
+    /// Focus the window only if the app was launched from the focused output.
+    ///
+    /// Otherwise, mark the window urgent.
+    FocusSameOutput,
+    /// Never focus the window; mark it urgent instead.
+    Urgent,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -123,4 +402,78 @@ impl WindowKeyboardFocusStack {
     pub fn unset_focus(&mut self) {
         self.focused = false;
     }
+
+    /// Get the topmost unminimized window in this stack that is on an active tag,
+    /// or `None` if this stack is unfocused.
+    pub fn current_focus(&self) -> Option<WindowElement> {
+        self.focused
+            .then(|| {
+                self.stack
+                    .iter()
+                    .rev()
+                    .filter(|win| win.is_on_active_tag())
+                    .filter(|win| !win.with_state(|state| state.minimized || state.swallowed))
+                    .filter(|win| !win.is_inactive_in_group())
+                    .find(|win| !win.is_x11_override_redirect())
+                    .cloned()
+            })
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, w: i32, h: i32) -> Rectangle<i32, Logical> {
+        Rectangle::from_loc_and_size((x, y), (w, h))
+    }
+
+    #[test]
+    fn closest_in_direction_prefers_overlapping_candidates() {
+        let from = rect(100, 100, 100, 100);
+
+        let candidates = [
+            ("left", rect(0, 100, 100, 100)),
+            ("right_far", rect(400, 100, 100, 100)),
+            ("right_offset", rect(200, 300, 100, 100)),
+            ("right", rect(250, 100, 100, 100)),
+            ("up", rect(100, 0, 100, 100)),
+        ];
+
+        assert_eq!(
+            closest_in_direction(from, Direction::Left, candidates),
+            Some("left")
+        );
+        // "right_offset" is closer but doesn't overlap vertically
+        assert_eq!(
+            closest_in_direction(from, Direction::Right, candidates),
+            Some("right")
+        );
+        assert_eq!(
+            closest_in_direction(from, Direction::Up, candidates),
+            Some("up")
+        );
+        assert_eq!(
+            closest_in_direction(from, Direction::Down, candidates),
+            Some("right_offset")
+        );
+    }
+
+    #[test]
+    fn closest_in_direction_ignores_candidates_behind() {
+        let from = rect(100, 100, 100, 100);
+
+        // Its center is at the same x as `from`'s
+        let candidates = [("same", rect(50, 300, 200, 100))];
+
+        assert_eq!(
+            closest_in_direction(from, Direction::Left, candidates),
+            None
+        );
+        assert_eq!(
+            closest_in_direction(from, Direction::Right, candidates),
+            None
+        );
+    }
 }
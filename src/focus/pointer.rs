@@ -423,6 +423,9 @@ impl From<KeyboardFocusTarget> for PointerFocusTarget {
             KeyboardFocusTarget::LayerSurface(layer) => {
                 PointerFocusTarget::WlSurface(layer.wl_surface().clone())
             }
+            KeyboardFocusTarget::LockSurface(surf) => {
+                PointerFocusTarget::WlSurface(surf.wl_surface().clone())
+            }
         }
     }
 }
@@ -75,6 +75,7 @@ impl PointerGrab<State> for MoveSurfaceGrab {
                 .space
                 .elements()
                 .rev()
+                .filter(|win| win.is_on_active_tag())
                 .find(|&win| {
                     if let Some(loc) = state.space.element_location(win) {
                         let size = win.geometry().size;
@@ -109,7 +110,9 @@ impl PointerGrab<State> for MoveSurfaceGrab {
                 }
 
                 if window_under.with_state(|state| {
-                    state.floating_or_tiled.is_floating() || state.target_loc.is_some()
+                    state.floating_or_tiled.is_floating()
+                        || !state.fullscreen_or_maximized.is_neither()
+                        || state.target_loc.is_some()
                 }) {
                     return;
                 }
@@ -288,6 +291,7 @@ pub fn move_request_client(
             initial_window_loc,
         };
 
+        state.deactivate_pointer_constraint();
         pointer.set_grab(state, grab, serial, Focus::Clear);
     } else {
         debug!("No grab start data for grab, cancelling");
@@ -327,5 +331,6 @@ pub fn move_request_server(
         initial_window_loc,
     };
 
+    state.deactivate_pointer_constraint();
     pointer.set_grab(state, grab, serial, Focus::Clear);
 }
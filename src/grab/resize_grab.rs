@@ -487,6 +487,7 @@ pub fn resize_request_client(
         );
 
         if let Some(grab) = grab {
+            state.deactivate_pointer_constraint();
             pointer.set_grab(state, grab, serial, Focus::Clear);
         }
     }
@@ -545,6 +546,7 @@ pub fn resize_request_server(
     );
 
     if let Some(grab) = grab {
+        state.deactivate_pointer_constraint();
         pointer.set_grab(state, grab, serial, Focus::Clear);
     }
 }
@@ -1,24 +1,36 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod xdg_activation;
 mod xdg_shell;
 mod xwayland;
 
-use std::{mem, os::fd::OwnedFd, time::Duration};
+use std::{collections::HashMap, mem, os::fd::OwnedFd, sync::Arc, time::Duration};
 
 use smithay::{
     backend::renderer::utils::{self, with_renderer_surface_state},
-    delegate_compositor, delegate_data_control, delegate_data_device, delegate_fractional_scale,
-    delegate_layer_shell, delegate_output, delegate_presentation, delegate_primary_selection,
-    delegate_relative_pointer, delegate_seat, delegate_shm, delegate_viewporter,
+    delegate_compositor, delegate_cursor_shape, delegate_data_control, delegate_data_device,
+    delegate_fractional_scale, delegate_idle_inhibit, delegate_idle_notify,
+    delegate_input_method_manager, delegate_keyboard_shortcuts_inhibit, delegate_layer_shell,
+    delegate_output, delegate_pointer_constraints, delegate_presentation,
+    delegate_primary_selection, delegate_relative_pointer, delegate_seat,
+    delegate_security_context, delegate_session_lock, delegate_shm, delegate_text_input_manager,
+    delegate_viewporter, delegate_xdg_decoration,
     desktop::{
         self, find_popup_root_surface, get_popup_toplevel_coords, layer_map_for_output,
-        utils::surface_primary_scanout_output, PopupKind, WindowSurfaceType,
+        space::SpaceElement, utils::surface_primary_scanout_output, PopupKind, PopupManager,
+        WindowSurfaceType,
+    },
+    input::{
+        pointer::{CursorImageStatus, PointerHandle},
+        Seat, SeatHandler, SeatState,
     },
-    input::{pointer::CursorImageStatus, Seat, SeatHandler, SeatState},
     output::Output,
     reexports::{
         calloop::Interest,
-        wayland_protocols::xdg::shell::server::xdg_positioner::ConstraintAdjustment,
+        wayland_protocols::xdg::{
+            decoration::zv1::server::zxdg_toplevel_decoration_v1::Mode as DecorationMode,
+            shell::server::xdg_positioner::ConstraintAdjustment,
+        },
         wayland_server::{
             protocol::{
                 wl_buffer::WlBuffer, wl_data_source::WlDataSource, wl_output::WlOutput,
@@ -27,7 +39,7 @@ use smithay::{
             Client, Resource,
         },
     },
-    utils::{Logical, Rectangle, SERIAL_COUNTER},
+    utils::{Logical, Physical, Rectangle, Size, SERIAL_COUNTER},
     wayland::{
         buffer::BufferHandler,
         compositor::{
@@ -36,8 +48,19 @@ use smithay::{
         },
         dmabuf,
         fractional_scale::{self, FractionalScaleHandler},
+        idle_inhibit::IdleInhibitHandler,
+        idle_notify::{IdleNotifierHandler, IdleNotifierState},
+        input_method::{InputMethodHandler, PopupSurface as InputMethodPopupSurface},
+        keyboard_shortcuts_inhibit::{
+            KeyboardShortcutsInhibitHandler, KeyboardShortcutsInhibitState,
+            KeyboardShortcutsInhibitor,
+        },
         output::OutputHandler,
+        pointer_constraints::PointerConstraintsHandler,
         seat::WaylandFocus,
+        security_context::{
+            SecurityContext, SecurityContextHandler, SecurityContextListenerSource,
+        },
         selection::{
             data_device::{
                 set_data_device_focus, ClientDndGrabHandler, DataDeviceHandler, DataDeviceState,
@@ -49,25 +72,43 @@ use smithay::{
             wlr_data_control::{DataControlHandler, DataControlState},
             SelectionHandler, SelectionSource, SelectionTarget,
         },
+        session_lock::{LockSurface, SessionLockHandler, SessionLockManagerState, SessionLocker},
         shell::{
             wlr_layer::{self, Layer, LayerSurfaceData, WlrLayerShellHandler, WlrLayerShellState},
-            xdg::{PopupSurface, XdgPopupSurfaceData, XdgToplevelSurfaceData},
+            xdg::{
+                decoration::XdgDecorationHandler, PopupSurface, ToplevelSurface,
+                XdgPopupSurfaceData, XdgToplevelSurfaceData,
+            },
         },
         shm::{ShmHandler, ShmState},
+        tablet_manager::TabletSeatHandler,
     },
     xwayland::{X11Wm, XWaylandClientData},
 };
-use tracing::{error, trace, warn};
+use tracing::{error, info, trace, warn};
 
 use crate::{
     backend::Backend,
-    delegate_gamma_control, delegate_screencopy,
+    delegate_ext_workspace, delegate_foreign_toplevel, delegate_gamma_control,
+    delegate_output_management, delegate_screencopy, delegate_toplevel_export,
     focus::{keyboard::KeyboardFocusTarget, pointer::PointerFocusTarget},
     protocol::{
+        ext_workspace::{ExtWorkspaceHandler, ExtWorkspaceManagerState, WorkspaceInfo},
+        foreign_toplevel::{
+            ForeignToplevelHandler, ForeignToplevelInfo, ForeignToplevelManagerState,
+        },
         gamma_control::{GammaControlHandler, GammaControlManagerState},
+        output_management::{
+            OutputConfiguration, OutputManagementHandler, OutputManagementManagerState,
+        },
         screencopy::{Screencopy, ScreencopyHandler},
+        toplevel_export::{ToplevelExport, ToplevelExportHandler},
     },
+    render::capture::window_capture_size,
+    session_lock::LockState,
     state::{ClientState, State, WithState},
+    tag::TagId,
+    window::{initial_configure_sent, window_state::WindowId},
 };
 
 impl BufferHandler for State {
@@ -131,6 +172,12 @@ impl CompositorHandler for State {
                 if let Some(loc) = window.with_state_mut(|state| state.target_loc.take()) {
                     self.space.map_element(window.clone(), loc, false);
                 }
+
+                let exports =
+                    window.with_state_mut(|state| mem::take(&mut state.pending_toplevel_exports));
+                for export in exports {
+                    self.submit_toplevel_export(&window, export);
+                }
             }
         };
 
@@ -152,10 +199,16 @@ impl CompositorHandler for State {
                 self.new_windows.retain(|win| win != &new_window);
                 self.windows.push(new_window.clone());
 
+                let should_focus = self.take_pending_activation(surface);
+
                 if let Some(output) = self.focused_output() {
                     tracing::debug!("Placing toplevel");
                     new_window.place_on_output(output);
-                    output.with_state_mut(|state| state.focus_stack.set_focus(new_window.clone()));
+                    if should_focus {
+                        output.with_state_mut(|state| {
+                            state.focus_stack.set_focus(new_window.clone())
+                        });
+                    }
                 }
 
                 // FIXME: I'm mapping way offscreen here then sending a frame to prevent a window from
@@ -164,9 +217,10 @@ impl CompositorHandler for State {
                 self.space
                     .map_element(new_window.clone(), (1000000, 0), true);
 
-                self.raise_window(new_window.clone(), true);
+                self.raise_window(new_window.clone(), should_focus);
 
                 self.apply_window_rules(&new_window);
+                self.swallow_parent(&new_window);
 
                 if let Some(focused_output) = self.focused_output().cloned() {
                     self.request_layout(&focused_output);
@@ -178,17 +232,24 @@ impl CompositorHandler for State {
                     );
                 }
 
-                self.loop_handle.insert_idle(move |state| {
-                    state
-                        .seat
-                        .get_keyboard()
-                        .expect("Seat had no keyboard") // FIXME: actually handle error
-                        .set_focus(
-                            state,
-                            Some(KeyboardFocusTarget::Window(new_window)),
-                            SERIAL_COUNTER.next_serial(),
-                        );
-                });
+                if should_focus {
+                    self.loop_handle.insert_idle(move |state| {
+                        if state.lock_state.is_locked() {
+                            return;
+                        }
+                        state
+                            .seat
+                            .get_keyboard()
+                            .expect("Seat had no keyboard") // FIXME: actually handle error
+                            .set_focus(
+                                state,
+                                Some(KeyboardFocusTarget::Window(new_window)),
+                                SERIAL_COUNTER.next_serial(),
+                            );
+                    });
+                } else {
+                    self.set_window_urgent(&new_window);
+                }
             } else if new_window.toplevel().is_some() {
                 new_window.on_commit();
                 ensure_initial_configure(surface, self);
@@ -240,6 +301,20 @@ impl CompositorHandler for State {
             .cloned()
         {
             vec![output] // surface is a layer surface
+        } else if let Some(output) = self
+            .space
+            .outputs()
+            .find(|op| {
+                op.with_state(|state| {
+                    state
+                        .lock_surface
+                        .as_ref()
+                        .is_some_and(|lock| lock.wl_surface() == &root)
+                })
+            })
+            .cloned()
+        {
+            vec![output] // surface is a lock surface
         } else {
             return;
         };
@@ -423,10 +498,15 @@ impl SeatHandler for State {
         });
         set_data_device_focus(&self.display_handle, seat, focus_client.clone());
         set_primary_focus(&self.display_handle, seat, focus_client);
+
+        self.update_pointer_constraint(focused);
     }
 }
 delegate_seat!(State);
 
+impl TabletSeatHandler for State {}
+delegate_cursor_shape!(State);
+
 impl ShmHandler for State {
     fn shm_state(&self) -> &ShmState {
         &self.shm_state
@@ -491,6 +571,76 @@ delegate_fractional_scale!(State);
 
 delegate_relative_pointer!(State);
 
+impl PointerConstraintsHandler for State {
+    fn new_constraint(&mut self, _surface: &WlSurface, _pointer: &PointerHandle<Self>) {
+        let keyboard_focus = self.seat.get_keyboard().and_then(|kb| kb.current_focus());
+        self.update_pointer_constraint(keyboard_focus.as_ref());
+    }
+}
+delegate_pointer_constraints!(State);
+
+impl KeyboardShortcutsInhibitHandler for State {
+    fn keyboard_shortcuts_inhibit_state(&mut self) -> &mut KeyboardShortcutsInhibitState {
+        &mut self.keyboard_shortcuts_inhibit_state
+    }
+
+    fn new_inhibitor(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
+        // Inhibitors only take effect while their surface has keyboard focus,
+        // so they can be activated right away
+        inhibitor.activate();
+        self.input_state
+            .keyboard_shortcuts_inhibitors
+            .insert(inhibitor.wl_surface().clone(), inhibitor);
+    }
+
+    fn inhibitor_destroyed(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
+        self.input_state
+            .keyboard_shortcuts_inhibitors
+            .remove(inhibitor.wl_surface());
+    }
+}
+delegate_keyboard_shortcuts_inhibit!(State);
+
+impl InputMethodHandler for State {
+    fn new_popup(&mut self, surface: InputMethodPopupSurface) {
+        if let Err(err) = self
+            .popup_manager
+            .track_popup(PopupKind::InputMethod(surface))
+        {
+            warn!("Failed to track input method popup: {err}");
+        }
+    }
+
+    fn dismiss_popup(&mut self, surface: InputMethodPopupSurface) {
+        if let Some(parent) = surface.get_parent().map(|parent| parent.surface.clone()) {
+            let _ = PopupManager::dismiss_popup(&parent, &PopupKind::InputMethod(surface));
+        }
+    }
+
+    // Input method popups are placed relative to the text cursor rectangle, which is
+    // relative to the parent's geometry. They're tracked in the popup manager, so they are
+    // rendered along with their parent window or layer surface.
+    fn parent_geometry(&self, parent: &WlSurface) -> Rectangle<i32, Logical> {
+        if let Some(window) = self.window_for_surface(parent) {
+            return window.geometry();
+        }
+
+        self.space
+            .outputs()
+            .find_map(|op| {
+                let layer_map = layer_map_for_output(op);
+                let layer = layer_map.layer_for_surface(parent, WindowSurfaceType::TOPLEVEL)?;
+                layer_map
+                    .layer_geometry(layer)
+                    .map(|geo| Rectangle::from_loc_and_size((0, 0), geo.size))
+            })
+            .unwrap_or_default()
+    }
+}
+delegate_input_method_manager!(State);
+
+delegate_text_input_manager!(State);
+
 delegate_presentation!(State);
 
 impl WlrLayerShellHandler for State {
@@ -566,6 +716,50 @@ impl ScreencopyHandler for State {
 }
 delegate_screencopy!(State);
 
+impl ToplevelExportHandler for State {
+    fn toplevel_for_handle(&mut self, handle: u32) -> Option<WlSurface> {
+        WindowId(handle).window(self)?.wl_surface()
+    }
+
+    fn toplevel_export_size(&mut self, toplevel: &WlSurface) -> Option<Size<i32, Physical>> {
+        let window = self.window_for_surface(toplevel)?;
+        // Capture-hidden windows can't be exported
+        if window.with_state(|state| state.capture_hidden) {
+            return None;
+        }
+        let scale = self.window_capture_scale(&window);
+        Some(window_capture_size(&window, scale))
+    }
+
+    fn frame(&mut self, frame: ToplevelExport) {
+        let Some(window) = self
+            .window_for_surface(frame.toplevel())
+            .filter(|win| !win.with_state(|state| state.capture_hidden))
+        else {
+            return;
+        };
+
+        if !frame.with_damage() {
+            self.submit_toplevel_export(&window, frame);
+            return;
+        }
+
+        window.with_state_mut(|state| state.pending_toplevel_exports.push(frame));
+
+        // Windows on inactive tags aren't sent frame callbacks when rendering,
+        // so send one here to get them to commit new content.
+        if !window.is_on_active_tag() {
+            if let Some(output) = window
+                .output(self)
+                .or_else(|| self.focused_output().cloned())
+            {
+                window.send_frame(&output, self.clock.now(), Some(Duration::ZERO), |_, _| None);
+            }
+        }
+    }
+}
+delegate_toplevel_export!(State);
+
 impl GammaControlHandler for State {
     fn gamma_control_manager_state(&mut self) -> &mut GammaControlManagerState {
         &mut self.gamma_control_manager_state
@@ -617,7 +811,384 @@ impl GammaControlHandler for State {
 }
 delegate_gamma_control!(State);
 
+impl SessionLockHandler for State {
+    fn lock_state(&mut self) -> &mut SessionLockManagerState {
+        &mut self.session_lock_manager_state
+    }
+
+    fn lock(&mut self, confirmation: SessionLocker) {
+        // Only allow a new lock to replace the current one if the old locker died.
+        // Dropping `confirmation` without locking sends `finished` to the client.
+        if self.lock_state.is_locked() && self.has_live_locker() {
+            warn!("Session is already locked, rejecting new lock");
+            return;
+        }
+
+        info!("Locking session");
+
+        self.lock_state = LockState::Locked;
+
+        for output in self.space.outputs().cloned().collect::<Vec<_>>() {
+            output.with_state_mut(|state| state.lock_surface.take());
+            self.schedule_render(&output);
+        }
+
+        self.focus_lock_surface();
+
+        confirmation.lock();
+    }
+
+    fn unlock(&mut self) {
+        info!("Unlocking session");
+
+        self.lock_state = LockState::Unlocked;
+
+        for output in self.space.outputs().cloned().collect::<Vec<_>>() {
+            output.with_state_mut(|state| state.lock_surface.take());
+            self.schedule_render(&output);
+        }
+
+        if let Some(output) = self.focused_output().cloned() {
+            self.update_focus(&output);
+        }
+    }
+
+    fn new_surface(&mut self, surface: LockSurface, output: WlOutput) {
+        let Some(output) = Output::from_resource(&output) else {
+            warn!("New lock surface, but its output doesn't exist");
+            return;
+        };
+
+        output.with_state_mut(|state| state.lock_surface.replace(surface));
+
+        self.reconfigure_lock_surface(&output);
+        self.focus_lock_surface();
+        self.schedule_render(&output);
+    }
+}
+delegate_session_lock!(State);
+
+impl XdgDecorationHandler for State {
+    fn new_decoration(&mut self, toplevel: ToplevelSurface) {
+        let mode = self
+            .forced_decoration_mode(&toplevel)
+            .unwrap_or(DecorationMode::ServerSide);
+
+        toplevel.with_pending_state(|state| {
+            state.decoration_mode = Some(mode);
+        });
+    }
+
+    fn request_mode(&mut self, toplevel: ToplevelSurface, mode: DecorationMode) {
+        let mode = self.forced_decoration_mode(&toplevel).unwrap_or(mode);
+
+        toplevel.with_pending_state(|state| {
+            state.decoration_mode = Some(mode);
+        });
+
+        if initial_configure_sent(&toplevel) {
+            toplevel.send_pending_configure();
+        }
+    }
+
+    fn unset_mode(&mut self, toplevel: ToplevelSurface) {
+        let mode = self
+            .forced_decoration_mode(&toplevel)
+            .unwrap_or(DecorationMode::ServerSide);
+
+        toplevel.with_pending_state(|state| {
+            state.decoration_mode = Some(mode);
+        });
+
+        if initial_configure_sent(&toplevel) {
+            toplevel.send_pending_configure();
+        }
+    }
+}
+delegate_xdg_decoration!(State);
+
+impl State {
+    /// Get the decoration mode forced on the window with `toplevel`, if any.
+    fn forced_decoration_mode(&self, toplevel: &ToplevelSurface) -> Option<DecorationMode> {
+        let surface = toplevel.wl_surface();
+        self.window_for_surface(surface)
+            .or_else(|| self.new_window_for_surface(surface))
+            .and_then(|win| win.with_state(|state| state.decoration_mode))
+            .map(Into::into)
+    }
+}
+
+impl ForeignToplevelHandler for State {
+    fn foreign_toplevel_manager_state(&mut self) -> &mut ForeignToplevelManagerState {
+        &mut self.foreign_toplevel_manager_state
+    }
+
+    fn activate(&mut self, wl_surface: WlSurface) {
+        // Don't let clients move focus off the lock surface
+        if self.lock_state.is_locked() {
+            return;
+        }
+
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        // Switch to the window's tag if it isn't visible
+        if !window.is_on_active_tag() {
+            let Some(tag) = window.with_state(|state| state.tags.first().cloned()) else {
+                return;
+            };
+
+            self.switch_to_tag(&tag);
+        }
+
+        self.activate_window(window);
+    }
+
+    fn close(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        window.close();
+    }
+
+    fn set_fullscreen(&mut self, wl_surface: WlSurface, _wl_output: Option<WlOutput>) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        if !window.with_state(|state| state.fullscreen_or_maximized.is_fullscreen()) {
+            window.toggle_fullscreen();
+        }
+
+        let Some(output) = window.output(self) else { return };
+        self.request_layout(&output);
+        self.schedule_render(&output);
+    }
+
+    fn unset_fullscreen(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        if window.with_state(|state| state.fullscreen_or_maximized.is_fullscreen()) {
+            window.toggle_fullscreen();
+        }
+
+        let Some(output) = window.output(self) else { return };
+        self.request_layout(&output);
+        self.schedule_render(&output);
+    }
+
+    fn set_maximized(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        if !window.with_state(|state| state.fullscreen_or_maximized.is_maximized()) {
+            window.toggle_maximized();
+        }
+
+        let Some(output) = window.output(self) else { return };
+        self.request_layout(&output);
+        self.schedule_render(&output);
+    }
+
+    fn unset_maximized(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        if window.with_state(|state| state.fullscreen_or_maximized.is_maximized()) {
+            window.toggle_maximized();
+        }
+
+        let Some(output) = window.output(self) else { return };
+        self.request_layout(&output);
+        self.schedule_render(&output);
+    }
+
+    fn set_minimized(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        self.set_window_minimized(&window, true);
+    }
+
+    fn unset_minimized(&mut self, wl_surface: WlSurface) {
+        let Some(window) = self.window_for_surface(&wl_surface) else {
+            return;
+        };
+
+        self.set_window_minimized(&window, false);
+    }
+}
+delegate_foreign_toplevel!(State);
+
+impl ExtWorkspaceHandler for State {
+    fn ext_workspace_manager_state(&mut self) -> &mut ExtWorkspaceManagerState {
+        &mut self.ext_workspace_manager_state
+    }
+
+    fn activate_workspace(&mut self, id: u32) {
+        let Some(tag) = TagId(id).tag(self) else {
+            return;
+        };
+        self.switch_to_tag(&tag);
+    }
+
+    fn deactivate_workspace(&mut self, id: u32) {
+        let Some(tag) = TagId(id).tag(self) else {
+            return;
+        };
+        self.set_tag_active(&tag, false);
+    }
+}
+delegate_ext_workspace!(State);
+
+impl OutputManagementHandler for State {
+    fn output_management_manager_state(&mut self) -> &mut OutputManagementManagerState {
+        &mut self.output_management_manager_state
+    }
+
+    fn apply_configuration(
+        &mut self,
+        config: HashMap<Output, OutputConfiguration>,
+        test_only: bool,
+    ) -> bool {
+        self.apply_output_configuration(config, test_only)
+    }
+}
+delegate_output_management!(State);
+
+impl SecurityContextHandler for State {
+    fn context_created(
+        &mut self,
+        source: SecurityContextListenerSource,
+        security_context: SecurityContext,
+    ) {
+        let res = self
+            .loop_handle
+            .insert_source(source, move |client_stream, _, state| {
+                let client_state = ClientState {
+                    security_context: Some(security_context.clone()),
+                    ..Default::default()
+                };
+                if let Err(err) = state
+                    .display_handle
+                    .insert_client(client_stream, Arc::new(client_state))
+                {
+                    warn!("Failed to insert sandboxed client: {err}");
+                }
+            });
+        if let Err(err) = res {
+            error!("Failed to insert security context listener into loop: {err}");
+        }
+    }
+}
+delegate_security_context!(State);
+
+impl IdleNotifierHandler for State {
+    fn idle_notifier_state(&mut self) -> &mut IdleNotifierState<Self> {
+        &mut self.idle_notifier_state
+    }
+}
+delegate_idle_notify!(State);
+
+impl IdleInhibitHandler for State {
+    fn inhibit(&mut self, surface: WlSurface) {
+        self.idle_state.add_inhibitor(surface);
+        self.refresh_idle_inhibit();
+    }
+
+    fn uninhibit(&mut self, surface: WlSurface) {
+        self.idle_state.remove_inhibitor(&surface);
+        self.refresh_idle_inhibit();
+    }
+}
+delegate_idle_inhibit!(State);
+
 impl State {
+    /// Send any changes to outputs to output management clients.
+    pub fn update_output_management(&mut self) {
+        let outputs = self
+            .space
+            .outputs()
+            .map(|op| (op.clone(), true))
+            .chain(self.disabled_outputs.iter().map(|op| (op.clone(), false)))
+            .collect::<Vec<_>>();
+        self.output_management_manager_state
+            .update::<State>(&outputs);
+    }
+
+    /// Send any changes to windows to foreign toplevel clients.
+    pub fn update_foreign_toplevels(&mut self) {
+        let focused_window = self
+            .focused_output()
+            .and_then(|output| self.focused_window(output));
+
+        let toplevels = self
+            .windows
+            .iter()
+            .filter(|win| !win.is_x11_override_redirect())
+            .filter_map(|win| {
+                let surface = win.wl_surface()?;
+                let (maximized, minimized, fullscreen) = win.with_state(|state| {
+                    (
+                        state.fullscreen_or_maximized.is_maximized(),
+                        state.minimized,
+                        state.fullscreen_or_maximized.is_fullscreen(),
+                    )
+                });
+                let info = ForeignToplevelInfo {
+                    title: win.title(),
+                    app_id: win.class(),
+                    output: win.output(self),
+                    activated: focused_window.as_ref() == Some(win),
+                    maximized,
+                    minimized,
+                    fullscreen,
+                };
+                Some((surface, info))
+            })
+            .collect::<Vec<_>>();
+
+        self.foreign_toplevel_manager_state
+            .refresh::<Self>(toplevels);
+    }
+
+    /// Advertise each output's tags as ext-workspace workspaces.
+    pub fn update_ext_workspaces(&mut self) {
+        let outputs = self.space.outputs().cloned().collect::<Vec<_>>();
+
+        let workspaces = outputs
+            .iter()
+            .flat_map(|output| {
+                output
+                    .with_state(|state| state.tags.clone())
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(index, tag)| (output.clone(), index, tag))
+            })
+            .map(|(output, index, tag)| {
+                let urgent = self.is_tag_urgent(&tag);
+                let info = WorkspaceInfo {
+                    name: tag.name(),
+                    output,
+                    index: index as u32,
+                    active: tag.active(),
+                    urgent,
+                };
+                (tag.id().0, info)
+            })
+            .collect::<Vec<_>>();
+
+        self.ext_workspace_manager_state
+            .refresh::<Self>(outputs, workspaces);
+    }
+
     fn position_popup(&self, popup: &PopupSurface) {
         trace!("State::position_popup");
         let Ok(root) = find_popup_root_surface(&PopupKind::Xdg(popup.clone())) else {
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use smithay::{
+    delegate_xdg_activation,
+    desktop::{layer_map_for_output, WindowSurfaceType},
+    output::Output,
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    wayland::{
+        seat::WaylandFocus,
+        xdg_activation::{
+            XdgActivationHandler, XdgActivationState, XdgActivationToken, XdgActivationTokenData,
+        },
+    },
+};
+use tracing::debug;
+
+use crate::{focus::ActivationPolicy, state::State};
+
+/// How long an activation token stays valid after being created.
+const ACTIVATION_TOKEN_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl XdgActivationHandler for State {
+    fn activation_state(&mut self) -> &mut XdgActivationState {
+        &mut self.xdg_activation_state
+    }
+
+    fn request_activation(
+        &mut self,
+        token: XdgActivationToken,
+        token_data: XdgActivationTokenData,
+        surface: WlSurface,
+    ) {
+        if token_data.timestamp.elapsed() >= ACTIVATION_TOKEN_TIMEOUT {
+            debug!("Ignoring activation request with an expired token");
+            return;
+        }
+
+        let launch_output = self
+            .activation_token_outputs
+            .get(&token)
+            .cloned()
+            .or_else(|| {
+                token_data
+                    .surface
+                    .as_ref()
+                    .and_then(|surface| self.output_for_activation_surface(surface))
+            });
+
+        let Some(window) = self.window_for_surface(&surface) else {
+            // Apply the policy once the window maps, see `take_pending_activation`
+            if self
+                .new_windows
+                .iter()
+                .any(|win| win.wl_surface().as_ref() == Some(&surface))
+            {
+                self.pending_activations.insert(surface, launch_output);
+            }
+            return;
+        };
+
+        if self.activation_allows_focus(launch_output.as_ref()) && window.is_on_active_tag() {
+            self.activate_window(window);
+        } else {
+            self.set_window_urgent(&window);
+        }
+    }
+}
+delegate_xdg_activation!(State);
+
+impl State {
+    /// Create an activation token for a process the compositor is about to spawn.
+    ///
+    /// The currently focused output is remembered so windows of the process can be
+    /// checked against it when they request activation.
+    pub fn create_spawn_activation_token(&mut self) -> String {
+        self.xdg_activation_state
+            .retain_tokens(|_, data| data.timestamp.elapsed() < ACTIVATION_TOKEN_TIMEOUT);
+        let activation_state = &self.xdg_activation_state;
+        self.activation_token_outputs
+            .retain(|token, _| activation_state.data_for_token(token).is_some());
+
+        let (token, _) = self.xdg_activation_state.create_external_token(None);
+        let token = token.clone();
+
+        if let Some(output) = self.focused_output().cloned() {
+            self.activation_token_outputs.insert(token.clone(), output);
+        }
+
+        token.to_string()
+    }
+
+    /// Returns whether a window that was mapped with `surface` should be focused.
+    ///
+    /// New windows are focused when they map unless they requested activation beforehand
+    /// and the activation policy doesn't allow it, in which case they are marked urgent
+    /// by the caller instead.
+    pub fn take_pending_activation(&mut self, surface: &WlSurface) -> bool {
+        match self.pending_activations.remove(surface) {
+            Some(launch_output) => self.activation_allows_focus(launch_output.as_ref()),
+            None => true,
+        }
+    }
+
+    /// Returns whether the activation policy allows focusing a window launched
+    /// from `launch_output`.
+    fn activation_allows_focus(&self, launch_output: Option<&Output>) -> bool {
+        match self.config.activation_policy {
+            ActivationPolicy::Focus => true,
+            ActivationPolicy::FocusSameOutput => {
+                launch_output.is_some() && launch_output == self.focused_output()
+            }
+            ActivationPolicy::Urgent => false,
+        }
+    }
+
+    /// Get the output the window or layer surface with `surface` is on.
+    fn output_for_activation_surface(&self, surface: &WlSurface) -> Option<Output> {
+        if let Some(window) = self.window_for_surface(surface) {
+            return window.output(self);
+        }
+
+        self.space
+            .outputs()
+            .find(|op| {
+                layer_map_for_output(op)
+                    .layer_for_surface(surface, WindowSurfaceType::TOPLEVEL)
+                    .is_some()
+            })
+            .cloned()
+    }
+}
@@ -48,6 +48,14 @@ impl XdgShellHandler for State {
 
     fn toplevel_destroyed(&mut self, surface: ToplevelSurface) {
         tracing::debug!("toplevel destroyed");
+
+        self.pending_activations.remove(surface.wl_surface());
+
+        if let Some(window) = self.window_for_surface(surface.wl_surface()) {
+            self.remove_from_group(&window);
+            self.restore_swallowed(&window);
+        }
+
         self.windows.retain(|window| {
             window
                 .wl_surface()
@@ -311,11 +319,12 @@ impl XdgShellHandler for State {
         self.request_layout(&output);
     }
 
-    fn minimize_request(&mut self, _surface: ToplevelSurface) {
-        // TODO:
-        // if let Some(window) = self.window_for_surface(surface.wl_surface()) {
-        //     self.space.unmap_elem(&window);
-        // }
+    fn minimize_request(&mut self, surface: ToplevelSurface) {
+        let Some(window) = self.window_for_surface(surface.wl_surface()) else {
+            return;
+        };
+
+        self.set_window_minimized(&window, true);
     }
 
     // TODO: impl the rest of the fns in XdgShellHandler
@@ -18,7 +18,7 @@ use smithay::{
         },
     },
     xwayland::{
-        xwm::{Reorder, WmWindowType, XwmId},
+        xwm::{Reorder, WmWindowProperty, WmWindowType, XwmId},
         X11Surface, X11Wm, XwmHandler,
     },
 };
@@ -104,6 +104,7 @@ impl XwmHandler for State {
         self.raise_window(window.clone(), true);
 
         self.apply_window_rules(&window);
+        self.swallow_parent(&window);
 
         if let Some(output) = window.output(self) {
             output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
@@ -168,13 +169,17 @@ impl XwmHandler for State {
             });
         }
 
+        // Hidden windows like inactive group members aren't in the space
         let win = self
-            .space
-            .elements()
+            .windows
+            .iter()
             .find(|elem| matches!(elem.x11_surface(), Some(surf) if surf == &surface))
             .cloned();
 
         if let Some(win) = win {
+            self.remove_from_group(&win);
+            self.restore_swallowed(&win);
+
             self.windows
                 .retain(|elem| win.wl_surface() != elem.wl_surface());
             self.z_index_stack
@@ -236,6 +241,9 @@ impl XwmHandler for State {
         if let Some(win) = win {
             debug!("removing x11 window from windows");
 
+            self.remove_from_group(&win);
+            self.restore_swallowed(&win);
+
             // INFO: comparing the windows doesn't work so wlsurface it is
             // self.windows.retain(|elem| &win != elem);
             self.windows
@@ -408,6 +416,43 @@ impl XwmHandler for State {
         }
     }
 
+    fn minimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        let Some(window) = window
+            .wl_surface()
+            .and_then(|surf| self.window_for_surface(&surf))
+        else {
+            return;
+        };
+
+        self.set_window_minimized(&window, true);
+    }
+
+    fn unminimize_request(&mut self, _xwm: XwmId, window: X11Surface) {
+        let Some(window) = window
+            .wl_surface()
+            .and_then(|surf| self.window_for_surface(&surf))
+        else {
+            return;
+        };
+
+        self.set_window_minimized(&window, false);
+    }
+
+    fn property_notify(&mut self, _xwm: XwmId, window: X11Surface, property: WmWindowProperty) {
+        if property != WmWindowProperty::Hints {
+            return;
+        }
+
+        let Some(window) = window
+            .wl_surface()
+            .and_then(|surf| self.window_for_surface(&surf))
+        else {
+            return;
+        };
+
+        self.update_x11_urgency(&window);
+    }
+
     fn resize_request(
         &mut self,
         _xwm: XwmId,
@@ -533,6 +578,31 @@ impl State {
             }
         }
     }
+
+    /// Mark an X11 window urgent or not according to the urgency hint in its `WM_HINTS`.
+    ///
+    /// Focused windows aren't marked urgent.
+    fn update_x11_urgency(&mut self, window: &WindowElement) {
+        let Some(surface) = window.x11_surface() else {
+            return;
+        };
+
+        let urgent = surface.hints().is_some_and(|hints| hints.urgent);
+
+        if !urgent {
+            self.unset_window_urgent(window);
+            return;
+        }
+
+        let is_focused = window
+            .output(self)
+            .and_then(|output| self.focused_window(&output))
+            .is_some_and(|focused| &focused == window);
+
+        if !is_focused {
+            self.set_window_urgent(window);
+        }
+    }
 }
 
 /// Make assumptions on whether or not the surface should be floating.
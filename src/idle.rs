@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use pinnacle_api_defs::pinnacle::signal::v0alpha1::{IdleEndResponse, IdleStartResponse};
+use smithay::{
+    desktop::{layer_map_for_output, WindowSurfaceType},
+    reexports::{
+        calloop::{
+            timer::{TimeoutAction, Timer},
+            RegistrationToken,
+        },
+        wayland_server::protocol::wl_surface::WlSurface,
+    },
+    utils::IsAlive,
+    wayland::{compositor, seat::WaylandFocus},
+};
+use tracing::error;
+
+use crate::state::State;
+
+/// State for the compositor's own idle tracking.
+///
+/// This is separate from ext-idle-notify, which tracks idle timeouts per client.
+/// The compositor's idle timeout is set by the config and drives the idle signals.
+#[derive(Debug)]
+pub struct IdleState {
+    /// How long the seat must be inactive before it becomes idle.
+    ///
+    /// `None` means the seat never becomes idle.
+    timeout: Option<Duration>,
+    last_activity: Instant,
+    timer_token: Option<RegistrationToken>,
+    is_idle: bool,
+    /// Surfaces that currently hold an idle inhibitor
+    inhibitors: HashSet<WlSurface>,
+    is_inhibited: bool,
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            last_activity: Instant::now(),
+            timer_token: None,
+            is_idle: false,
+            inhibitors: HashSet::new(),
+            is_inhibited: false,
+        }
+    }
+}
+
+impl IdleState {
+    /// Whether or not the seat is currently idle.
+    pub fn is_idle(&self) -> bool {
+        self.is_idle
+    }
+
+    /// Whether or not idling is currently inhibited by a visible surface.
+    pub fn is_inhibited(&self) -> bool {
+        self.is_inhibited
+    }
+
+    pub fn add_inhibitor(&mut self, surface: WlSurface) {
+        self.inhibitors.insert(surface);
+    }
+
+    pub fn remove_inhibitor(&mut self, surface: &WlSurface) {
+        self.inhibitors.remove(surface);
+    }
+}
+
+impl State {
+    /// Notify idle trackers of user activity.
+    ///
+    /// This resets every idle timeout and ends the idle state if the seat was idle.
+    pub fn notify_activity(&mut self) {
+        self.idle_notifier_state.notify_activity(&self.seat);
+
+        self.idle_state.last_activity = Instant::now();
+
+        if self.idle_state.is_idle {
+            self.idle_state.is_idle = false;
+            self.signal_state
+                .idle_end
+                .signal(|buffer| buffer.push_back(IdleEndResponse {}));
+        }
+    }
+
+    /// Set the compositor's idle timeout.
+    ///
+    /// Passing `None` disables the compositor's idle tracking.
+    /// This does not affect idle timeouts requested by ext-idle-notify clients.
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        if let Some(token) = self.idle_state.timer_token.take() {
+            self.loop_handle.remove(token);
+        }
+
+        self.idle_state.timeout = timeout;
+        self.idle_state.last_activity = Instant::now();
+        self.idle_state.is_idle = false;
+
+        let Some(timeout) = timeout else {
+            return;
+        };
+
+        let res = self
+            .loop_handle
+            .insert_source(Timer::from_duration(timeout), |_instant, _, state| {
+                state.on_idle_timer()
+            });
+
+        match res {
+            Ok(token) => self.idle_state.timer_token = Some(token),
+            Err(err) => error!("Failed to insert idle timer into event loop: {err}"),
+        }
+    }
+
+    fn on_idle_timer(&mut self) -> TimeoutAction {
+        let Some(timeout) = self.idle_state.timeout else {
+            self.idle_state.timer_token = None;
+            return TimeoutAction::Drop;
+        };
+
+        if self.idle_state.is_inhibited {
+            self.idle_state.last_activity = Instant::now();
+            return TimeoutAction::ToDuration(timeout);
+        }
+
+        let elapsed = self.idle_state.last_activity.elapsed();
+        if elapsed < timeout {
+            return TimeoutAction::ToDuration(timeout - elapsed);
+        }
+
+        if !self.idle_state.is_idle {
+            self.idle_state.is_idle = true;
+            self.signal_state
+                .idle_start
+                .signal(|buffer| buffer.push_back(IdleStartResponse {}));
+        }
+
+        TimeoutAction::ToDuration(timeout)
+    }
+
+    /// Recompute whether idling is inhibited.
+    ///
+    /// Idling is inhibited when any surface holding an idle inhibitor is visible,
+    /// that is, it belongs to a mapped window on an active tag or to a layer surface.
+    pub fn refresh_idle_inhibit(&mut self) {
+        self.idle_state.inhibitors.retain(|surface| surface.alive());
+
+        let is_inhibited = self
+            .idle_state
+            .inhibitors
+            .iter()
+            .any(|surface| self.is_surface_visible(surface));
+
+        if is_inhibited == self.idle_state.is_inhibited {
+            return;
+        }
+
+        self.idle_state.is_inhibited = is_inhibited;
+        self.idle_notifier_state.set_is_inhibited(is_inhibited);
+
+        if is_inhibited {
+            self.notify_activity();
+        }
+    }
+
+    fn is_surface_visible(&self, surface: &WlSurface) -> bool {
+        let mut root = surface.clone();
+        while let Some(parent) = compositor::get_parent(&root) {
+            root = parent;
+        }
+
+        let window_visible = self
+            .space
+            .elements()
+            .any(|win| win.wl_surface().as_ref() == Some(&root) && win.is_on_active_tag());
+
+        window_visible
+            || self.space.outputs().any(|op| {
+                layer_map_for_output(op)
+                    .layer_for_surface(&root, WindowSurfaceType::TOPLEVEL)
+                    .is_some()
+            })
+    }
+}
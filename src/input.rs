@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+pub mod constraints;
 pub mod libinput;
+pub mod virtual_input;
 
-use std::{collections::HashMap, mem::Discriminant, time::Duration};
+use std::{collections::HashMap, mem::Discriminant, sync::Arc, time::Duration};
 
 use crate::{
     focus::{keyboard::KeyboardFocusTarget, pointer::PointerFocusTarget},
@@ -18,15 +20,21 @@ use smithay::{
         AbsolutePositionEvent, Axis, AxisSource, ButtonState, Event, InputBackend, InputEvent,
         KeyState, KeyboardKeyEvent, PointerAxisEvent, PointerButtonEvent, PointerMotionEvent,
     },
-    desktop::{layer_map_for_output, space::SpaceElement, WindowSurfaceType},
+    desktop::{
+        layer_map_for_output, space::SpaceElement, utils::under_from_surface_tree,
+        WindowSurfaceType,
+    },
     input::{
-        keyboard::{keysyms, FilterResult, ModifiersState},
+        keyboard::{keysyms, FilterResult, ModifiersState, XkbConfig},
         pointer::{AxisFrame, ButtonEvent, MotionEvent, RelativeMotionEvent},
     },
     reexports::input::{self, Led},
+    reexports::wayland_server::protocol::wl_surface::WlSurface,
     utils::{IsAlive, Logical, Point, SERIAL_COUNTER},
     wayland::{
         compositor,
+        keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitor,
+        seat::WaylandFocus,
         shell::wlr_layer::{self, KeyboardInteractivity, LayerSurfaceCachedState},
     },
 };
@@ -88,6 +96,8 @@ impl From<&ModifiersState> for ModifierMask {
 pub struct InputState {
     pub reload_keybind: Option<(ModifierMask, Keysym)>,
     pub kill_keybind: Option<(ModifierMask, Keysym)>,
+    /// The keybind that toggles the focused surface's keyboard shortcuts inhibitor
+    pub inhibit_escape_keybind: Option<(ModifierMask, Keysym)>,
     /// All libinput devices that have been connected
     pub libinput_devices: Vec<input::Device>,
 
@@ -104,12 +114,26 @@ pub struct InputState {
     /// surfaces. When used, the first item is the previous focus before there were any
     /// exclusive layer surfaces.
     exclusive_layer_focus_stack: Vec<KeyboardFocusTarget>,
+
+    /// The xkb config set through the API.
+    ///
+    /// This is kept so the seat keymap can be restored after a virtual keyboard
+    /// typed with its own.
+    pub xkb_settings: XkbSettings,
+    /// The keymap of the virtual keyboard that last typed, while the seat keyboard uses it.
+    pub virtual_keymap: Option<Arc<str>>,
+
+    /// Keyboard shortcuts inhibitors, keyed by the surface that requested them.
+    ///
+    /// These are client state, so they aren't cleared on config reload.
+    pub keyboard_shortcuts_inhibitors: HashMap<WlSurface, KeyboardShortcutsInhibitor>,
 }
 
 impl InputState {
     pub fn clear(&mut self) {
         self.reload_keybind = None;
         self.kill_keybind = None;
+        self.inhibit_escape_keybind = None;
         self.libinput_devices.clear();
         self.keybinds.clear();
         self.mousebinds.clear();
@@ -122,6 +146,7 @@ impl std::fmt::Debug for InputState {
         f.debug_struct("InputState")
             .field("reload_keybind", &self.reload_keybind)
             .field("kill_keybind", &self.kill_keybind)
+            .field("inhibit_escape_keybind", &self.inhibit_escape_keybind)
             .field("libinput_devices", &self.libinput_devices)
             .field("keybinds", &self.keybinds)
             .field("mousebinds", &self.mousebinds)
@@ -134,6 +159,48 @@ impl InputState {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Get the action of the compositor keybind `keybind` triggers, if any.
+    ///
+    /// While keyboard shortcuts are inhibited, only the kill and inhibit escape
+    /// keybinds fire.
+    fn builtin_key_action(
+        &self,
+        keybind: (ModifierMask, Keysym),
+        shortcuts_inhibited: bool,
+    ) -> Option<KeyAction> {
+        if self.kill_keybind == Some(keybind) {
+            Some(KeyAction::Quit)
+        } else if self.inhibit_escape_keybind == Some(keybind) {
+            Some(KeyAction::ToggleShortcutsInhibit)
+        } else if !shortcuts_inhibited && self.reload_keybind == Some(keybind) {
+            Some(KeyAction::ReloadConfig)
+        } else {
+            None
+        }
+    }
+}
+
+/// An owned [`XkbConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct XkbSettings {
+    pub rules: String,
+    pub model: String,
+    pub layout: String,
+    pub variant: String,
+    pub options: Option<String>,
+}
+
+impl XkbSettings {
+    pub fn as_xkb_config(&self) -> XkbConfig<'_> {
+        XkbConfig {
+            rules: &self.rules,
+            model: &self.model,
+            layout: &self.layout,
+            variant: &self.variant,
+            options: self.options.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -142,16 +209,27 @@ enum KeyAction {
     Quit,
     SwitchVt(i32),
     ReloadConfig,
+    ToggleShortcutsInhibit,
 }
 
 impl State {
     pub fn process_input_event<B: InputBackend>(&mut self, event: InputEvent<B>) {
+        if !matches!(
+            event,
+            InputEvent::DeviceAdded { .. } | InputEvent::DeviceRemoved { .. }
+        ) {
+            self.notify_activity();
+        }
+
         match event {
             // TODO: rest of input events
 
             // InputEvent::DeviceAdded { device } => todo!(),
             // InputEvent::DeviceRemoved { device } => todo!(),
-            InputEvent::Keyboard { event } => self.keyboard::<B>(event),
+            InputEvent::Keyboard { event } => {
+                self.restore_seat_keymap();
+                self.keyboard::<B>(event)
+            }
             InputEvent::PointerMotion { event } => self.pointer_motion::<B>(event),
             InputEvent::PointerMotionAbsolute { event } => self.pointer_motion_absolute::<B>(event),
             InputEvent::PointerButton { event } => self.pointer_button::<B>(event),
@@ -161,6 +239,20 @@ impl State {
         }
     }
 
+    /// Get the keyboard shortcuts inhibitor of the surface with keyboard focus, if any.
+    fn focused_shortcuts_inhibitor(&self) -> Option<KeyboardShortcutsInhibitor> {
+        let surface = self
+            .seat
+            .get_keyboard()?
+            .current_focus()
+            .and_then(|focus| focus.wl_surface())?;
+
+        self.input_state
+            .keyboard_shortcuts_inhibitors
+            .get(&surface)
+            .cloned()
+    }
+
     /// Get the [`PointerFocusTarget`] under `point` along with its origin in the global space.
     pub fn pointer_focus_target_under<P>(
         &self,
@@ -183,6 +275,18 @@ impl State {
             .output_geometry(output)
             .expect("called output_geometry on unmapped output");
 
+        // Only the lock surface may receive pointer input while the session is locked
+        if self.lock_state.is_locked() {
+            let lock_surface = self.lock_surface_for_output(output)?;
+            return under_from_surface_tree(
+                lock_surface.wl_surface(),
+                point,
+                output_geo.loc,
+                WindowSurfaceType::ALL,
+            )
+            .map(|(surf, surf_loc)| (PointerFocusTarget::WlSurface(surf), surf_loc));
+        }
+
         let mut fullscreen_and_up_split_at = 0;
 
         for (i, win) in self
@@ -302,9 +406,6 @@ impl State {
         let time = event.time_msec();
         let press_state = event.state();
 
-        let reload_keybind = self.input_state.reload_keybind;
-        let kill_keybind = self.input_state.kill_keybind;
-
         let keyboard = self.seat.get_keyboard().expect("Seat has no keyboard");
 
         let modifiers = keyboard.modifier_state();
@@ -322,62 +423,78 @@ impl State {
             device.led_update(leds);
         }
 
-        for layer in self.layer_shell_state.layer_surfaces().rev() {
-            let data = compositor::with_states(layer.wl_surface(), |states| {
-                *states.cached_state.current::<LayerSurfaceCachedState>()
-            });
-            if data.keyboard_interactivity == KeyboardInteractivity::Exclusive
-                && matches!(
-                    data.layer,
-                    wlr_layer::Layer::Top | wlr_layer::Layer::Overlay
-                )
-            {
-                let layer_surface = self.space.outputs().find_map(|op| {
-                    let map = layer_map_for_output(op);
-                    let cloned = map.layers().find(|l| l.layer_surface() == &layer).cloned();
-                    cloned
+        // While the session is locked, all keyboard input goes to the lock surface
+        if self.lock_state.is_locked() {
+            // Don't let an input method's keyboard grab see what's typed into the lock surface
+            if keyboard.is_grabbed() {
+                keyboard.unset_grab(self);
+            }
+            self.focus_lock_surface();
+        } else {
+            for layer in self.layer_shell_state.layer_surfaces().rev() {
+                let data = compositor::with_states(layer.wl_surface(), |states| {
+                    *states.cached_state.current::<LayerSurfaceCachedState>()
                 });
+                if data.keyboard_interactivity == KeyboardInteractivity::Exclusive
+                    && matches!(
+                        data.layer,
+                        wlr_layer::Layer::Top | wlr_layer::Layer::Overlay
+                    )
+                {
+                    let layer_surface = self.space.outputs().find_map(|op| {
+                        let map = layer_map_for_output(op);
+                        let cloned = map.layers().find(|l| l.layer_surface() == &layer).cloned();
+                        cloned
+                    });
 
-                if let Some(layer_surface) = layer_surface {
-                    match self.input_state.exclusive_layer_focus_stack.last() {
-                        Some(focus) => {
-                            let layer_focus = KeyboardFocusTarget::LayerSurface(layer_surface);
-                            if &layer_focus != focus {
+                    if let Some(layer_surface) = layer_surface {
+                        match self.input_state.exclusive_layer_focus_stack.last() {
+                            Some(focus) => {
+                                let layer_focus = KeyboardFocusTarget::LayerSurface(layer_surface);
+                                if &layer_focus != focus {
+                                    self.input_state
+                                        .exclusive_layer_focus_stack
+                                        .push(layer_focus);
+                                }
+                            }
+                            // Push the previous focus on as this is the first exclusive layer surface
+                            // on screen. This lets us restore it when that layer surface goes away.
+                            None => {
+                                self.input_state
+                                    .exclusive_layer_focus_stack
+                                    .extend(keyboard.current_focus());
                                 self.input_state
                                     .exclusive_layer_focus_stack
-                                    .push(layer_focus);
+                                    .push(KeyboardFocusTarget::LayerSurface(layer_surface));
                             }
                         }
-                        // Push the previous focus on as this is the first exclusive layer surface
-                        // on screen. This lets us restore it when that layer surface goes away.
-                        None => {
-                            self.input_state
-                                .exclusive_layer_focus_stack
-                                .extend(keyboard.current_focus());
-                            self.input_state
-                                .exclusive_layer_focus_stack
-                                .push(KeyboardFocusTarget::LayerSurface(layer_surface));
-                        }
                     }
                 }
             }
-        }
 
-        while let Some(last) = self.input_state.exclusive_layer_focus_stack.pop() {
-            if last.alive() {
-                // If it's not empty then there's another exclusive layer surface
-                // underneath. Otherwise `last` is the previous keyboard focus
-                // and we don't need the stack anymore.
-                if !self.input_state.exclusive_layer_focus_stack.is_empty() {
-                    self.input_state
-                        .exclusive_layer_focus_stack
-                        .push(last.clone());
+            while let Some(last) = self.input_state.exclusive_layer_focus_stack.pop() {
+                if last.alive() {
+                    // If it's not empty then there's another exclusive layer surface
+                    // underneath. Otherwise `last` is the previous keyboard focus
+                    // and we don't need the stack anymore.
+                    if !self.input_state.exclusive_layer_focus_stack.is_empty() {
+                        self.input_state
+                            .exclusive_layer_focus_stack
+                            .push(last.clone());
+                    }
+                    keyboard.set_focus(self, Some(last), serial);
+                    break;
                 }
-                keyboard.set_focus(self, Some(last), serial);
-                break;
             }
         }
 
+        let shortcuts_inhibited = self
+            .focused_shortcuts_inhibitor()
+            .is_some_and(|inhibitor| inhibitor.is_active());
+
+        // If an input method has grabbed the keyboard, `input` routes keys that aren't
+        // intercepted below to the input method instead of the focused client.
+        // It then sends the text or keys it produces to the focused text input.
         let action = keyboard.input(
             self,
             event.key_code(),
@@ -391,21 +508,31 @@ impl State {
                     let raw_sym = keysym.raw_syms().iter().next();
                     let mod_sym = keysym.modified_sym();
 
-                    if let (Some(sender), _) | (None, Some(sender)) = (
-                        state.input_state.keybinds.get(&(mod_mask, mod_sym)),
-                        raw_sym.and_then(|raw_sym| {
-                            state.input_state.keybinds.get(&(mod_mask, *raw_sym))
-                        }),
-                    ) {
-                        return FilterResult::Intercept(KeyAction::CallCallback(sender.clone()));
+                    // Keybinds don't fire while the session is locked, but VT switching still works
+                    if !state.lock_state.is_locked() {
+                        if !shortcuts_inhibited {
+                            if let (Some(sender), _) | (None, Some(sender)) = (
+                                state.input_state.keybinds.get(&(mod_mask, mod_sym)),
+                                raw_sym.and_then(|raw_sym| {
+                                    state.input_state.keybinds.get(&(mod_mask, *raw_sym))
+                                }),
+                            ) {
+                                return FilterResult::Intercept(KeyAction::CallCallback(
+                                    sender.clone(),
+                                ));
+                            }
+                        }
+
+                        if let Some(action) = state
+                            .input_state
+                            .builtin_key_action((mod_mask, mod_sym), shortcuts_inhibited)
+                        {
+                            return FilterResult::Intercept(action);
+                        }
                     }
 
-                    if kill_keybind == Some((mod_mask, mod_sym)) {
-                        return FilterResult::Intercept(KeyAction::Quit);
-                    } else if reload_keybind == Some((mod_mask, mod_sym)) {
-                        return FilterResult::Intercept(KeyAction::ReloadConfig);
-                    } else if let mut vt @ keysyms::KEY_XF86Switch_VT_1
-                        ..=keysyms::KEY_XF86Switch_VT_12 = keysym.modified_sym().raw()
+                    if let mut vt @ keysyms::KEY_XF86Switch_VT_1..=keysyms::KEY_XF86Switch_VT_12 =
+                        keysym.modified_sym().raw()
                     {
                         vt = vt - keysyms::KEY_XF86Switch_VT_1 + 1;
                         tracing::info!("Switching to vt {vt}");
@@ -432,6 +559,17 @@ impl State {
                 self.start_config(Some(self.config.dir(&self.xdg_base_dirs)))
                     .expect("failed to restart config");
             }
+            Some(KeyAction::ToggleShortcutsInhibit) => {
+                if let Some(inhibitor) = self.focused_shortcuts_inhibitor() {
+                    if inhibitor.is_active() {
+                        info!("Deactivating keyboard shortcuts inhibitor");
+                        inhibitor.inactivate();
+                    } else {
+                        info!("Reactivating keyboard shortcuts inhibitor");
+                        inhibitor.activate();
+                    }
+                }
+            }
             None => (),
         }
     }
@@ -455,10 +593,13 @@ impl State {
             ButtonState::Pressed => set_mousebind_request::MouseEdge::Press,
         };
 
+        let locked = self.lock_state.is_locked();
+
         if let Some(stream) = self
             .input_state
             .mousebinds
             .get(&(mod_mask, button, mouse_edge))
+            .filter(|_| !locked)
         {
             let _ = stream.send(Ok(SetMousebindResponse {}));
             return;
@@ -466,7 +607,9 @@ impl State {
 
         // If the button was clicked, focus on the window below if exists, else
         // unfocus on windows.
-        if button_state == ButtonState::Pressed {
+        //
+        // Focus stays on the lock surface while the session is locked.
+        if button_state == ButtonState::Pressed && !locked {
             if let Some((focus, _)) = self.pointer_focus_target_under(pointer_loc) {
                 // NOTE: *Do not* set keyboard focus to an override redirect window. This leads
                 // |     to wonky things like right-click menus not correctly getting pointer
@@ -596,25 +739,27 @@ impl State {
 
     /// Handle an absolute pointer motion event.
     ///
-    /// This *should* only be generated on the winit backend.
-    /// Unless there's a case where it's generated on udev that I'm unaware of.
+    /// The position is mapped onto the bounding box of all outputs.
+    /// This is generated on the winit backend, which only has one output,
+    /// and by virtual pointers.
     fn pointer_motion_absolute<I: InputBackend>(&mut self, event: I::PointerMotionAbsoluteEvent) {
         let Some(pointer) = self.seat.get_pointer() else {
             tracing::error!("Pointer motion absolute received with no pointer on seat");
             return;
         };
 
-        let Some(output) = self.space.outputs().next() else {
+        let Some(layout_geo) = self.output_layout_geometry() else {
             return;
         };
 
-        let Some(output_geo) = self.space.output_geometry(output) else {
-            unreachable!("output should have a geometry as it was mapped");
-        };
-
-        let pointer_loc = event.position_transformed(output_geo.size) + output_geo.loc.to_f64();
+        let pointer_loc = event.position_transformed(layout_geo.size) + layout_geo.loc.to_f64();
         let serial = SERIAL_COUNTER.next_serial();
 
+        let Some(pointer_loc) = self.constrain_pointer_motion(&pointer, pointer_loc) else {
+            pointer.frame(self);
+            return;
+        };
+
         if let Some(output) = self.space.output_under(pointer_loc).next().cloned() {
             self.output_focus_stack.set_focus(output);
         }
@@ -632,6 +777,9 @@ impl State {
         );
 
         pointer.frame(self);
+
+        let keyboard_focus = self.seat.get_keyboard().and_then(|kb| kb.current_focus());
+        self.update_pointer_constraint(keyboard_focus.as_ref());
     }
 
     fn pointer_motion<I: InputBackend>(&mut self, event: I::PointerMotionEvent) {
@@ -647,21 +795,26 @@ impl State {
         // this event is never generated by winit
         pointer_loc = self.clamp_coords(pointer_loc);
 
-        if let Some(output) = self.space.output_under(pointer_loc).next().cloned() {
-            self.output_focus_stack.set_focus(output);
-        }
+        if let Some(pointer_loc) = self.constrain_pointer_motion(&pointer, pointer_loc) {
+            if let Some(output) = self.space.output_under(pointer_loc).next().cloned() {
+                self.output_focus_stack.set_focus(output);
+            }
 
-        let surface_under = self.pointer_focus_target_under(pointer_loc);
+            let surface_under = self.pointer_focus_target_under(pointer_loc);
+
+            pointer.motion(
+                self,
+                surface_under,
+                &MotionEvent {
+                    location: pointer_loc,
+                    serial: SERIAL_COUNTER.next_serial(),
+                    time: event.time_msec(),
+                },
+            );
+        }
 
-        pointer.motion(
-            self,
-            surface_under.clone(),
-            &MotionEvent {
-                location: pointer_loc,
-                serial: SERIAL_COUNTER.next_serial(),
-                time: event.time_msec(),
-            },
-        );
+        // Relative motion is sent even when the pointer is locked or confined
+        let surface_under = self.pointer_focus_target_under(pointer.current_location());
 
         pointer.relative_motion(
             self,
@@ -675,8 +828,45 @@ impl State {
 
         pointer.frame(self);
 
+        let keyboard_focus = self.seat.get_keyboard().and_then(|kb| kb.current_focus());
+        self.update_pointer_constraint(keyboard_focus.as_ref());
+
         if let Some(output) = self.focused_output().cloned() {
             self.schedule_render(&output);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inhibit_escape_keybind_fires_while_shortcuts_are_inhibited() {
+        let kill = (ModifierMask::CTRL | ModifierMask::ALT, Keysym::Escape);
+        let reload = (ModifierMask::CTRL | ModifierMask::ALT, Keysym::r);
+        let escape = (ModifierMask::SUPER, Keysym::Escape);
+
+        let mut input_state = InputState::new();
+        input_state.kill_keybind = Some(kill);
+        input_state.reload_keybind = Some(reload);
+        input_state.inhibit_escape_keybind = Some(escape);
+
+        for inhibited in [false, true] {
+            assert!(matches!(
+                input_state.builtin_key_action(escape, inhibited),
+                Some(KeyAction::ToggleShortcutsInhibit)
+            ));
+            assert!(matches!(
+                input_state.builtin_key_action(kill, inhibited),
+                Some(KeyAction::Quit)
+            ));
+        }
+
+        assert!(matches!(
+            input_state.builtin_key_action(reload, false),
+            Some(KeyAction::ReloadConfig)
+        ));
+        assert!(input_state.builtin_key_action(reload, true).is_none());
+    }
+}
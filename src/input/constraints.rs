@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use smithay::{
+    backend::renderer::utils::with_renderer_surface_state,
+    input::pointer::PointerHandle,
+    utils::{Logical, Point, Rectangle},
+    wayland::{
+        compositor::RectangleKind,
+        pointer_constraints::{with_pointer_constraint, PointerConstraint},
+        seat::WaylandFocus,
+    },
+};
+
+use crate::{focus::keyboard::KeyboardFocusTarget, state::State};
+
+impl State {
+    /// Get where the pointer moves to when it moves towards `new_loc`,
+    /// given the active constraint on the surface under it, if any.
+    ///
+    /// Locked pointers may not move at all, so this returns `None` for them.
+    /// Confined pointers are clamped to the closest point inside their surface's
+    /// confinement region so they slide along its edges.
+    pub fn constrain_pointer_motion(
+        &self,
+        pointer: &PointerHandle<State>,
+        new_loc: Point<f64, Logical>,
+    ) -> Option<Point<f64, Logical>> {
+        let current_loc = pointer.current_location();
+
+        let Some((target, surface_loc)) = self.pointer_focus_target_under(current_loc) else {
+            return Some(new_loc);
+        };
+        let Some(surface) = target.wl_surface() else {
+            return Some(new_loc);
+        };
+
+        let surface_loc = surface_loc.to_f64();
+
+        let mut constrained = Some(new_loc);
+        let mut confined = false;
+
+        with_pointer_constraint(&surface, pointer, |constraint| {
+            let Some(constraint) = constraint.filter(|constraint| constraint.is_active()) else {
+                return;
+            };
+
+            constrained = match &*constraint {
+                PointerConstraint::Locked(_) => None,
+                PointerConstraint::Confined(_) => {
+                    confined = true;
+
+                    let rects = match constraint.region() {
+                        Some(region) => region
+                            .rects
+                            .iter()
+                            .filter(|(kind, _)| matches!(kind, RectangleKind::Add))
+                            .map(|(_, rect)| *rect)
+                            .collect::<Vec<_>>(),
+                        None => with_renderer_surface_state(&surface, |state| state.surface_size())
+                            .flatten()
+                            .map(|size| vec![Rectangle::from_loc_and_size((0, 0), size)])
+                            .unwrap_or_default(),
+                    };
+
+                    let contains = |point: Point<f64, Logical>| {
+                        let point = point.to_i32_round();
+                        match constraint.region() {
+                            Some(region) => region.contains(point),
+                            None => rects.iter().any(|rect| rect.contains(point)),
+                        }
+                    };
+
+                    let loc = closest_point_in_rects(new_loc - surface_loc, &rects, contains)
+                        .map(|point| point + surface_loc)
+                        .unwrap_or(current_loc);
+
+                    Some(loc)
+                }
+            };
+        });
+
+        // Don't let a confined pointer move onto a surface above its own
+        constrained.map(|loc| {
+            let surface_under = self
+                .pointer_focus_target_under(loc)
+                .and_then(|(target, _)| target.wl_surface());
+
+            if confined && surface_under.as_ref() != Some(&surface) {
+                current_loc
+            } else {
+                loc
+            }
+        })
+    }
+
+    /// Activate or deactivate the pointer constraint on the surface under the pointer.
+    ///
+    /// Constraints are only active while their surface has keyboard focus, the pointer
+    /// is inside the constraint's region, and no pointer grab is in progress.
+    pub fn update_pointer_constraint(&mut self, keyboard_focus: Option<&KeyboardFocusTarget>) {
+        let Some(pointer) = self.seat.get_pointer() else {
+            return;
+        };
+
+        let pointer_loc = pointer.current_location();
+
+        let Some((target, surface_loc)) = self.pointer_focus_target_under(pointer_loc) else {
+            return;
+        };
+        let Some(surface) = target.wl_surface() else {
+            return;
+        };
+
+        let has_keyboard_focus = keyboard_focus.is_some()
+            && target.to_keyboard_focus_target(self).as_ref() == keyboard_focus;
+
+        let should_activate = has_keyboard_focus && !pointer.is_grabbed();
+
+        with_pointer_constraint(&surface, &pointer, |constraint| {
+            let Some(constraint) = constraint else {
+                return;
+            };
+
+            if !should_activate {
+                if constraint.is_active() {
+                    constraint.deactivate();
+                }
+                return;
+            }
+
+            if constraint.is_active() {
+                return;
+            }
+
+            let point = pointer_loc.to_i32_round() - surface_loc;
+            if constraint
+                .region()
+                .map_or(true, |region| region.contains(point))
+            {
+                constraint.activate();
+            }
+        });
+    }
+
+    /// Deactivate the pointer constraint on the pointer's current focus, if any.
+    ///
+    /// This is used when a pointer grab starts so that moving or resizing
+    /// a window isn't blocked by a locked or confined pointer.
+    pub fn deactivate_pointer_constraint(&mut self) {
+        let Some(pointer) = self.seat.get_pointer() else {
+            return;
+        };
+
+        let Some(surface) = pointer.current_focus().and_then(|focus| focus.wl_surface()) else {
+            return;
+        };
+
+        with_pointer_constraint(&surface, &pointer, |constraint| {
+            if let Some(constraint) = constraint.filter(|constraint| constraint.is_active()) {
+                constraint.deactivate();
+            }
+        });
+    }
+}
+
+/// Get the point closest to `point` that is inside one of `rects` and passes `contains`.
+///
+/// If `point` itself passes `contains`, it is returned unchanged.
+fn closest_point_in_rects(
+    point: Point<f64, Logical>,
+    rects: &[Rectangle<i32, Logical>],
+    contains: impl Fn(Point<f64, Logical>) -> bool,
+) -> Option<Point<f64, Logical>> {
+    if contains(point) {
+        return Some(point);
+    }
+
+    rects
+        .iter()
+        .filter(|rect| !rect.is_empty())
+        .map(|rect| {
+            let x = point
+                .x
+                .clamp(rect.loc.x as f64, (rect.loc.x + rect.size.w - 1) as f64);
+            let y = point
+                .y
+                .clamp(rect.loc.y as f64, (rect.loc.y + rect.size.h - 1) as f64);
+            Point::from((x, y))
+        })
+        .filter(|clamped| contains(*clamped))
+        .min_by(|a, b| {
+            let dist = |p: &Point<f64, Logical>| (p.x - point.x).powi(2) + (p.y - point.y).powi(2);
+            f64::total_cmp(&dist(a), &dist(b))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confined_point_slides_along_region_edge() {
+        let rects = [Rectangle::from_loc_and_size((0, 0), (100, 50))];
+        let contains = |point: Point<f64, Logical>| rects[0].contains(point.to_i32_round());
+
+        let inside = Point::from((20.0, 20.0));
+        assert_eq!(
+            closest_point_in_rects(inside, &rects, contains),
+            Some(inside)
+        );
+
+        // Moving past the right edge keeps the vertical movement
+        assert_eq!(
+            closest_point_in_rects(Point::from((150.0, 30.0)), &rects, contains),
+            Some(Point::from((99.0, 30.0)))
+        );
+
+        assert_eq!(
+            closest_point_in_rects(Point::from((-10.0, -10.0)), &rects, contains),
+            Some(Point::from((0.0, 0.0)))
+        );
+    }
+}
@@ -0,0 +1,413 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! An input backend for events from virtual keyboards and pointers.
+//!
+//! Events from the virtual keyboard and virtual pointer protocols are converted into
+//! [`InputEvent`]s of this backend so they go through the same paths as real input devices.
+
+use std::sync::Arc;
+
+use smithay::{
+    backend::input::{
+        AbsolutePositionEvent, Axis, AxisSource, ButtonState, Device, DeviceCapability, Event,
+        InputBackend, InputEvent, KeyState, KeyboardKeyEvent, PointerAxisEvent, PointerButtonEvent,
+        PointerMotionAbsoluteEvent, PointerMotionEvent, UnusedEvent,
+    },
+    output::Output,
+    utils::{Logical, Point, Rectangle},
+};
+
+use tracing::error;
+
+use crate::{
+    delegate_virtual_keyboard, delegate_virtual_pointer,
+    protocol::{
+        virtual_keyboard::VirtualKeyboardHandler,
+        virtual_pointer::{VirtualPointerAxisFrame, VirtualPointerHandler},
+    },
+    state::State,
+};
+
+#[derive(Debug)]
+pub struct VirtualInputBackend;
+
+impl InputBackend for VirtualInputBackend {
+    type Device = VirtualDevice;
+    type KeyboardKeyEvent = VirtualKeyboardKeyEvent;
+    type PointerAxisEvent = VirtualPointerAxisEvent;
+    type PointerButtonEvent = VirtualPointerButtonEvent;
+    type PointerMotionEvent = VirtualPointerMotionEvent;
+    type PointerMotionAbsoluteEvent = VirtualPointerMotionAbsoluteEvent;
+
+    type GestureSwipeBeginEvent = UnusedEvent;
+    type GestureSwipeUpdateEvent = UnusedEvent;
+    type GestureSwipeEndEvent = UnusedEvent;
+    type GesturePinchBeginEvent = UnusedEvent;
+    type GesturePinchUpdateEvent = UnusedEvent;
+    type GesturePinchEndEvent = UnusedEvent;
+    type GestureHoldBeginEvent = UnusedEvent;
+    type GestureHoldEndEvent = UnusedEvent;
+
+    type TouchDownEvent = UnusedEvent;
+    type TouchUpEvent = UnusedEvent;
+    type TouchMotionEvent = UnusedEvent;
+    type TouchCancelEvent = UnusedEvent;
+    type TouchFrameEvent = UnusedEvent;
+
+    type TabletToolAxisEvent = UnusedEvent;
+    type TabletToolProximityEvent = UnusedEvent;
+    type TabletToolTipEvent = UnusedEvent;
+    type TabletToolButtonEvent = UnusedEvent;
+
+    type SwitchToggleEvent = UnusedEvent;
+
+    type SpecialEvent = UnusedEvent;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VirtualDevice {
+    Keyboard,
+    Pointer,
+}
+
+impl Device for VirtualDevice {
+    fn id(&self) -> String {
+        match self {
+            VirtualDevice::Keyboard => "virtual-keyboard".to_string(),
+            VirtualDevice::Pointer => "virtual-pointer".to_string(),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            VirtualDevice::Keyboard => "Virtual Keyboard".to_string(),
+            VirtualDevice::Pointer => "Virtual Pointer".to_string(),
+        }
+    }
+
+    fn has_capability(&self, capability: DeviceCapability) -> bool {
+        matches!(
+            (self, capability),
+            (VirtualDevice::Keyboard, DeviceCapability::Keyboard)
+                | (VirtualDevice::Pointer, DeviceCapability::Pointer)
+        )
+    }
+
+    fn usb_id(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    fn syspath(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+/// Convert a protocol timestamp in milliseconds to microseconds.
+fn time_usec(time_msec: u32) -> u64 {
+    time_msec as u64 * 1000
+}
+
+#[derive(Debug)]
+pub struct VirtualKeyboardKeyEvent {
+    time: u32,
+    key: u32,
+    state: KeyState,
+}
+
+impl Event<VirtualInputBackend> for VirtualKeyboardKeyEvent {
+    fn time(&self) -> u64 {
+        time_usec(self.time)
+    }
+
+    fn device(&self) -> VirtualDevice {
+        VirtualDevice::Keyboard
+    }
+}
+
+impl KeyboardKeyEvent<VirtualInputBackend> for VirtualKeyboardKeyEvent {
+    fn key_code(&self) -> u32 {
+        self.key
+    }
+
+    fn state(&self) -> KeyState {
+        self.state
+    }
+
+    fn count(&self) -> u32 {
+        match self.state {
+            KeyState::Pressed => 1,
+            KeyState::Released => 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VirtualPointerMotionEvent {
+    time: u32,
+    delta: Point<f64, Logical>,
+}
+
+impl Event<VirtualInputBackend> for VirtualPointerMotionEvent {
+    fn time(&self) -> u64 {
+        time_usec(self.time)
+    }
+
+    fn device(&self) -> VirtualDevice {
+        VirtualDevice::Pointer
+    }
+}
+
+impl PointerMotionEvent<VirtualInputBackend> for VirtualPointerMotionEvent {
+    fn delta_x(&self) -> f64 {
+        self.delta.x
+    }
+
+    fn delta_y(&self) -> f64 {
+        self.delta.y
+    }
+
+    fn delta_x_unaccel(&self) -> f64 {
+        self.delta.x
+    }
+
+    fn delta_y_unaccel(&self) -> f64 {
+        self.delta.y
+    }
+}
+
+/// An absolute motion event normalized to the whole output layout.
+#[derive(Debug)]
+pub struct VirtualPointerMotionAbsoluteEvent {
+    time: u32,
+    position: Point<f64, Logical>,
+}
+
+impl Event<VirtualInputBackend> for VirtualPointerMotionAbsoluteEvent {
+    fn time(&self) -> u64 {
+        time_usec(self.time)
+    }
+
+    fn device(&self) -> VirtualDevice {
+        VirtualDevice::Pointer
+    }
+}
+
+impl AbsolutePositionEvent<VirtualInputBackend> for VirtualPointerMotionAbsoluteEvent {
+    fn x(&self) -> f64 {
+        self.position.x
+    }
+
+    fn y(&self) -> f64 {
+        self.position.y
+    }
+
+    fn x_transformed(&self, width: i32) -> f64 {
+        self.position.x * width as f64
+    }
+
+    fn y_transformed(&self, height: i32) -> f64 {
+        self.position.y * height as f64
+    }
+}
+
+impl PointerMotionAbsoluteEvent<VirtualInputBackend> for VirtualPointerMotionAbsoluteEvent {}
+
+#[derive(Debug)]
+pub struct VirtualPointerButtonEvent {
+    time: u32,
+    button: u32,
+    state: ButtonState,
+}
+
+impl Event<VirtualInputBackend> for VirtualPointerButtonEvent {
+    fn time(&self) -> u64 {
+        time_usec(self.time)
+    }
+
+    fn device(&self) -> VirtualDevice {
+        VirtualDevice::Pointer
+    }
+}
+
+impl PointerButtonEvent<VirtualInputBackend> for VirtualPointerButtonEvent {
+    fn button_code(&self) -> u32 {
+        self.button
+    }
+
+    fn state(&self) -> ButtonState {
+        self.state
+    }
+}
+
+#[derive(Debug)]
+pub struct VirtualPointerAxisEvent(VirtualPointerAxisFrame);
+
+impl Event<VirtualInputBackend> for VirtualPointerAxisEvent {
+    fn time(&self) -> u64 {
+        time_usec(self.0.time)
+    }
+
+    fn device(&self) -> VirtualDevice {
+        VirtualDevice::Pointer
+    }
+}
+
+impl PointerAxisEvent<VirtualInputBackend> for VirtualPointerAxisEvent {
+    fn amount(&self, axis: Axis) -> Option<f64> {
+        match axis {
+            Axis::Horizontal => self.0.horizontal,
+            Axis::Vertical => self.0.vertical,
+        }
+    }
+
+    fn amount_v120(&self, axis: Axis) -> Option<f64> {
+        match axis {
+            Axis::Horizontal => self.0.horizontal_v120,
+            Axis::Vertical => self.0.vertical_v120,
+        }
+    }
+
+    fn source(&self) -> AxisSource {
+        self.0.source.unwrap_or(AxisSource::Wheel)
+    }
+}
+
+impl VirtualKeyboardHandler for State {
+    fn virtual_keyboard_key(&mut self, time: u32, key: u32, state: KeyState, keymap: &Arc<str>) {
+        self.use_virtual_keymap(keymap);
+
+        // This doesn't go through `process_input_event` because that restores the seat keymap
+        self.notify_activity();
+        self.keyboard::<VirtualInputBackend>(VirtualKeyboardKeyEvent { time, key, state });
+    }
+
+    fn virtual_keyboard_modifiers(
+        &mut self,
+        depressed: u32,
+        latched: u32,
+        locked: u32,
+        group: u32,
+        keymap: &Arc<str>,
+    ) {
+        self.use_virtual_keymap(keymap);
+
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+
+        self.notify_activity();
+
+        let mut mods = keyboard.modifier_state();
+        mods.serialized.depressed = depressed;
+        mods.serialized.latched = latched;
+        mods.serialized.locked = locked;
+        mods.serialized.layout_locked = group;
+        keyboard.set_modifier_state(mods);
+    }
+}
+delegate_virtual_keyboard!(State);
+
+impl State {
+    /// Make the seat keyboard use a virtual keyboard's keymap so clients
+    /// interpret its keycodes the way the virtual keyboard intended.
+    fn use_virtual_keymap(&mut self, keymap: &Arc<str>) {
+        if self
+            .input_state
+            .virtual_keymap
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, keymap))
+        {
+            return;
+        }
+
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+
+        if let Err(err) = keyboard.set_keymap_from_string(self, keymap.to_string()) {
+            error!("Failed to use virtual keyboard keymap: {err}");
+            return;
+        }
+
+        self.input_state.virtual_keymap = Some(keymap.clone());
+    }
+
+    /// Switch the seat keyboard back to the configured keymap if a virtual keyboard
+    /// changed it.
+    pub fn restore_seat_keymap(&mut self) {
+        if self.input_state.virtual_keymap.take().is_none() {
+            return;
+        }
+
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+
+        let settings = self.input_state.xkb_settings.clone();
+        if let Err(err) = keyboard.set_xkb_config(self, settings.as_xkb_config()) {
+            error!("Failed to restore the seat keymap: {err}");
+        }
+    }
+}
+
+impl VirtualPointerHandler for State {
+    fn virtual_pointer_motion(&mut self, time: u32, delta: Point<f64, Logical>) {
+        self.process_input_event(InputEvent::<VirtualInputBackend>::PointerMotion {
+            event: VirtualPointerMotionEvent { time, delta },
+        });
+    }
+
+    fn virtual_pointer_motion_absolute(
+        &mut self,
+        time: u32,
+        position: Point<f64, Logical>,
+        output: Option<&Output>,
+    ) {
+        let Some(layout_geo) = self.output_layout_geometry() else {
+            return;
+        };
+
+        // Remap output-relative positions so they're relative to the whole layout
+        let position = match output.and_then(|op| self.space.output_geometry(op)) {
+            Some(output_geo) if layout_geo.size.w > 0 && layout_geo.size.h > 0 => {
+                let output_geo = output_geo.to_f64();
+                let layout_geo = layout_geo.to_f64();
+                let x = output_geo.loc.x - layout_geo.loc.x + position.x * output_geo.size.w;
+                let y = output_geo.loc.y - layout_geo.loc.y + position.y * output_geo.size.h;
+                Point::from((x / layout_geo.size.w, y / layout_geo.size.h))
+            }
+            _ => position,
+        };
+
+        self.process_input_event(InputEvent::<VirtualInputBackend>::PointerMotionAbsolute {
+            event: VirtualPointerMotionAbsoluteEvent { time, position },
+        });
+    }
+
+    fn virtual_pointer_button(&mut self, time: u32, button: u32, state: ButtonState) {
+        self.process_input_event(InputEvent::<VirtualInputBackend>::PointerButton {
+            event: VirtualPointerButtonEvent {
+                time,
+                button,
+                state,
+            },
+        });
+    }
+
+    fn virtual_pointer_axis(&mut self, frame: VirtualPointerAxisFrame) {
+        self.process_input_event(InputEvent::<VirtualInputBackend>::PointerAxis {
+            event: VirtualPointerAxisEvent(frame),
+        });
+    }
+}
+delegate_virtual_pointer!(State);
+
+impl State {
+    /// Get the bounding box of all mapped outputs.
+    pub fn output_layout_geometry(&self) -> Option<Rectangle<i32, Logical>> {
+        self.space
+            .outputs()
+            .filter_map(|op| self.space.output_geometry(op))
+            .reduce(|acc, geo| acc.merge(geo))
+    }
+}
@@ -31,28 +31,14 @@ impl State {
         output: &Output,
         geometries: Vec<Rectangle<i32, Logical>>,
     ) {
-        let windows_on_foc_tags = output.with_state(|state| {
-            let focused_tags = state.focused_tags().collect::<Vec<_>>();
-            self.windows
-                .iter()
-                .filter(|win| !win.is_x11_override_redirect())
-                .filter(|win| {
-                    win.with_state(|state| state.tags.iter().any(|tg| focused_tags.contains(&tg)))
-                })
-                .cloned()
-                .collect::<Vec<_>>()
-        });
+        let windows_on_foc_tags = self.windows_on_focused_tags(output);
 
-        let tiled_windows = windows_on_foc_tags
-            .iter()
-            .filter(|win| {
-                win.with_state(|state| {
-                    state.floating_or_tiled.is_tiled() && state.fullscreen_or_maximized.is_neither()
-                })
-            })
-            .cloned();
+        let tiled_windows = self.tiled_windows(output).into_iter();
 
-        let output_geo = self.space.output_geometry(output).expect("no output geo");
+        // The output may have been disabled while the layout was being computed
+        let Some(output_geo) = self.space.output_geometry(output) else {
+            return;
+        };
 
         let non_exclusive_geo = {
             let map = layer_map_for_output(output);
@@ -64,8 +50,12 @@ impl State {
             geo
         }));
 
-        for (win, geo) in zipped.by_ref() {
-            win.change_geometry(geo);
+        for (win, mut geo) in zipped.by_ref() {
+            if win.group().is_some() {
+                geo.loc.y += self.config.tab_bar.height;
+                geo.size.h -= self.config.tab_bar.height;
+            }
+            win.change_geometry(self.inset_by_border(&win, geo));
         }
 
         let (remaining_wins, _remaining_geos) = zipped.unzip::<_, _, Vec<_>, Vec<_>>();
@@ -81,10 +71,11 @@ impl State {
                     window.change_geometry(output_geo);
                 }
                 FullscreenOrMaximized::Maximized => {
-                    window.change_geometry(Rectangle::from_loc_and_size(
+                    let geo = Rectangle::from_loc_and_size(
                         output_geo.loc + non_exclusive_geo.loc,
                         non_exclusive_geo.size,
-                    ));
+                    );
+                    window.change_geometry(self.inset_by_border(window, geo));
                 }
                 FullscreenOrMaximized::Neither => {
                     if let FloatingOrTiled::Floating(rect) =
@@ -149,6 +140,65 @@ impl State {
         self.fixup_z_layering();
     }
 
+    /// Shrink `geo` so that `window`'s border fits inside of it.
+    ///
+    /// Borders are drawn outside of a window's geometry, so this keeps tiled and
+    /// maximized windows from overlapping each other's borders.
+    fn inset_by_border(
+        &self,
+        window: &WindowElement,
+        geo: Rectangle<i32, Logical>,
+    ) -> Rectangle<i32, Logical> {
+        let width = self.config.borders.width_for(window);
+
+        Rectangle::from_loc_and_size(
+            (geo.loc.x + width, geo.loc.y + width),
+            (
+                (geo.size.w - width * 2).max(1),
+                (geo.size.h - width * 2).max(1),
+            ),
+        )
+    }
+
+    /// Get all unminimized windows on `output`'s focused tags.
+    ///
+    /// Sticky windows on `output` are included as long as any of its tags are focused.
+    fn windows_on_focused_tags(&self, output: &Output) -> Vec<WindowElement> {
+        output.with_state(|state| {
+            let focused_tags = state.focused_tags().collect::<Vec<_>>();
+            self.windows
+                .iter()
+                .filter(|win| !win.is_x11_override_redirect())
+                .filter(|win| !win.is_inactive_in_group())
+                .filter(|win| {
+                    win.with_state(|win_state| {
+                        let on_focused_tag = if win_state.sticky {
+                            !focused_tags.is_empty()
+                                && win_state.tags.iter().any(|tg| state.tags.contains(tg))
+                        } else {
+                            win_state.tags.iter().any(|tg| focused_tags.contains(&tg))
+                        };
+
+                        !win_state.minimized && !win_state.swallowed && on_focused_tag
+                    })
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Get the windows that are tiled on `output`, in layout order.
+    pub fn tiled_windows(&self, output: &Output) -> Vec<WindowElement> {
+        self.windows_on_focused_tags(output)
+            .into_iter()
+            .filter(|win| {
+                win.with_state(|state| {
+                    state.floating_or_tiled.is_tiled() && state.fullscreen_or_maximized.is_neither()
+                })
+            })
+            .collect()
+    }
+
     /// Swaps two windows in the main window vec and updates all windows.
     pub fn swap_window_positions(&mut self, win1: &WindowElement, win2: &WindowElement) {
         let win1_index = self.windows.iter().position(|win| win == win1);
@@ -156,12 +206,56 @@ impl State {
 
         if let (Some(first), Some(second)) = (win1_index, win2_index) {
             self.windows.swap(first, second);
-            if let Some(output) = win1.output(self) {
+
+            let output1 = win1.output(self);
+            let output2 = win2.output(self).filter(|op| Some(op) != output1.as_ref());
+            for output in [output1, output2].into_iter().flatten() {
                 self.request_layout(&output);
             }
+
             self.layout_state.pending_swap = true;
         }
     }
+
+    /// Move `window` `delta` places in the tiled layout order of its output.
+    ///
+    /// Negative values move it towards the start of the stack, and the
+    /// resulting position is clamped to the stack.
+    pub fn move_window_in_stack(&mut self, window: &WindowElement, delta: i32) {
+        let Some(output) = window.output(self) else {
+            return;
+        };
+
+        let tiled_windows = self.tiled_windows(&output);
+
+        let Some(index) = tiled_windows.iter().position(|win| win == window) else {
+            return;
+        };
+
+        let target_index = (index as i64 + delta as i64).clamp(0, tiled_windows.len() as i64 - 1);
+        let target = &tiled_windows[target_index as usize];
+
+        if target == window {
+            return;
+        }
+
+        let window_pos = self.windows.iter().position(|win| win == window);
+        let target_pos = self.windows.iter().position(|win| win == target);
+
+        let (Some(window_pos), Some(mut target_pos)) = (window_pos, target_pos) else {
+            return;
+        };
+
+        let window = self.windows.remove(window_pos);
+        if window_pos < target_pos {
+            target_pos -= 1;
+        }
+
+        let insert_pos = if delta > 0 { target_pos + 1 } else { target_pos };
+        self.windows.insert(insert_pos, window);
+
+        self.request_layout(&output);
+    }
 }
 
 /// A monotonically increasing identifier for layout requests.
@@ -184,27 +278,12 @@ impl State {
             return;
         };
 
-        let windows_on_foc_tags = output.with_state(|state| {
-            let focused_tags = state.focused_tags().collect::<Vec<_>>();
-            self.windows
-                .iter()
-                .filter(|win| !win.is_x11_override_redirect())
-                .filter(|win| {
-                    win.with_state(|state| state.tags.iter().any(|tg| focused_tags.contains(&tg)))
-                })
-                .cloned()
-                .collect::<Vec<_>>()
-        });
+        // Disabled outputs aren't in the space and have nothing to lay out
+        if self.space.output_geometry(output).is_none() {
+            return;
+        }
 
-        let windows = windows_on_foc_tags
-            .iter()
-            .filter(|win| {
-                win.with_state(|state| {
-                    state.floating_or_tiled.is_tiled() && state.fullscreen_or_maximized.is_neither()
-                })
-            })
-            .cloned()
-            .collect::<Vec<_>>();
+        let windows = self.tiled_windows(output);
 
         let (output_width, output_height) = {
             let map = layer_map_for_output(output);
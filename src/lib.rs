@@ -8,11 +8,14 @@ pub mod cursor;
 pub mod focus;
 pub mod grab;
 pub mod handlers;
+pub mod idle;
 pub mod input;
 pub mod layout;
 pub mod output;
 pub mod protocol;
 pub mod render;
+pub mod security;
+pub mod session_lock;
 pub mod state;
 pub mod tag;
 pub mod window;
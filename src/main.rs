@@ -134,6 +134,10 @@ async fn main() -> anyhow::Result<()> {
         state.fixup_z_layering();
         state.space.refresh();
         state.popup_manager.cleanup();
+        state.update_foreign_toplevels();
+        state.update_ext_workspaces();
+        state.update_output_management();
+        state.refresh_idle_inhibit();
 
         state
             .display_handle
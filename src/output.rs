@@ -1,20 +1,24 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{cell::RefCell, num::NonZeroU32};
+use std::{cell::RefCell, collections::HashMap, num::NonZeroU32};
 
 use pinnacle_api_defs::pinnacle::signal::v0alpha1::{OutputMoveResponse, OutputResizeResponse};
 use smithay::{
+    backend::renderer::element::solid::SolidColorBuffer,
     desktop::layer_map_for_output,
     output::{Mode, Output, Scale},
     utils::{Logical, Point, Transform},
+    wayland::session_lock::LockSurface,
 };
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::{
+    backend::Backend,
     focus::WindowKeyboardFocusStack,
-    protocol::screencopy::Screencopy,
+    protocol::{output_management::OutputConfiguration, screencopy::Screencopy},
     state::{State, WithState},
     tag::Tag,
+    window::window_state::FloatingOrTiled,
 };
 
 /// A unique identifier for an output.
@@ -42,6 +46,10 @@ pub struct OutputState {
     pub focus_stack: WindowKeyboardFocusStack,
     pub screencopy: Option<Screencopy>,
     pub serial: Option<NonZeroU32>,
+    /// The session lock surface on this output, if the session is locked
+    pub lock_surface: Option<LockSurface>,
+    /// The solid backdrop drawn under the lock surface
+    pub lock_backdrop: Option<SolidColorBuffer>,
 }
 
 impl WithState for Output {
@@ -101,6 +109,7 @@ impl State {
         }
         if mode.is_some() || transform.is_some() || scale.is_some() {
             layer_map_for_output(output).arrange();
+            self.reconfigure_lock_surface(output);
             self.signal_state.output_resize.signal(|buf| {
                 let geo = self.space.output_geometry(output);
                 buf.push_back(OutputResizeResponse {
@@ -111,4 +120,193 @@ impl State {
             });
         }
     }
+
+    /// Apply a configuration from an output management client, or only check if it
+    /// would apply if `test_only` is true.
+    ///
+    /// Returns whether or not the configuration was (or would be) applied successfully.
+    pub fn apply_output_configuration(
+        &mut self,
+        config: HashMap<Output, OutputConfiguration>,
+        test_only: bool,
+    ) -> bool {
+        let mut to_apply = Vec::new();
+        let mut to_disable = Vec::new();
+
+        for (output, config) in config {
+            let enabled = self.space.outputs().any(|op| op == &output);
+
+            if !enabled && !self.disabled_outputs.contains(&output) {
+                return false;
+            }
+
+            let OutputConfiguration::Enabled {
+                mode,
+                position,
+                transform,
+                scale,
+                adaptive_sync,
+            } = config
+            else {
+                if enabled {
+                    to_disable.push(output);
+                }
+                continue;
+            };
+
+            let mode = match mode {
+                Some(mode) => match find_supported_mode(&output, mode) {
+                    Some(mode) => Some(mode),
+                    None => {
+                        warn!("Output {} does not support mode {mode:?}", output.name());
+                        return false;
+                    }
+                },
+                None => None,
+            };
+
+            if adaptive_sync == Some(true) {
+                warn!("Adaptive sync is not supported");
+                return false;
+            }
+
+            to_apply.push((output, mode, position, transform, scale));
+        }
+
+        let any_stay_enabled = self.space.outputs().any(|op| !to_disable.contains(op));
+        let any_get_enabled = to_apply
+            .iter()
+            .any(|(output, ..)| self.disabled_outputs.contains(output));
+
+        if !any_stay_enabled && !any_get_enabled {
+            warn!("Refusing to disable every output");
+            return false;
+        }
+
+        if test_only {
+            return true;
+        }
+
+        // Mode changes are the only part that can fail, so do them all first and
+        // put back the old modes if any of them fail
+        let mut changed_modes = Vec::new();
+
+        for (output, mode, ..) in to_apply.iter() {
+            let Some(mode) = *mode else {
+                continue;
+            };
+
+            let old_mode = output.current_mode();
+
+            if old_mode == Some(mode) {
+                continue;
+            }
+
+            if !self.resize_output(output, mode) {
+                for (output, old_mode) in changed_modes.into_iter().rev() {
+                    if !self.resize_output(&output, old_mode) {
+                        warn!("Failed to restore the mode of output {}", output.name());
+                    }
+                }
+                return false;
+            }
+
+            if let Some(old_mode) = old_mode {
+                changed_modes.push((output.clone(), old_mode));
+            }
+        }
+
+        for (output, _mode, position, transform, scale) in to_apply {
+            if self.disabled_outputs.contains(&output) {
+                self.enable_output(&output);
+            }
+
+            if let Some(position) = position {
+                self.config
+                    .connector_saved_states
+                    .entry(OutputName(output.name()))
+                    .or_default()
+                    .loc = position;
+            }
+
+            self.change_output_state(
+                &output,
+                None,
+                transform,
+                scale.map(Scale::Fractional),
+                position,
+            );
+            self.request_layout(&output);
+            self.schedule_render(&output);
+        }
+
+        for output in to_disable {
+            self.disable_output(&output);
+        }
+
+        true
+    }
+
+    /// Map a disabled output back into the space at its last location.
+    fn enable_output(&mut self, output: &Output) {
+        self.disabled_outputs.retain(|op| op != output);
+        self.space.map_output(output, output.current_location());
+
+        if let Backend::Udev(udev) = &mut self.backend {
+            udev.enable_output(output);
+        }
+    }
+
+    /// Unmap `output` from the space, moving its windows onto another output.
+    ///
+    /// The output keeps its tags so they come back if it gets enabled again.
+    fn disable_output(&mut self, output: &Output) {
+        let windows = self
+            .windows
+            .iter()
+            .filter(|win| win.output(self).as_ref() == Some(output))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.space.unmap_output(output);
+        self.disabled_outputs.push(output.clone());
+
+        if let Backend::Udev(udev) = &mut self.backend {
+            udev.disable_output(output);
+        }
+
+        let Some(new_output) = self.space.outputs().next().cloned() else {
+            return;
+        };
+
+        if self.focused_output() == Some(output) {
+            self.output_focus_stack.set_focus(new_output.clone());
+        }
+
+        // Keep floating windows in the same spot relative to their output
+        let offset = new_output.current_location() - output.current_location();
+
+        for win in windows {
+            win.place_on_output(&new_output);
+            win.with_state_mut(|state| {
+                if let FloatingOrTiled::Floating(rect) = &mut state.floating_or_tiled {
+                    rect.loc += offset;
+                }
+            });
+        }
+
+        self.request_layout(&new_output);
+        self.schedule_render(&new_output);
+    }
+}
+
+/// Find a mode on `output` matching `mode`.
+///
+/// A refresh rate of 0 matches the mode with the highest refresh rate of the same size.
+fn find_supported_mode(output: &Output, mode: Mode) -> Option<Mode> {
+    output
+        .modes()
+        .into_iter()
+        .filter(|m| m.size == mode.size && (mode.refresh == 0 || m.refresh == mode.refresh))
+        .max_by_key(|m| m.refresh)
 }
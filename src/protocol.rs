@@ -1,2 +1,8 @@
+pub mod ext_workspace;
+pub mod foreign_toplevel;
 pub mod gamma_control;
+pub mod output_management;
 pub mod screencopy;
+pub mod toplevel_export;
+pub mod virtual_keyboard;
+pub mod virtual_pointer;
@@ -0,0 +1,509 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Mutex,
+};
+
+use smithay::{
+    output::Output,
+    reexports::wayland_server::{
+        self, backend::ClientId, Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch,
+        Resource,
+    },
+};
+use tracing::error;
+
+use self::server::{
+    ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1, GroupCapabilities},
+    ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1, WorkspaceCapabilities},
+    ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1},
+};
+
+#[allow(missing_docs, dead_code, non_camel_case_types, non_upper_case_globals)]
+#[allow(unused_imports, clippy::all)]
+pub mod server {
+    use smithay::reexports::wayland_server;
+    use smithay::reexports::wayland_server::protocol::*;
+
+    pub mod __interfaces {
+        use smithay::reexports::wayland_server::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("resources/protocols/ext-workspace-v1.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_server_code!("resources/protocols/ext-workspace-v1.xml");
+}
+
+const VERSION: u32 = 1;
+
+pub struct ExtWorkspaceManagerState {
+    display: DisplayHandle,
+    instances: Vec<ManagerInstance>,
+    /// Outputs advertised as workspace groups
+    groups: Vec<Output>,
+    workspaces: HashMap<u32, WorkspaceInfo>,
+}
+
+pub struct ExtWorkspaceGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+/// The state of a workspace as advertised to ext-workspace clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    /// The output whose workspace group this workspace belongs to.
+    pub output: Output,
+    /// The position of this workspace in its group.
+    pub index: u32,
+    pub active: bool,
+    pub urgent: bool,
+}
+
+impl WorkspaceInfo {
+    fn state(&self) -> ext_workspace_handle_v1::State {
+        let mut state = ext_workspace_handle_v1::State::empty();
+        if self.active {
+            state |= ext_workspace_handle_v1::State::Active;
+        }
+        if self.urgent {
+            state |= ext_workspace_handle_v1::State::Urgent;
+        }
+        state
+    }
+}
+
+/// A request to change a workspace, applied when the client commits.
+#[derive(Debug, Clone, Copy)]
+enum WorkspaceRequest {
+    Activate(u32),
+    Deactivate(u32),
+}
+
+#[derive(Debug, Default)]
+pub struct ExtWorkspaceManagerData {
+    pending: Mutex<Vec<WorkspaceRequest>>,
+}
+
+#[derive(Debug)]
+pub struct ExtWorkspaceHandleData {
+    id: u32,
+    manager: ExtWorkspaceManagerV1,
+}
+
+/// A bound manager and the handles created for its client.
+struct ManagerInstance {
+    manager: ExtWorkspaceManagerV1,
+    groups: HashMap<Output, ExtWorkspaceGroupHandleV1>,
+    workspaces: HashMap<u32, ExtWorkspaceHandleV1>,
+}
+
+impl ManagerInstance {
+    fn add_group<D>(&mut self, display: &DisplayHandle, output: &Output)
+    where
+        D: Dispatch<ExtWorkspaceGroupHandleV1, ()> + 'static,
+    {
+        let Some(client) = self.manager.client() else {
+            return;
+        };
+
+        let group = match client.create_resource::<ExtWorkspaceGroupHandleV1, _, D>(
+            display,
+            self.manager.version(),
+            (),
+        ) {
+            Ok(group) => group,
+            Err(err) => {
+                error!("Failed to create workspace group handle: {err}");
+                return;
+            }
+        };
+
+        self.manager.workspace_group(&group);
+        group.capabilities(GroupCapabilities::empty());
+        for wl_output in output.client_outputs(&client) {
+            group.output_enter(&wl_output);
+        }
+
+        self.groups.insert(output.clone(), group);
+    }
+
+    fn remove_group(&mut self, output: &Output) {
+        let Some(group) = self.groups.remove(output) else {
+            return;
+        };
+
+        if let Some(client) = group.client() {
+            for wl_output in output.client_outputs(&client) {
+                group.output_leave(&wl_output);
+            }
+        }
+        group.removed();
+    }
+
+    fn add_workspace<D>(&mut self, display: &DisplayHandle, id: u32, info: &WorkspaceInfo)
+    where
+        D: Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData> + 'static,
+    {
+        let Some(client) = self.manager.client() else {
+            return;
+        };
+
+        let data = ExtWorkspaceHandleData {
+            id,
+            manager: self.manager.clone(),
+        };
+
+        let workspace = match client.create_resource::<ExtWorkspaceHandleV1, _, D>(
+            display,
+            self.manager.version(),
+            data,
+        ) {
+            Ok(workspace) => workspace,
+            Err(err) => {
+                error!("Failed to create workspace handle: {err}");
+                return;
+            }
+        };
+
+        self.manager.workspace(&workspace);
+        workspace.id(id.to_string());
+        workspace.name(info.name.clone());
+        workspace.coordinates(info.index.to_ne_bytes().to_vec());
+        workspace.state(info.state());
+        workspace.capabilities(WorkspaceCapabilities::Activate | WorkspaceCapabilities::Deactivate);
+
+        if let Some(group) = self.groups.get(&info.output) {
+            group.workspace_enter(&workspace);
+        }
+
+        self.workspaces.insert(id, workspace);
+    }
+
+    fn remove_workspace(&mut self, id: u32, info: &WorkspaceInfo) {
+        let Some(workspace) = self.workspaces.remove(&id) else {
+            return;
+        };
+
+        if let Some(group) = self.groups.get(&info.output) {
+            group.workspace_leave(&workspace);
+        }
+        workspace.removed();
+    }
+
+    fn update_workspace(&self, id: u32, old: &WorkspaceInfo, new: &WorkspaceInfo) {
+        let Some(workspace) = self.workspaces.get(&id) else {
+            return;
+        };
+
+        if old.output != new.output {
+            if let Some(group) = self.groups.get(&old.output) {
+                group.workspace_leave(workspace);
+            }
+            if let Some(group) = self.groups.get(&new.output) {
+                group.workspace_enter(workspace);
+            }
+        }
+        if old.name != new.name {
+            workspace.name(new.name.clone());
+        }
+        if old.index != new.index {
+            workspace.coordinates(new.index.to_ne_bytes().to_vec());
+        }
+        if old.state() != new.state() {
+            workspace.state(new.state());
+        }
+    }
+}
+
+impl ExtWorkspaceManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<ExtWorkspaceManagerV1, ExtWorkspaceGlobalData>
+            + Dispatch<ExtWorkspaceManagerV1, ExtWorkspaceManagerData>
+            + Dispatch<ExtWorkspaceGroupHandleV1, ()>
+            + Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData>
+            + ExtWorkspaceHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = ExtWorkspaceGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, ExtWorkspaceManagerV1, _>(VERSION, global_data);
+        Self {
+            display: display.clone(),
+            instances: Vec::new(),
+            groups: Vec::new(),
+            workspaces: HashMap::new(),
+        }
+    }
+
+    /// Update advertised workspace groups and workspaces.
+    ///
+    /// Each output in `outputs` is advertised as a workspace group, and each
+    /// workspace is identified by a unique id.
+    /// New groups and workspaces will be announced, changed ones will have their
+    /// changes sent, and ones that are no longer present will be removed.
+    pub fn refresh<D>(&mut self, outputs: Vec<Output>, workspaces: Vec<(u32, WorkspaceInfo)>)
+    where
+        D: Dispatch<ExtWorkspaceGroupHandleV1, ()>
+            + Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData>
+            + 'static,
+    {
+        let mut changed = false;
+
+        // Workspaces must leave their groups before the groups are removed
+        self.workspaces.retain(|id, info| {
+            let keep = workspaces.iter().any(|(ws_id, _)| ws_id == id);
+            if !keep {
+                for instance in self.instances.iter_mut() {
+                    instance.remove_workspace(*id, info);
+                }
+                changed = true;
+            }
+            keep
+        });
+
+        self.groups.retain(|output| {
+            let keep = outputs.contains(output);
+            if !keep {
+                for instance in self.instances.iter_mut() {
+                    instance.remove_group(output);
+                }
+                changed = true;
+            }
+            keep
+        });
+
+        for output in outputs {
+            if self.groups.contains(&output) {
+                continue;
+            }
+            for instance in self.instances.iter_mut() {
+                instance.add_group::<D>(&self.display, &output);
+            }
+            self.groups.push(output);
+            changed = true;
+        }
+
+        for (id, info) in workspaces {
+            match self.workspaces.entry(id) {
+                Entry::Occupied(mut entry) => {
+                    if entry.get() == &info {
+                        continue;
+                    }
+                    for instance in self.instances.iter() {
+                        instance.update_workspace(id, entry.get(), &info);
+                    }
+                    entry.insert(info);
+                }
+                Entry::Vacant(entry) => {
+                    for instance in self.instances.iter_mut() {
+                        instance.add_workspace::<D>(&self.display, id, &info);
+                    }
+                    entry.insert(info);
+                }
+            }
+            changed = true;
+        }
+
+        if changed {
+            for instance in self.instances.iter() {
+                instance.manager.done();
+            }
+        }
+    }
+}
+
+impl<D> GlobalDispatch<ExtWorkspaceManagerV1, ExtWorkspaceGlobalData, D>
+    for ExtWorkspaceManagerState
+where
+    D: GlobalDispatch<ExtWorkspaceManagerV1, ExtWorkspaceGlobalData>
+        + Dispatch<ExtWorkspaceManagerV1, ExtWorkspaceManagerData>
+        + Dispatch<ExtWorkspaceGroupHandleV1, ()>
+        + Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData>
+        + ExtWorkspaceHandler
+        + 'static,
+{
+    fn bind(
+        state: &mut D,
+        handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<ExtWorkspaceManagerV1>,
+        _global_data: &ExtWorkspaceGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let manager = data_init.init(resource, ExtWorkspaceManagerData::default());
+
+        let manager_state = state.ext_workspace_manager_state();
+
+        let mut instance = ManagerInstance {
+            manager,
+            groups: HashMap::new(),
+            workspaces: HashMap::new(),
+        };
+
+        for output in manager_state.groups.iter() {
+            instance.add_group::<D>(handle, output);
+        }
+        for (id, info) in manager_state.workspaces.iter() {
+            instance.add_workspace::<D>(handle, *id, info);
+        }
+        instance.manager.done();
+
+        manager_state.instances.push(instance);
+    }
+
+    fn can_view(client: Client, global_data: &ExtWorkspaceGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceManagerV1, ExtWorkspaceManagerData, D> for ExtWorkspaceManagerState
+where
+    D: Dispatch<ExtWorkspaceManagerV1, ExtWorkspaceManagerData> + ExtWorkspaceHandler + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        manager: &ExtWorkspaceManagerV1,
+        request: <ExtWorkspaceManagerV1 as Resource>::Request,
+        data: &ExtWorkspaceManagerData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            ext_workspace_manager_v1::Request::Commit => {
+                let pending = std::mem::take(&mut *data.pending.lock().expect("mutex poisoned"));
+                for request in pending {
+                    match request {
+                        WorkspaceRequest::Activate(id) => state.activate_workspace(id),
+                        WorkspaceRequest::Deactivate(id) => state.deactivate_workspace(id),
+                    }
+                }
+            }
+            ext_workspace_manager_v1::Request::Stop => {
+                state
+                    .ext_workspace_manager_state()
+                    .instances
+                    .retain(|instance| &instance.manager != manager);
+                manager.finished();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(
+        state: &mut D,
+        _client: ClientId,
+        manager: &ExtWorkspaceManagerV1,
+        _data: &ExtWorkspaceManagerData,
+    ) {
+        state
+            .ext_workspace_manager_state()
+            .instances
+            .retain(|instance| &instance.manager != manager);
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceGroupHandleV1, (), D> for ExtWorkspaceManagerState
+where
+    D: Dispatch<ExtWorkspaceGroupHandleV1, ()> + ExtWorkspaceHandler + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _group: &ExtWorkspaceGroupHandleV1,
+        request: <ExtWorkspaceGroupHandleV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            // Creating workspaces isn't advertised as a capability
+            ext_workspace_group_handle_v1::Request::CreateWorkspace { .. } => (),
+            ext_workspace_group_handle_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(state: &mut D, _client: ClientId, group: &ExtWorkspaceGroupHandleV1, _data: &()) {
+        for instance in state.ext_workspace_manager_state().instances.iter_mut() {
+            instance.groups.retain(|_, grp| grp != group);
+        }
+    }
+}
+
+impl<D> Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData, D> for ExtWorkspaceManagerState
+where
+    D: Dispatch<ExtWorkspaceHandleV1, ExtWorkspaceHandleData> + ExtWorkspaceHandler + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _workspace: &ExtWorkspaceHandleV1,
+        request: <ExtWorkspaceHandleV1 as Resource>::Request,
+        data: &ExtWorkspaceHandleData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let request = match request {
+            ext_workspace_handle_v1::Request::Activate => WorkspaceRequest::Activate(data.id),
+            ext_workspace_handle_v1::Request::Deactivate => WorkspaceRequest::Deactivate(data.id),
+            // Assigning and removing workspaces aren't advertised as capabilities
+            ext_workspace_handle_v1::Request::Assign { .. }
+            | ext_workspace_handle_v1::Request::Remove
+            | ext_workspace_handle_v1::Request::Destroy => return,
+            _ => unreachable!(),
+        };
+
+        if let Some(manager_data) = data.manager.data::<ExtWorkspaceManagerData>() {
+            manager_data
+                .pending
+                .lock()
+                .expect("mutex poisoned")
+                .push(request);
+        }
+    }
+
+    fn destroyed(
+        state: &mut D,
+        _client: ClientId,
+        workspace: &ExtWorkspaceHandleV1,
+        _data: &ExtWorkspaceHandleData,
+    ) {
+        for instance in state.ext_workspace_manager_state().instances.iter_mut() {
+            instance.workspaces.retain(|_, ws| ws != workspace);
+        }
+    }
+}
+
+pub trait ExtWorkspaceHandler {
+    fn ext_workspace_manager_state(&mut self) -> &mut ExtWorkspaceManagerState;
+    /// A client requested that the workspace with the given id be activated.
+    fn activate_workspace(&mut self, id: u32);
+    /// A client requested that the workspace with the given id be deactivated.
+    fn deactivate_workspace(&mut self, id: u32);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_ext_workspace {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::ext_workspace::server::ext_workspace_manager_v1::ExtWorkspaceManagerV1: $crate::protocol::ext_workspace::ExtWorkspaceGlobalData
+        ] => $crate::protocol::ext_workspace::ExtWorkspaceManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::ext_workspace::server::ext_workspace_manager_v1::ExtWorkspaceManagerV1: $crate::protocol::ext_workspace::ExtWorkspaceManagerData
+        ] => $crate::protocol::ext_workspace::ExtWorkspaceManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::ext_workspace::server::ext_workspace_group_handle_v1::ExtWorkspaceGroupHandleV1: ()
+        ] => $crate::protocol::ext_workspace::ExtWorkspaceManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::ext_workspace::server::ext_workspace_handle_v1::ExtWorkspaceHandleV1: $crate::protocol::ext_workspace::ExtWorkspaceHandleData
+        ] => $crate::protocol::ext_workspace::ExtWorkspaceManagerState);
+    };
+}
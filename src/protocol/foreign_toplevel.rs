@@ -0,0 +1,408 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use smithay::{
+    output::Output,
+    reexports::{
+        wayland_protocols_wlr::foreign_toplevel::v1::server::{
+            zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+            zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+        },
+        wayland_server::{
+            self,
+            backend::ClientId,
+            protocol::{wl_output::WlOutput, wl_surface::WlSurface},
+            Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, Resource,
+        },
+    },
+};
+use tracing::error;
+
+const VERSION: u32 = 3;
+
+pub struct ForeignToplevelManagerState {
+    display: DisplayHandle,
+    instances: Vec<ZwlrForeignToplevelManagerV1>,
+    toplevels: HashMap<WlSurface, ToplevelData>,
+}
+
+pub struct ForeignToplevelManagerGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+/// The state of a toplevel as advertised to foreign toplevel clients.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForeignToplevelInfo {
+    pub title: Option<String>,
+    pub app_id: Option<String>,
+    pub output: Option<Output>,
+    pub activated: bool,
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+}
+
+impl ForeignToplevelInfo {
+    fn states(&self, version: u32) -> Vec<u8> {
+        let mut states = Vec::new();
+        if self.maximized {
+            states.push(zwlr_foreign_toplevel_handle_v1::State::Maximized);
+        }
+        if self.minimized {
+            states.push(zwlr_foreign_toplevel_handle_v1::State::Minimized);
+        }
+        if self.activated {
+            states.push(zwlr_foreign_toplevel_handle_v1::State::Activated);
+        }
+        if self.fullscreen && version >= 2 {
+            states.push(zwlr_foreign_toplevel_handle_v1::State::Fullscreen);
+        }
+
+        states
+            .into_iter()
+            .flat_map(|state| (state as u32).to_ne_bytes())
+            .collect()
+    }
+}
+
+struct ToplevelData {
+    info: ForeignToplevelInfo,
+    instances: Vec<ZwlrForeignToplevelHandleV1>,
+}
+
+impl ForeignToplevelManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<ZwlrForeignToplevelManagerV1, ForeignToplevelManagerGlobalData>
+            + Dispatch<ZwlrForeignToplevelManagerV1, ()>
+            + Dispatch<ZwlrForeignToplevelHandleV1, ()>
+            + ForeignToplevelHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = ForeignToplevelManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, ZwlrForeignToplevelManagerV1, _>(VERSION, global_data);
+        Self {
+            display: display.clone(),
+            instances: Vec::new(),
+            toplevels: HashMap::new(),
+        }
+    }
+
+    /// Get the surface of the toplevel that `handle` refers to.
+    pub fn surface_for_handle(&self, handle: &ZwlrForeignToplevelHandleV1) -> Option<WlSurface> {
+        self.toplevels
+            .iter()
+            .find(|(_, data)| data.instances.contains(handle))
+            .map(|(surface, _)| surface.clone())
+    }
+
+    /// Update advertised toplevels to match `toplevels`.
+    ///
+    /// New toplevels will be announced, changed ones will have their changes sent,
+    /// and ones that are no longer present will be closed.
+    pub fn refresh<D>(&mut self, toplevels: Vec<(WlSurface, ForeignToplevelInfo)>)
+    where
+        D: Dispatch<ZwlrForeignToplevelHandleV1, ()> + 'static,
+    {
+        self.toplevels.retain(|surface, data| {
+            let keep = toplevels.iter().any(|(surf, _)| surf == surface);
+            if !keep {
+                for instance in data.instances.iter() {
+                    instance.closed();
+                }
+            }
+            keep
+        });
+
+        for (surface, info) in toplevels {
+            match self.toplevels.entry(surface) {
+                Entry::Occupied(mut entry) => {
+                    let data = entry.get_mut();
+                    if data.info == info {
+                        continue;
+                    }
+
+                    for instance in data.instances.iter() {
+                        send_changes(instance, &data.info, &info);
+                    }
+
+                    data.info = info;
+                }
+                Entry::Vacant(entry) => {
+                    let mut data = ToplevelData {
+                        info,
+                        instances: Vec::new(),
+                    };
+
+                    for manager in self.instances.iter() {
+                        if let Some(instance) =
+                            new_toplevel_instance::<D>(&self.display, manager, &data.info)
+                        {
+                            data.instances.push(instance);
+                        }
+                    }
+
+                    entry.insert(data);
+                }
+            }
+        }
+    }
+}
+
+/// Send changed properties of a toplevel to a handle, followed by `done`.
+fn send_changes(
+    instance: &ZwlrForeignToplevelHandleV1,
+    old: &ForeignToplevelInfo,
+    new: &ForeignToplevelInfo,
+) {
+    if old.title != new.title {
+        if let Some(title) = new.title.clone() {
+            instance.title(title);
+        }
+    }
+
+    if old.app_id != new.app_id {
+        if let Some(app_id) = new.app_id.clone() {
+            instance.app_id(app_id);
+        }
+    }
+
+    if old.output != new.output {
+        if let Some(client) = instance.client() {
+            if let Some(output) = old.output.as_ref() {
+                for wl_output in output.client_outputs(&client) {
+                    instance.output_leave(&wl_output);
+                }
+            }
+            if let Some(output) = new.output.as_ref() {
+                for wl_output in output.client_outputs(&client) {
+                    instance.output_enter(&wl_output);
+                }
+            }
+        }
+    }
+
+    let version = instance.version();
+    if old.states(version) != new.states(version) {
+        instance.state(new.states(version));
+    }
+
+    instance.done();
+}
+
+/// Create a new handle for a toplevel on `manager`'s client and send its initial state.
+fn new_toplevel_instance<D>(
+    display: &DisplayHandle,
+    manager: &ZwlrForeignToplevelManagerV1,
+    info: &ForeignToplevelInfo,
+) -> Option<ZwlrForeignToplevelHandleV1>
+where
+    D: Dispatch<ZwlrForeignToplevelHandleV1, ()> + 'static,
+{
+    let client = manager.client()?;
+
+    let instance = match client.create_resource::<ZwlrForeignToplevelHandleV1, _, D>(
+        display,
+        manager.version(),
+        (),
+    ) {
+        Ok(instance) => instance,
+        Err(err) => {
+            error!("Failed to create foreign toplevel handle: {err}");
+            return None;
+        }
+    };
+
+    manager.toplevel(&instance);
+
+    if let Some(title) = info.title.clone() {
+        instance.title(title);
+    }
+    if let Some(app_id) = info.app_id.clone() {
+        instance.app_id(app_id);
+    }
+    if let Some(output) = info.output.as_ref() {
+        for wl_output in output.client_outputs(&client) {
+            instance.output_enter(&wl_output);
+        }
+    }
+    instance.state(info.states(instance.version()));
+    instance.done();
+
+    Some(instance)
+}
+
+impl<D> GlobalDispatch<ZwlrForeignToplevelManagerV1, ForeignToplevelManagerGlobalData, D>
+    for ForeignToplevelManagerState
+where
+    D: GlobalDispatch<ZwlrForeignToplevelManagerV1, ForeignToplevelManagerGlobalData>
+        + Dispatch<ZwlrForeignToplevelManagerV1, ()>
+        + Dispatch<ZwlrForeignToplevelHandleV1, ()>
+        + ForeignToplevelHandler
+        + 'static,
+{
+    fn bind(
+        state: &mut D,
+        handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<ZwlrForeignToplevelManagerV1>,
+        _global_data: &ForeignToplevelManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let manager = data_init.init(resource, ());
+
+        let manager_state = state.foreign_toplevel_manager_state();
+
+        for data in manager_state.toplevels.values_mut() {
+            if let Some(instance) = new_toplevel_instance::<D>(handle, &manager, &data.info) {
+                data.instances.push(instance);
+            }
+        }
+
+        manager_state.instances.push(manager);
+    }
+
+    fn can_view(client: Client, global_data: &ForeignToplevelManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<ZwlrForeignToplevelManagerV1, (), D> for ForeignToplevelManagerState
+where
+    D: Dispatch<ZwlrForeignToplevelManagerV1, ()> + ForeignToplevelHandler + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        manager: &ZwlrForeignToplevelManagerV1,
+        request: <ZwlrForeignToplevelManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwlr_foreign_toplevel_manager_v1::Request::Stop => {
+                state
+                    .foreign_toplevel_manager_state()
+                    .instances
+                    .retain(|instance| instance != manager);
+                manager.finished();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(
+        state: &mut D,
+        _client: ClientId,
+        manager: &ZwlrForeignToplevelManagerV1,
+        _data: &(),
+    ) {
+        state
+            .foreign_toplevel_manager_state()
+            .instances
+            .retain(|instance| instance != manager);
+    }
+}
+
+impl<D> Dispatch<ZwlrForeignToplevelHandleV1, (), D> for ForeignToplevelManagerState
+where
+    D: Dispatch<ZwlrForeignToplevelHandleV1, ()> + ForeignToplevelHandler + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &ZwlrForeignToplevelHandleV1,
+        request: <ZwlrForeignToplevelHandleV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let Some(surface) = state
+            .foreign_toplevel_manager_state()
+            .surface_for_handle(resource)
+        else {
+            return;
+        };
+
+        match request {
+            zwlr_foreign_toplevel_handle_v1::Request::SetMaximized => state.set_maximized(surface),
+            zwlr_foreign_toplevel_handle_v1::Request::UnsetMaximized => {
+                state.unset_maximized(surface)
+            }
+            zwlr_foreign_toplevel_handle_v1::Request::SetMinimized => state.set_minimized(surface),
+            zwlr_foreign_toplevel_handle_v1::Request::UnsetMinimized => {
+                state.unset_minimized(surface)
+            }
+            zwlr_foreign_toplevel_handle_v1::Request::Activate { seat: _ } => {
+                state.activate(surface)
+            }
+            zwlr_foreign_toplevel_handle_v1::Request::Close => state.close(surface),
+            zwlr_foreign_toplevel_handle_v1::Request::SetRectangle { .. } => (),
+            zwlr_foreign_toplevel_handle_v1::Request::Destroy => (),
+            zwlr_foreign_toplevel_handle_v1::Request::SetFullscreen { output } => {
+                state.set_fullscreen(surface, output)
+            }
+            zwlr_foreign_toplevel_handle_v1::Request::UnsetFullscreen => {
+                state.unset_fullscreen(surface)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(
+        state: &mut D,
+        _client: ClientId,
+        resource: &ZwlrForeignToplevelHandleV1,
+        _data: &(),
+    ) {
+        for data in state
+            .foreign_toplevel_manager_state()
+            .toplevels
+            .values_mut()
+        {
+            data.instances.retain(|instance| instance != resource);
+        }
+    }
+}
+
+pub trait ForeignToplevelHandler {
+    fn foreign_toplevel_manager_state(&mut self) -> &mut ForeignToplevelManagerState;
+    /// A client requested that the toplevel with the given surface be activated.
+    fn activate(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be closed.
+    fn close(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be fullscreened,
+    /// optionally on the given output.
+    fn set_fullscreen(&mut self, wl_surface: WlSurface, wl_output: Option<WlOutput>);
+    /// A client requested that the toplevel with the given surface be unfullscreened.
+    fn unset_fullscreen(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be maximized.
+    fn set_maximized(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be unmaximized.
+    fn unset_maximized(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be minimized.
+    fn set_minimized(&mut self, wl_surface: WlSurface);
+    /// A client requested that the toplevel with the given surface be unminimized.
+    fn unset_minimized(&mut self, wl_surface: WlSurface);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_foreign_toplevel {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::foreign_toplevel::v1::server::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1: $crate::protocol::foreign_toplevel::ForeignToplevelManagerGlobalData
+        ] => $crate::protocol::foreign_toplevel::ForeignToplevelManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::foreign_toplevel::v1::server::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1: ()
+        ] => $crate::protocol::foreign_toplevel::ForeignToplevelManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::foreign_toplevel::v1::server::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1: ()
+        ] => $crate::protocol::foreign_toplevel::ForeignToplevelManagerState);
+    };
+}
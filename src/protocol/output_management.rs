@@ -0,0 +1,795 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Mutex,
+};
+
+use smithay::{
+    output::{Mode, Output},
+    reexports::{
+        wayland_protocols_wlr::output_management::v1::server::{
+            zwlr_output_configuration_head_v1::{self, ZwlrOutputConfigurationHeadV1},
+            zwlr_output_configuration_v1::{self, ZwlrOutputConfigurationV1},
+            zwlr_output_head_v1::{self, AdaptiveSyncState, ZwlrOutputHeadV1},
+            zwlr_output_manager_v1::{self, ZwlrOutputManagerV1},
+            zwlr_output_mode_v1::{self, ZwlrOutputModeV1},
+        },
+        wayland_server::{
+            self, backend::ClientId, Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch,
+            Resource, WEnum,
+        },
+    },
+    utils::{Logical, Point, Transform},
+};
+use tracing::error;
+
+const VERSION: u32 = 4;
+
+pub struct OutputManagementManagerState {
+    display: DisplayHandle,
+    managers: Vec<ManagerData>,
+    outputs: HashMap<Output, OutputHeadInfo>,
+    serial: u32,
+}
+
+pub struct OutputManagementGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+/// The state of an output as advertised to output management clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputHeadInfo {
+    pub enabled: bool,
+    pub modes: Vec<Mode>,
+    pub current_mode: Option<Mode>,
+    pub preferred_mode: Option<Mode>,
+    pub location: Point<i32, Logical>,
+    pub transform: Transform,
+    pub scale: f64,
+}
+
+impl OutputHeadInfo {
+    pub fn from_output(output: &Output, enabled: bool) -> Self {
+        Self {
+            enabled,
+            modes: output.modes(),
+            current_mode: output.current_mode(),
+            preferred_mode: output.preferred_mode(),
+            location: output.current_location(),
+            transform: output.current_transform(),
+            scale: output.current_scale().fractional_scale(),
+        }
+    }
+}
+
+/// A requested configuration for a single output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputConfiguration {
+    /// The output should be disabled.
+    Disabled,
+    /// The output should be enabled with the given properties.
+    ///
+    /// Properties that are `None` should be left unchanged.
+    Enabled {
+        mode: Option<Mode>,
+        position: Option<Point<i32, Logical>>,
+        transform: Option<Transform>,
+        scale: Option<f64>,
+        adaptive_sync: Option<bool>,
+    },
+}
+
+struct ManagerData {
+    manager: ZwlrOutputManagerV1,
+    heads: HashMap<Output, HeadInstance>,
+}
+
+struct HeadInstance {
+    head: ZwlrOutputHeadV1,
+    modes: Vec<(Mode, ZwlrOutputModeV1)>,
+}
+
+impl HeadInstance {
+    fn finished(&self) {
+        for (_, wl_mode) in self.modes.iter() {
+            wl_mode.finished();
+        }
+        self.head.finished();
+    }
+}
+
+/// The state of a configuration object.
+pub struct OutputConfigurationData {
+    serial: u32,
+    used: bool,
+    heads: Vec<(Output, Option<ZwlrOutputConfigurationHeadV1>)>,
+}
+
+/// The properties a client set on a configuration head.
+#[derive(Default)]
+pub struct OutputConfigurationHeadData {
+    mode: Option<Mode>,
+    position: Option<Point<i32, Logical>>,
+    transform: Option<Transform>,
+    scale: Option<f64>,
+    adaptive_sync: Option<bool>,
+}
+
+impl OutputManagementManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<ZwlrOutputManagerV1, OutputManagementGlobalData>
+            + Dispatch<ZwlrOutputManagerV1, ()>
+            + Dispatch<ZwlrOutputHeadV1, Output>
+            + Dispatch<ZwlrOutputModeV1, Mode>
+            + Dispatch<ZwlrOutputConfigurationV1, Mutex<OutputConfigurationData>>
+            + Dispatch<ZwlrOutputConfigurationHeadV1, Mutex<OutputConfigurationHeadData>>
+            + OutputManagementHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = OutputManagementGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, ZwlrOutputManagerV1, _>(VERSION, global_data);
+        Self {
+            display: display.clone(),
+            managers: Vec::new(),
+            outputs: HashMap::new(),
+            serial: 0,
+        }
+    }
+
+    /// Update advertised outputs to match `outputs`, each paired with whether or not
+    /// it is enabled.
+    ///
+    /// New outputs will be announced, changed ones will have their changes sent,
+    /// and ones that are no longer present will be finished.
+    /// If anything changed, the configuration serial is bumped and sent to clients.
+    pub fn update<D>(&mut self, outputs: &[(Output, bool)])
+    where
+        D: Dispatch<ZwlrOutputHeadV1, Output> + Dispatch<ZwlrOutputModeV1, Mode> + 'static,
+    {
+        let mut changed = false;
+
+        self.outputs.retain(|output, _| {
+            let keep = outputs.iter().any(|(op, _)| op == output);
+            if !keep {
+                changed = true;
+                for manager_data in self.managers.iter_mut() {
+                    if let Some(instance) = manager_data.heads.remove(output) {
+                        instance.finished();
+                    }
+                }
+            }
+            keep
+        });
+
+        for (output, enabled) in outputs {
+            let info = OutputHeadInfo::from_output(output, *enabled);
+
+            match self.outputs.entry(output.clone()) {
+                Entry::Occupied(mut entry) => {
+                    if entry.get() == &info {
+                        continue;
+                    }
+
+                    changed = true;
+
+                    for manager_data in self.managers.iter_mut() {
+                        let Some(instance) = manager_data.heads.get_mut(output) else {
+                            continue;
+                        };
+                        send_changes::<D>(&self.display, instance, entry.get(), &info);
+                    }
+
+                    entry.insert(info);
+                }
+                Entry::Vacant(entry) => {
+                    changed = true;
+
+                    for manager_data in self.managers.iter_mut() {
+                        if let Some(instance) = new_head_instance::<D>(
+                            &self.display,
+                            &manager_data.manager,
+                            output,
+                            &info,
+                        ) {
+                            manager_data.heads.insert(output.clone(), instance);
+                        }
+                    }
+
+                    entry.insert(info);
+                }
+            }
+        }
+
+        if changed {
+            self.serial = self.serial.wrapping_add(1);
+            for manager_data in self.managers.iter() {
+                manager_data.manager.done(self.serial);
+            }
+        }
+    }
+}
+
+/// Create mode objects for `modes` on `head`'s client and advertise them on `head`.
+fn new_mode_instances<D>(
+    display: &DisplayHandle,
+    head: &ZwlrOutputHeadV1,
+    modes: &[Mode],
+    preferred_mode: Option<Mode>,
+) -> Vec<(Mode, ZwlrOutputModeV1)>
+where
+    D: Dispatch<ZwlrOutputModeV1, Mode> + 'static,
+{
+    let Some(client) = head.client() else {
+        return Vec::new();
+    };
+
+    modes
+        .iter()
+        .filter_map(|mode| {
+            let wl_mode = match client.create_resource::<ZwlrOutputModeV1, _, D>(
+                display,
+                head.version(),
+                *mode,
+            ) {
+                Ok(wl_mode) => wl_mode,
+                Err(err) => {
+                    error!("Failed to create output mode: {err}");
+                    return None;
+                }
+            };
+
+            head.mode(&wl_mode);
+            wl_mode.size(mode.size.w, mode.size.h);
+            if mode.refresh > 0 {
+                wl_mode.refresh(mode.refresh);
+            }
+            if Some(*mode) == preferred_mode {
+                wl_mode.preferred();
+            }
+
+            Some((*mode, wl_mode))
+        })
+        .collect()
+}
+
+/// Send the current mode of a head, if it was advertised.
+fn send_current_mode(instance: &HeadInstance, current_mode: Option<Mode>) {
+    if let Some((_, wl_mode)) = instance
+        .modes
+        .iter()
+        .find(|(mode, _)| Some(*mode) == current_mode)
+    {
+        instance.head.current_mode(wl_mode);
+    }
+}
+
+/// Send changed properties of an output to a head.
+fn send_changes<D>(
+    display: &DisplayHandle,
+    instance: &mut HeadInstance,
+    old: &OutputHeadInfo,
+    new: &OutputHeadInfo,
+) where
+    D: Dispatch<ZwlrOutputModeV1, Mode> + 'static,
+{
+    let enabled_changed = old.enabled != new.enabled;
+
+    if enabled_changed {
+        instance.head.enabled(new.enabled as i32);
+    }
+
+    let modes_changed = old.modes != new.modes || old.preferred_mode != new.preferred_mode;
+
+    if modes_changed {
+        for (_, wl_mode) in instance.modes.drain(..) {
+            wl_mode.finished();
+        }
+        instance.modes =
+            new_mode_instances::<D>(display, &instance.head, &new.modes, new.preferred_mode);
+    }
+
+    // The rest of the state is only sent for enabled heads, so a head that was just
+    // enabled gets all of it
+    if !new.enabled {
+        return;
+    }
+
+    if enabled_changed || modes_changed || old.current_mode != new.current_mode {
+        send_current_mode(instance, new.current_mode);
+    }
+
+    if enabled_changed || old.location != new.location {
+        instance.head.position(new.location.x, new.location.y);
+    }
+
+    if enabled_changed || old.transform != new.transform {
+        instance.head.transform(new.transform.into());
+    }
+
+    if enabled_changed || old.scale != new.scale {
+        instance.head.scale(new.scale);
+    }
+}
+
+/// Create a new head for an output on `manager`'s client and send its initial state.
+fn new_head_instance<D>(
+    display: &DisplayHandle,
+    manager: &ZwlrOutputManagerV1,
+    output: &Output,
+    info: &OutputHeadInfo,
+) -> Option<HeadInstance>
+where
+    D: Dispatch<ZwlrOutputHeadV1, Output> + Dispatch<ZwlrOutputModeV1, Mode> + 'static,
+{
+    let client = manager.client()?;
+
+    let head = match client.create_resource::<ZwlrOutputHeadV1, _, D>(
+        display,
+        manager.version(),
+        output.clone(),
+    ) {
+        Ok(head) => head,
+        Err(err) => {
+            error!("Failed to create output head: {err}");
+            return None;
+        }
+    };
+
+    manager.head(&head);
+
+    let physical_properties = output.physical_properties();
+
+    head.name(output.name());
+    head.description(output.description());
+    head.physical_size(physical_properties.size.w, physical_properties.size.h);
+
+    let instance = HeadInstance {
+        modes: new_mode_instances::<D>(display, &head, &info.modes, info.preferred_mode),
+        head,
+    };
+
+    instance.head.enabled(info.enabled as i32);
+    if info.enabled {
+        send_current_mode(&instance, info.current_mode);
+        instance.head.position(info.location.x, info.location.y);
+        instance.head.transform(info.transform.into());
+        instance.head.scale(info.scale);
+    }
+
+    if instance.head.version() >= zwlr_output_head_v1::EVT_MAKE_SINCE {
+        instance.head.make(physical_properties.make);
+        instance.head.model(physical_properties.model);
+    }
+
+    if instance.head.version() >= zwlr_output_head_v1::EVT_ADAPTIVE_SYNC_SINCE {
+        instance.head.adaptive_sync(AdaptiveSyncState::Disabled);
+    }
+
+    Some(instance)
+}
+
+impl<D> GlobalDispatch<ZwlrOutputManagerV1, OutputManagementGlobalData, D>
+    for OutputManagementManagerState
+where
+    D: GlobalDispatch<ZwlrOutputManagerV1, OutputManagementGlobalData>
+        + Dispatch<ZwlrOutputManagerV1, ()>
+        + Dispatch<ZwlrOutputHeadV1, Output>
+        + Dispatch<ZwlrOutputModeV1, Mode>
+        + OutputManagementHandler
+        + 'static,
+{
+    fn bind(
+        state: &mut D,
+        handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<ZwlrOutputManagerV1>,
+        _global_data: &OutputManagementGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let manager = data_init.init(resource, ());
+
+        let manager_state = state.output_management_manager_state();
+
+        let mut manager_data = ManagerData {
+            manager: manager.clone(),
+            heads: HashMap::new(),
+        };
+
+        for (output, info) in manager_state.outputs.iter() {
+            if let Some(instance) = new_head_instance::<D>(handle, &manager, output, info) {
+                manager_data.heads.insert(output.clone(), instance);
+            }
+        }
+
+        manager.done(manager_state.serial);
+
+        manager_state.managers.push(manager_data);
+    }
+
+    fn can_view(client: Client, global_data: &OutputManagementGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputManagerV1, (), D> for OutputManagementManagerState
+where
+    D: Dispatch<ZwlrOutputManagerV1, ()>
+        + Dispatch<ZwlrOutputConfigurationV1, Mutex<OutputConfigurationData>>
+        + OutputManagementHandler
+        + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        manager: &ZwlrOutputManagerV1,
+        request: <ZwlrOutputManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwlr_output_manager_v1::Request::CreateConfiguration { id, serial } => {
+                data_init.init(
+                    id,
+                    Mutex::new(OutputConfigurationData {
+                        serial,
+                        used: false,
+                        heads: Vec::new(),
+                    }),
+                );
+            }
+            zwlr_output_manager_v1::Request::Stop => {
+                state
+                    .output_management_manager_state()
+                    .managers
+                    .retain(|data| &data.manager != manager);
+                manager.finished();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(state: &mut D, _client: ClientId, manager: &ZwlrOutputManagerV1, _data: &()) {
+        state
+            .output_management_manager_state()
+            .managers
+            .retain(|data| &data.manager != manager);
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputHeadV1, Output, D> for OutputManagementManagerState
+where
+    D: Dispatch<ZwlrOutputHeadV1, Output> + OutputManagementHandler + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _resource: &ZwlrOutputHeadV1,
+        request: <ZwlrOutputHeadV1 as Resource>::Request,
+        _data: &Output,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwlr_output_head_v1::Request::Release => (),
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(state: &mut D, _client: ClientId, resource: &ZwlrOutputHeadV1, _data: &Output) {
+        for manager_data in state.output_management_manager_state().managers.iter_mut() {
+            manager_data
+                .heads
+                .retain(|_, instance| &instance.head != resource);
+        }
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputModeV1, Mode, D> for OutputManagementManagerState
+where
+    D: Dispatch<ZwlrOutputModeV1, Mode> + OutputManagementHandler + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _resource: &ZwlrOutputModeV1,
+        request: <ZwlrOutputModeV1 as Resource>::Request,
+        _data: &Mode,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwlr_output_mode_v1::Request::Release => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputConfigurationV1, Mutex<OutputConfigurationData>, D>
+    for OutputManagementManagerState
+where
+    D: Dispatch<ZwlrOutputConfigurationV1, Mutex<OutputConfigurationData>>
+        + Dispatch<ZwlrOutputConfigurationHeadV1, Mutex<OutputConfigurationHeadData>>
+        + OutputManagementHandler
+        + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &ZwlrOutputConfigurationV1,
+        request: <ZwlrOutputConfigurationV1 as Resource>::Request,
+        data: &Mutex<OutputConfigurationData>,
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let mut data = data
+            .lock()
+            .expect("failed to lock output configuration data");
+
+        match request {
+            zwlr_output_configuration_v1::Request::EnableHead { id, head } => {
+                let config_head = data_init.init(id, Mutex::new(Default::default()));
+
+                let Some(output) = head.data::<Output>().cloned() else {
+                    return;
+                };
+
+                if data.heads.iter().any(|(op, _)| op == &output) {
+                    resource.post_error(
+                        zwlr_output_configuration_v1::Error::AlreadyConfiguredHead,
+                        "head was already configured",
+                    );
+                    return;
+                }
+
+                data.heads.push((output, Some(config_head)));
+            }
+            zwlr_output_configuration_v1::Request::DisableHead { head } => {
+                let Some(output) = head.data::<Output>().cloned() else {
+                    return;
+                };
+
+                if data.heads.iter().any(|(op, _)| op == &output) {
+                    resource.post_error(
+                        zwlr_output_configuration_v1::Error::AlreadyConfiguredHead,
+                        "head was already configured",
+                    );
+                    return;
+                }
+
+                data.heads.push((output, None));
+            }
+            req @ (zwlr_output_configuration_v1::Request::Apply
+            | zwlr_output_configuration_v1::Request::Test) => {
+                if data.used {
+                    resource.post_error(
+                        zwlr_output_configuration_v1::Error::AlreadyUsed,
+                        "configuration was already applied or tested",
+                    );
+                    return;
+                }
+
+                data.used = true;
+
+                let manager_state = state.output_management_manager_state();
+
+                if data.serial != manager_state.serial {
+                    resource.cancelled();
+                    return;
+                }
+
+                let all_heads_configured = manager_state
+                    .outputs
+                    .keys()
+                    .all(|output| data.heads.iter().any(|(op, _)| op == output));
+
+                if !all_heads_configured {
+                    resource.post_error(
+                        zwlr_output_configuration_v1::Error::UnconfiguredHead,
+                        "not all heads were configured",
+                    );
+                    return;
+                }
+
+                let configs = data
+                    .heads
+                    .iter()
+                    .map(|(output, config_head)| {
+                        let config = match config_head {
+                            Some(config_head) => {
+                                let head_data = config_head
+                                    .data::<Mutex<OutputConfigurationHeadData>>()
+                                    .expect("configuration head had no data")
+                                    .lock()
+                                    .expect("failed to lock output configuration head data");
+
+                                OutputConfiguration::Enabled {
+                                    mode: head_data.mode,
+                                    position: head_data.position,
+                                    transform: head_data.transform,
+                                    scale: head_data.scale,
+                                    adaptive_sync: head_data.adaptive_sync,
+                                }
+                            }
+                            None => OutputConfiguration::Disabled,
+                        };
+                        (output.clone(), config)
+                    })
+                    .collect::<HashMap<_, _>>();
+
+                let test_only = matches!(req, zwlr_output_configuration_v1::Request::Test);
+
+                if state.apply_configuration(configs, test_only) {
+                    resource.succeeded();
+                } else {
+                    resource.failed();
+                }
+            }
+            zwlr_output_configuration_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<ZwlrOutputConfigurationHeadV1, Mutex<OutputConfigurationHeadData>, D>
+    for OutputManagementManagerState
+where
+    D: Dispatch<ZwlrOutputConfigurationHeadV1, Mutex<OutputConfigurationHeadData>>
+        + OutputManagementHandler
+        + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        resource: &ZwlrOutputConfigurationHeadV1,
+        request: <ZwlrOutputConfigurationHeadV1 as Resource>::Request,
+        data: &Mutex<OutputConfigurationHeadData>,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let mut data = data
+            .lock()
+            .expect("failed to lock output configuration head data");
+
+        let already_set = |resource: &ZwlrOutputConfigurationHeadV1| {
+            resource.post_error(
+                zwlr_output_configuration_head_v1::Error::AlreadySet,
+                "property was already set",
+            );
+        };
+
+        match request {
+            zwlr_output_configuration_head_v1::Request::SetMode { mode } => {
+                if data.mode.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                let Some(mode) = mode.data::<Mode>() else {
+                    resource.post_error(
+                        zwlr_output_configuration_head_v1::Error::InvalidMode,
+                        "invalid mode",
+                    );
+                    return;
+                };
+                data.mode = Some(*mode);
+            }
+            zwlr_output_configuration_head_v1::Request::SetCustomMode {
+                width,
+                height,
+                refresh,
+            } => {
+                if data.mode.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                if width <= 0 || height <= 0 || refresh < 0 {
+                    resource.post_error(
+                        zwlr_output_configuration_head_v1::Error::InvalidCustomMode,
+                        "invalid custom mode",
+                    );
+                    return;
+                }
+                data.mode = Some(Mode {
+                    size: (width, height).into(),
+                    refresh,
+                });
+            }
+            zwlr_output_configuration_head_v1::Request::SetPosition { x, y } => {
+                if data.position.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                data.position = Some((x, y).into());
+            }
+            zwlr_output_configuration_head_v1::Request::SetTransform { transform } => {
+                if data.transform.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                let WEnum::Value(transform) = transform else {
+                    resource.post_error(
+                        zwlr_output_configuration_head_v1::Error::InvalidTransform,
+                        "invalid transform",
+                    );
+                    return;
+                };
+                data.transform = Some(transform.into());
+            }
+            zwlr_output_configuration_head_v1::Request::SetScale { scale } => {
+                if data.scale.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                if scale <= 0.0 {
+                    resource.post_error(
+                        zwlr_output_configuration_head_v1::Error::InvalidScale,
+                        "invalid scale",
+                    );
+                    return;
+                }
+                data.scale = Some(scale);
+            }
+            zwlr_output_configuration_head_v1::Request::SetAdaptiveSync { state } => {
+                if data.adaptive_sync.is_some() {
+                    already_set(resource);
+                    return;
+                }
+                let WEnum::Value(state) = state else {
+                    resource.post_error(
+                        zwlr_output_configuration_head_v1::Error::InvalidAdaptiveSyncState,
+                        "invalid adaptive sync state",
+                    );
+                    return;
+                };
+                data.adaptive_sync = Some(state == AdaptiveSyncState::Enabled);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+pub trait OutputManagementHandler {
+    fn output_management_manager_state(&mut self) -> &mut OutputManagementManagerState;
+    /// A client requested that the given configuration be applied to outputs,
+    /// or only tested if `test_only` is true.
+    ///
+    /// Returns whether or not the configuration was (or would be) applied successfully.
+    fn apply_configuration(
+        &mut self,
+        config: HashMap<Output, OutputConfiguration>,
+        test_only: bool,
+    ) -> bool;
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_output_management {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_manager_v1::ZwlrOutputManagerV1: $crate::protocol::output_management::OutputManagementGlobalData
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_manager_v1::ZwlrOutputManagerV1: ()
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_head_v1::ZwlrOutputHeadV1: smithay::output::Output
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_mode_v1::ZwlrOutputModeV1: smithay::output::Mode
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_configuration_v1::ZwlrOutputConfigurationV1: std::sync::Mutex<$crate::protocol::output_management::OutputConfigurationData>
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::output_management::v1::server::zwlr_output_configuration_head_v1::ZwlrOutputConfigurationHeadV1: std::sync::Mutex<$crate::protocol::output_management::OutputConfigurationHeadData>
+        ] => $crate::protocol::output_management::OutputManagementManagerState);
+    };
+}
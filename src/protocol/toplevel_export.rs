@@ -0,0 +1,400 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::UNIX_EPOCH,
+};
+
+use smithay::{
+    backend::{
+        allocator::Buffer,
+        renderer::{buffer_type, BufferType},
+    },
+    reexports::{
+        wayland_protocols_wlr::foreign_toplevel::v1::server::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        wayland_server::{
+            self,
+            protocol::{wl_buffer::WlBuffer, wl_shm, wl_surface::WlSurface},
+            Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, Resource,
+        },
+    },
+    utils::{Physical, Rectangle, Size},
+    wayland::{
+        dmabuf::get_dmabuf,
+        shm::{self, shm_format_to_fourcc},
+    },
+};
+use tracing::trace;
+
+use super::foreign_toplevel::ForeignToplevelHandler;
+
+use self::server::{
+    hyprland_toplevel_export_frame_v1::{self, HyprlandToplevelExportFrameV1},
+    hyprland_toplevel_export_manager_v1::{self, HyprlandToplevelExportManagerV1},
+};
+
+#[allow(missing_docs, dead_code, non_camel_case_types, non_upper_case_globals)]
+#[allow(unused_imports, clippy::all)]
+pub mod server {
+    use smithay::reexports::wayland_protocols_wlr::foreign_toplevel::v1::server::*;
+    use smithay::reexports::wayland_server;
+    use smithay::reexports::wayland_server::protocol::*;
+
+    pub mod __interfaces {
+        use smithay::reexports::wayland_protocols_wlr::foreign_toplevel::v1::__interfaces::*;
+        use smithay::reexports::wayland_server::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!(
+            "resources/protocols/hyprland-toplevel-export-v1.xml"
+        );
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_server_code!("resources/protocols/hyprland-toplevel-export-v1.xml");
+}
+
+const VERSION: u32 = 2;
+
+pub struct ToplevelExportManagerState;
+
+pub struct ToplevelExportManagerGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+impl ToplevelExportManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<HyprlandToplevelExportManagerV1, ToplevelExportManagerGlobalData>
+            + Dispatch<HyprlandToplevelExportManagerV1, ()>
+            + Dispatch<HyprlandToplevelExportFrameV1, ToplevelExportFrameState>
+            + ToplevelExportHandler
+            + ForeignToplevelHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = ToplevelExportManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, HyprlandToplevelExportManagerV1, _>(VERSION, global_data);
+        Self
+    }
+}
+
+impl<D> GlobalDispatch<HyprlandToplevelExportManagerV1, ToplevelExportManagerGlobalData, D>
+    for ToplevelExportManagerState
+where
+    D: GlobalDispatch<HyprlandToplevelExportManagerV1, ToplevelExportManagerGlobalData>
+        + Dispatch<HyprlandToplevelExportManagerV1, ()>
+        + Dispatch<HyprlandToplevelExportFrameV1, ToplevelExportFrameState>
+        + ToplevelExportHandler
+        + ForeignToplevelHandler
+        + 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<HyprlandToplevelExportManagerV1>,
+        _global_data: &ToplevelExportManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &ToplevelExportManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<HyprlandToplevelExportManagerV1, (), D> for ToplevelExportManagerState
+where
+    D: Dispatch<HyprlandToplevelExportManagerV1, ()>
+        + Dispatch<HyprlandToplevelExportFrameV1, ToplevelExportFrameState>
+        + ToplevelExportHandler
+        + ForeignToplevelHandler
+        + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        _manager: &HyprlandToplevelExportManagerV1,
+        request: <HyprlandToplevelExportManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let (frame, overlay_cursor, toplevel) = match request {
+            hyprland_toplevel_export_manager_v1::Request::CaptureToplevel {
+                frame,
+                overlay_cursor,
+                handle,
+            } => (frame, overlay_cursor, state.toplevel_for_handle(handle)),
+            hyprland_toplevel_export_manager_v1::Request::CaptureToplevelWithWlrToplevelHandle {
+                frame,
+                overlay_cursor,
+                handle,
+            } => (
+                frame,
+                overlay_cursor,
+                surface_for_wlr_handle(state, &handle),
+            ),
+            hyprland_toplevel_export_manager_v1::Request::Destroy => return,
+            _ => unreachable!(),
+        };
+
+        let Some((toplevel, size)) = toplevel.and_then(|toplevel| {
+            let size = state.toplevel_export_size(&toplevel)?;
+            Some((toplevel, size))
+        }) else {
+            trace!("Toplevel export client requested a nonexistent toplevel");
+            let frame = data_init.init(frame, ToplevelExportFrameState::Failed);
+            frame.failed();
+            return;
+        };
+
+        if size.w <= 0 || size.h <= 0 {
+            trace!("Toplevel export client requested a toplevel with no size");
+            let frame = data_init.init(frame, ToplevelExportFrameState::Failed);
+            frame.failed();
+            return;
+        }
+
+        let info = ToplevelExportFrameInfo {
+            toplevel,
+            size,
+            overlay_cursor: overlay_cursor != 0,
+        };
+        let frame = data_init.init(
+            frame,
+            ToplevelExportFrameState::Pending {
+                info,
+                copied: Arc::new(AtomicBool::new(false)),
+            },
+        );
+
+        // Send desired SHM buffer parameters.
+        frame.buffer(
+            wl_shm::Format::Argb8888,
+            size.w as u32,
+            size.h as u32,
+            size.w as u32 * 4,
+        );
+
+        // Send desired DMA buffer parameters.
+        frame.linux_dmabuf(
+            smithay::backend::allocator::Fourcc::Argb8888 as u32,
+            size.w as u32,
+            size.h as u32,
+        );
+
+        // Notify client that all supported buffers were enumerated.
+        frame.buffer_done();
+    }
+}
+
+fn surface_for_wlr_handle<D>(
+    state: &mut D,
+    handle: &ZwlrForeignToplevelHandleV1,
+) -> Option<WlSurface>
+where
+    D: ForeignToplevelHandler,
+{
+    state
+        .foreign_toplevel_manager_state()
+        .surface_for_handle(handle)
+}
+
+pub trait ToplevelExportHandler {
+    /// Get the toplevel surface referred to by the `handle` of a `capture_toplevel` request.
+    fn toplevel_for_handle(&mut self, handle: u32) -> Option<WlSurface>;
+    /// Get the size of the buffer a capture of `toplevel` needs.
+    fn toplevel_export_size(&mut self, toplevel: &WlSurface) -> Option<Size<i32, Physical>>;
+    fn frame(&mut self, frame: ToplevelExport);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_toplevel_export {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::toplevel_export::server::hyprland_toplevel_export_manager_v1::HyprlandToplevelExportManagerV1: $crate::protocol::toplevel_export::ToplevelExportManagerGlobalData
+        ] => $crate::protocol::toplevel_export::ToplevelExportManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::toplevel_export::server::hyprland_toplevel_export_manager_v1::HyprlandToplevelExportManagerV1: ()
+        ] => $crate::protocol::toplevel_export::ToplevelExportManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            $crate::protocol::toplevel_export::server::hyprland_toplevel_export_frame_v1::HyprlandToplevelExportFrameV1: $crate::protocol::toplevel_export::ToplevelExportFrameState
+        ] => $crate::protocol::toplevel_export::ToplevelExportManagerState);
+    };
+}
+
+#[derive(Clone, Debug)]
+pub struct ToplevelExportFrameInfo {
+    toplevel: WlSurface,
+    size: Size<i32, Physical>,
+    overlay_cursor: bool,
+}
+
+pub enum ToplevelExportFrameState {
+    Failed,
+    Pending {
+        info: ToplevelExportFrameInfo,
+        copied: Arc<AtomicBool>,
+    },
+}
+
+impl<D> Dispatch<HyprlandToplevelExportFrameV1, ToplevelExportFrameState, D>
+    for ToplevelExportManagerState
+where
+    D: Dispatch<HyprlandToplevelExportFrameV1, ToplevelExportFrameState>
+        + ToplevelExportHandler
+        + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        frame: &HyprlandToplevelExportFrameV1,
+        request: <HyprlandToplevelExportFrameV1 as Resource>::Request,
+        data: &ToplevelExportFrameState,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        if matches!(request, hyprland_toplevel_export_frame_v1::Request::Destroy) {
+            return;
+        }
+
+        let (info, copied) = match data {
+            ToplevelExportFrameState::Failed => return,
+            ToplevelExportFrameState::Pending { info, copied } => (info, copied),
+        };
+
+        if copied.load(Ordering::SeqCst) {
+            frame.post_error(
+                hyprland_toplevel_export_frame_v1::Error::AlreadyUsed,
+                "copy was already requested",
+            );
+            return;
+        }
+
+        let (buffer, with_damage) = match request {
+            hyprland_toplevel_export_frame_v1::Request::Copy {
+                buffer,
+                ignore_damage,
+            } => (buffer, ignore_damage == 0),
+            _ => unreachable!(),
+        };
+
+        let buffer_valid = match buffer_type(&buffer) {
+            Some(BufferType::Shm) => {
+                shm::with_buffer_contents(&buffer, |_buf, shm_len, buffer_data| {
+                    buffer_data.format == wl_shm::Format::Argb8888
+                        && buffer_data.stride == info.size.w * 4
+                        && buffer_data.height == info.size.h
+                        && shm_len as i32 == buffer_data.stride * buffer_data.height
+                })
+                .unwrap_or(false)
+            }
+            Some(BufferType::Dma) => get_dmabuf(&buffer).is_ok_and(|dmabuf| {
+                Some(dmabuf.format().code) == shm_format_to_fourcc(wl_shm::Format::Argb8888)
+                    && dmabuf.width() == info.size.w as u32
+                    && dmabuf.height() == info.size.h as u32
+            }),
+            _ => false,
+        };
+
+        if !buffer_valid {
+            frame.post_error(
+                hyprland_toplevel_export_frame_v1::Error::InvalidBuffer,
+                "invalid buffer",
+            );
+            return;
+        }
+
+        copied.store(true, Ordering::SeqCst);
+
+        state.frame(ToplevelExport {
+            with_damage,
+            buffer,
+            frame: frame.clone(),
+            info: info.clone(),
+            submitted: false,
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct ToplevelExport {
+    info: ToplevelExportFrameInfo,
+    frame: HyprlandToplevelExportFrameV1,
+    with_damage: bool,
+    buffer: WlBuffer,
+    submitted: bool,
+}
+
+// If `ToplevelExport::submit` wasn't called, send the failed event.
+impl Drop for ToplevelExport {
+    fn drop(&mut self) {
+        if !self.submitted {
+            self.frame.failed();
+        }
+    }
+}
+
+impl ToplevelExport {
+    pub fn buffer(&self) -> &WlBuffer {
+        &self.buffer
+    }
+
+    /// Get the surface of the toplevel being captured.
+    pub fn toplevel(&self) -> &WlSurface {
+        &self.info.toplevel
+    }
+
+    /// Get the physical size of the buffer.
+    pub fn size(&self) -> Size<i32, Physical> {
+        self.info.size
+    }
+
+    pub fn overlay_cursor(&self) -> bool {
+        self.info.overlay_cursor
+    }
+
+    /// Get whether or not this capture should wait until the toplevel is damaged.
+    pub fn with_damage(&self) -> bool {
+        self.with_damage
+    }
+
+    /// Mark damaged regions of the buffer.
+    pub fn damage(&mut self, damage: &[Rectangle<i32, Physical>]) {
+        if !self.with_damage {
+            return;
+        }
+
+        for Rectangle { loc, size } in damage {
+            self.frame
+                .damage(loc.x as u32, loc.y as u32, size.w as u32, size.h as u32);
+        }
+    }
+
+    /// Submit the copied content.
+    pub fn submit(mut self, y_invert: bool) {
+        self.frame.flags(if y_invert {
+            hyprland_toplevel_export_frame_v1::Flags::YInvert
+        } else {
+            hyprland_toplevel_export_frame_v1::Flags::empty()
+        });
+
+        let time = UNIX_EPOCH
+            .elapsed()
+            .expect("failed to get time since UNIX_EPOCH");
+        let tv_sec_hi = (time.as_secs() >> 32) as u32;
+        let tv_sec_lo = (time.as_secs() & 0xFFFFFFFF) as u32;
+        let tv_nsec = time.subsec_nanos();
+        self.frame.ready(tv_sec_hi, tv_sec_lo, tv_nsec);
+
+        // Mark frame as submitted to ensure destructor isn't run.
+        self.submitted = true;
+    }
+}
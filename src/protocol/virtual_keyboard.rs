@@ -0,0 +1,398 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    os::{fd::OwnedFd, unix::fs::FileExt},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::Context;
+use smithay::{
+    backend::input::KeyState,
+    reexports::{
+        wayland_protocols_misc::zwp_virtual_keyboard_v1::server::{
+            zwp_virtual_keyboard_manager_v1::{self, ZwpVirtualKeyboardManagerV1},
+            zwp_virtual_keyboard_v1::{self, ZwpVirtualKeyboardV1},
+        },
+        wayland_server::{
+            self, backend::ClientId, protocol::wl_keyboard::KeymapFormat, Client, DataInit,
+            Dispatch, DisplayHandle, GlobalDispatch, Resource, WEnum,
+        },
+    },
+};
+use tracing::warn;
+use xkbcommon::xkb;
+
+const VERSION: u32 = 1;
+
+/// The largest keymap a virtual keyboard may send.
+const MAX_KEYMAP_SIZE: u32 = 1024 * 1024;
+
+pub struct VirtualKeyboardManagerState;
+
+pub struct VirtualKeyboardManagerGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+impl VirtualKeyboardManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<ZwpVirtualKeyboardManagerV1, VirtualKeyboardManagerGlobalData>
+            + Dispatch<ZwpVirtualKeyboardManagerV1, ()>
+            + Dispatch<ZwpVirtualKeyboardV1, VirtualKeyboardData>
+            + VirtualKeyboardHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = VirtualKeyboardManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, ZwpVirtualKeyboardManagerV1, _>(VERSION, global_data);
+        Self
+    }
+}
+
+/// Data attached to a virtual keyboard.
+#[derive(Debug, Default)]
+pub struct VirtualKeyboardData {
+    /// The xkb keymap the client sent, if it has sent one yet.
+    ///
+    /// The protocol requires a keymap before any key events.
+    keymap: Mutex<Option<Arc<str>>>,
+    /// What this keyboard currently holds down, so it can be released on destruction.
+    held: Mutex<HeldInput>,
+}
+
+#[derive(Debug, Default)]
+struct HeldInput {
+    /// Keycodes that are pressed.
+    keys: HashSet<u32>,
+    /// Whether depressed or latched modifiers are set.
+    mods: bool,
+    /// The locked modifiers and group, which are kept when releasing held modifiers.
+    locked: u32,
+    group: u32,
+    /// The timestamp of the last key event.
+    time: u32,
+}
+
+impl VirtualKeyboardData {
+    fn keymap(&self) -> Option<Arc<str>> {
+        self.keymap
+            .lock()
+            .expect("keymap mutex was poisoned")
+            .clone()
+    }
+
+    fn held(&self) -> MutexGuard<'_, HeldInput> {
+        self.held.lock().expect("held input mutex was poisoned")
+    }
+}
+
+/// Read the keymap a client sent and make sure it compiles.
+fn read_keymap(fd: OwnedFd, size: u32) -> anyhow::Result<Arc<str>> {
+    anyhow::ensure!(size <= MAX_KEYMAP_SIZE, "keymap is too large");
+
+    // The fd may have been written to, so read from the start instead of the current offset
+    let mut buf = vec![0; size as usize];
+    File::from(fd)
+        .read_exact_at(&mut buf, 0)
+        .context("failed to read keymap")?;
+
+    // Keymaps are null-terminated
+    let len = buf.iter().position(|byte| *byte == 0).unwrap_or(buf.len());
+    buf.truncate(len);
+    let keymap = String::from_utf8(buf).context("keymap isn't valid utf-8")?;
+
+    let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+    xkb::Keymap::new_from_string(
+        &context,
+        keymap.clone(),
+        xkb::KEYMAP_FORMAT_TEXT_V1,
+        xkb::KEYMAP_COMPILE_NO_FLAGS,
+    )
+    .context("keymap failed to compile")?;
+
+    Ok(keymap.into())
+}
+
+impl<D> GlobalDispatch<ZwpVirtualKeyboardManagerV1, VirtualKeyboardManagerGlobalData, D>
+    for VirtualKeyboardManagerState
+where
+    D: GlobalDispatch<ZwpVirtualKeyboardManagerV1, VirtualKeyboardManagerGlobalData>
+        + Dispatch<ZwpVirtualKeyboardManagerV1, ()>
+        + Dispatch<ZwpVirtualKeyboardV1, VirtualKeyboardData>
+        + VirtualKeyboardHandler
+        + 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<ZwpVirtualKeyboardManagerV1>,
+        _global_data: &VirtualKeyboardManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &VirtualKeyboardManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<ZwpVirtualKeyboardManagerV1, (), D> for VirtualKeyboardManagerState
+where
+    D: Dispatch<ZwpVirtualKeyboardManagerV1, ()>
+        + Dispatch<ZwpVirtualKeyboardV1, VirtualKeyboardData>
+        + VirtualKeyboardHandler
+        + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _manager: &ZwpVirtualKeyboardManagerV1,
+        request: <ZwpVirtualKeyboardManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            // There is only one seat, so the requested one is ignored
+            zwp_virtual_keyboard_manager_v1::Request::CreateVirtualKeyboard { seat: _, id } => {
+                data_init.init(id, VirtualKeyboardData::default());
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<ZwpVirtualKeyboardV1, VirtualKeyboardData, D> for VirtualKeyboardManagerState
+where
+    D: Dispatch<ZwpVirtualKeyboardV1, VirtualKeyboardData> + VirtualKeyboardHandler + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        keyboard: &ZwpVirtualKeyboardV1,
+        request: <ZwpVirtualKeyboardV1 as Resource>::Request,
+        data: &VirtualKeyboardData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwp_virtual_keyboard_v1::Request::Keymap { format, fd, size } => {
+                if format != WEnum::Value(KeymapFormat::XkbV1) {
+                    keyboard.post_error(
+                        zwp_virtual_keyboard_v1::Error::NoKeymap,
+                        "only xkb_v1 keymaps are supported",
+                    );
+                    return;
+                }
+
+                match read_keymap(fd, size) {
+                    Ok(keymap) => {
+                        *data.keymap.lock().expect("keymap mutex was poisoned") = Some(keymap);
+                    }
+                    Err(err) => {
+                        warn!("Virtual keyboard sent an unusable keymap: {err:#}");
+                        keyboard.post_error(
+                            zwp_virtual_keyboard_v1::Error::NoKeymap,
+                            format!("unusable keymap: {err}"),
+                        );
+                    }
+                }
+            }
+            zwp_virtual_keyboard_v1::Request::Key {
+                time,
+                key,
+                state: key_state,
+            } => {
+                let Some(keymap) = data.keymap() else {
+                    keyboard.post_error(
+                        zwp_virtual_keyboard_v1::Error::NoKeymap,
+                        "no keymap was set before sending keys",
+                    );
+                    return;
+                };
+
+                let key_state = match key_state {
+                    0 => KeyState::Released,
+                    _ => KeyState::Pressed,
+                };
+
+                {
+                    let mut held = data.held();
+                    held.time = time;
+                    match key_state {
+                        KeyState::Pressed => held.keys.insert(key),
+                        KeyState::Released => held.keys.remove(&key),
+                    };
+                }
+
+                state.virtual_keyboard_key(time, key, key_state, &keymap);
+            }
+            zwp_virtual_keyboard_v1::Request::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+            } => {
+                let Some(keymap) = data.keymap() else {
+                    keyboard.post_error(
+                        zwp_virtual_keyboard_v1::Error::NoKeymap,
+                        "no keymap was set before sending modifiers",
+                    );
+                    return;
+                };
+
+                {
+                    let mut held = data.held();
+                    held.mods = mods_depressed != 0 || mods_latched != 0;
+                    held.locked = mods_locked;
+                    held.group = group;
+                }
+
+                state.virtual_keyboard_modifiers(
+                    mods_depressed,
+                    mods_latched,
+                    mods_locked,
+                    group,
+                    &keymap,
+                );
+            }
+            zwp_virtual_keyboard_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+
+    fn destroyed(
+        state: &mut D,
+        _client: ClientId,
+        _keyboard: &ZwpVirtualKeyboardV1,
+        data: &VirtualKeyboardData,
+    ) {
+        // Release everything the keyboard left held so keys and modifiers don't get stuck
+        release_held_input(state, data);
+    }
+}
+
+/// Release the keys and depressed or latched modifiers a virtual keyboard is holding.
+///
+/// Locked modifiers and the group are left as they are.
+fn release_held_input(state: &mut impl VirtualKeyboardHandler, data: &VirtualKeyboardData) {
+    let Some(keymap) = data.keymap() else {
+        return;
+    };
+
+    let held = std::mem::take(&mut *data.held());
+
+    for key in held.keys {
+        state.virtual_keyboard_key(held.time, key, KeyState::Released, &keymap);
+    }
+
+    if held.mods {
+        state.virtual_keyboard_modifiers(0, 0, held.locked, held.group, &keymap);
+    }
+}
+
+pub trait VirtualKeyboardHandler {
+    /// A virtual keyboard pressed or released a key.
+    ///
+    /// `time` is in milliseconds and `key` is an evdev keycode in the virtual keyboard's
+    /// xkb `keymap`.
+    fn virtual_keyboard_key(&mut self, time: u32, key: u32, state: KeyState, keymap: &Arc<str>);
+
+    /// A virtual keyboard set the modifier state.
+    ///
+    /// The modifier masks and group are in terms of the virtual keyboard's xkb `keymap`.
+    fn virtual_keyboard_modifiers(
+        &mut self,
+        depressed: u32,
+        latched: u32,
+        locked: u32,
+        group: u32,
+        keymap: &Arc<str>,
+    );
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_virtual_keyboard {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_misc::zwp_virtual_keyboard_v1::server::zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1: $crate::protocol::virtual_keyboard::VirtualKeyboardManagerGlobalData
+        ] => $crate::protocol::virtual_keyboard::VirtualKeyboardManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_misc::zwp_virtual_keyboard_v1::server::zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1: ()
+        ] => $crate::protocol::virtual_keyboard::VirtualKeyboardManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_misc::zwp_virtual_keyboard_v1::server::zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1: $crate::protocol::virtual_keyboard::VirtualKeyboardData
+        ] => $crate::protocol::virtual_keyboard::VirtualKeyboardManagerState);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        released_keys: Vec<(u32, u32)>,
+        modifiers: Vec<(u32, u32, u32, u32)>,
+    }
+
+    impl VirtualKeyboardHandler for RecordingHandler {
+        fn virtual_keyboard_key(
+            &mut self,
+            time: u32,
+            key: u32,
+            state: KeyState,
+            _keymap: &Arc<str>,
+        ) {
+            assert_eq!(state, KeyState::Released);
+            self.released_keys.push((time, key));
+        }
+
+        fn virtual_keyboard_modifiers(
+            &mut self,
+            depressed: u32,
+            latched: u32,
+            locked: u32,
+            group: u32,
+            _keymap: &Arc<str>,
+        ) {
+            self.modifiers.push((depressed, latched, locked, group));
+        }
+    }
+
+    #[test]
+    fn destroying_releases_held_keys_and_modifiers() {
+        let data = VirtualKeyboardData {
+            keymap: Mutex::new(Some(Arc::from("keymap"))),
+            held: Mutex::new(HeldInput {
+                keys: HashSet::from([30, 31]),
+                mods: true,
+                locked: 2,
+                group: 1,
+                time: 42,
+            }),
+        };
+
+        let mut handler = RecordingHandler::default();
+        release_held_input(&mut handler, &data);
+
+        handler.released_keys.sort();
+        assert_eq!(handler.released_keys, vec![(42, 30), (42, 31)]);
+        // Locked modifiers and the group stay
+        assert_eq!(handler.modifiers, vec![(0, 0, 2, 1)]);
+
+        // Nothing is held anymore
+        let mut handler = RecordingHandler::default();
+        release_held_input(&mut handler, &data);
+
+        assert!(handler.released_keys.is_empty());
+        assert!(handler.modifiers.is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::Mutex;
+
+use smithay::{
+    backend::input::{Axis, AxisSource, ButtonState},
+    output::Output,
+    reexports::{
+        wayland_protocols_wlr::virtual_pointer::v1::server::{
+            zwlr_virtual_pointer_manager_v1::{self, ZwlrVirtualPointerManagerV1},
+            zwlr_virtual_pointer_v1::{self, ZwlrVirtualPointerV1},
+        },
+        wayland_server::{
+            self, protocol::wl_pointer, Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch,
+            Resource, WEnum,
+        },
+    },
+    utils::{Logical, Point},
+};
+
+const VERSION: u32 = 2;
+
+pub struct VirtualPointerManagerState;
+
+pub struct VirtualPointerManagerGlobalData {
+    filter: Box<dyn Fn(&Client) -> bool + Send + Sync>,
+}
+
+impl VirtualPointerManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<ZwlrVirtualPointerManagerV1, VirtualPointerManagerGlobalData>
+            + Dispatch<ZwlrVirtualPointerManagerV1, ()>
+            + Dispatch<ZwlrVirtualPointerV1, VirtualPointerData>
+            + VirtualPointerHandler
+            + 'static,
+        F: Fn(&Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = VirtualPointerManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, ZwlrVirtualPointerManagerV1, _>(VERSION, global_data);
+        Self
+    }
+}
+
+/// A frame of axis events from a virtual pointer.
+///
+/// Axis events are accumulated until the client sends a frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct VirtualPointerAxisFrame {
+    pub time: u32,
+    pub source: Option<AxisSource>,
+    /// The horizontal scroll amount, if any.
+    pub horizontal: Option<f64>,
+    /// The vertical scroll amount, if any.
+    pub vertical: Option<f64>,
+    /// The horizontal scroll amount in 120ths of a wheel click, if any.
+    pub horizontal_v120: Option<f64>,
+    /// The vertical scroll amount in 120ths of a wheel click, if any.
+    pub vertical_v120: Option<f64>,
+}
+
+impl VirtualPointerAxisFrame {
+    fn is_empty(&self) -> bool {
+        self.horizontal.is_none()
+            && self.vertical.is_none()
+            && self.horizontal_v120.is_none()
+            && self.vertical_v120.is_none()
+    }
+
+    fn set_amount(&mut self, axis: Axis, amount: f64) {
+        match axis {
+            Axis::Horizontal => *self.horizontal.get_or_insert(0.0) += amount,
+            Axis::Vertical => *self.vertical.get_or_insert(0.0) += amount,
+        }
+    }
+
+    fn set_v120(&mut self, axis: Axis, v120: f64) {
+        match axis {
+            Axis::Horizontal => *self.horizontal_v120.get_or_insert(0.0) += v120,
+            Axis::Vertical => *self.vertical_v120.get_or_insert(0.0) += v120,
+        }
+    }
+}
+
+/// Data attached to a virtual pointer.
+#[derive(Debug)]
+pub struct VirtualPointerData {
+    /// The output absolute motion is mapped to.
+    ///
+    /// If `None`, absolute motion is mapped to the whole output layout.
+    output: Option<Output>,
+    pending_axis: Mutex<VirtualPointerAxisFrame>,
+}
+
+impl VirtualPointerData {
+    fn new(output: Option<Output>) -> Self {
+        Self {
+            output,
+            pending_axis: Mutex::new(VirtualPointerAxisFrame::default()),
+        }
+    }
+}
+
+impl<D> GlobalDispatch<ZwlrVirtualPointerManagerV1, VirtualPointerManagerGlobalData, D>
+    for VirtualPointerManagerState
+where
+    D: GlobalDispatch<ZwlrVirtualPointerManagerV1, VirtualPointerManagerGlobalData>
+        + Dispatch<ZwlrVirtualPointerManagerV1, ()>
+        + Dispatch<ZwlrVirtualPointerV1, VirtualPointerData>
+        + VirtualPointerHandler
+        + 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: wayland_server::New<ZwlrVirtualPointerManagerV1>,
+        _global_data: &VirtualPointerManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &VirtualPointerManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<ZwlrVirtualPointerManagerV1, (), D> for VirtualPointerManagerState
+where
+    D: Dispatch<ZwlrVirtualPointerManagerV1, ()>
+        + Dispatch<ZwlrVirtualPointerV1, VirtualPointerData>
+        + VirtualPointerHandler
+        + 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _manager: &ZwlrVirtualPointerManagerV1,
+        request: <ZwlrVirtualPointerManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        // There is only one seat, so the requested one is ignored
+        match request {
+            zwlr_virtual_pointer_manager_v1::Request::CreateVirtualPointer { seat: _, id } => {
+                data_init.init(id, VirtualPointerData::new(None));
+            }
+            zwlr_virtual_pointer_manager_v1::Request::CreateVirtualPointerWithOutput {
+                seat: _,
+                output,
+                id,
+            } => {
+                let output = output.as_ref().and_then(Output::from_resource);
+                data_init.init(id, VirtualPointerData::new(output));
+            }
+            zwlr_virtual_pointer_manager_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<ZwlrVirtualPointerV1, VirtualPointerData, D> for VirtualPointerManagerState
+where
+    D: Dispatch<ZwlrVirtualPointerV1, VirtualPointerData> + VirtualPointerHandler + 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        pointer: &ZwlrVirtualPointerV1,
+        request: <ZwlrVirtualPointerV1 as Resource>::Request,
+        data: &VirtualPointerData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            zwlr_virtual_pointer_v1::Request::Motion { time, dx, dy } => {
+                state.virtual_pointer_motion(time, (dx, dy).into());
+            }
+            zwlr_virtual_pointer_v1::Request::MotionAbsolute {
+                time,
+                x,
+                y,
+                x_extent,
+                y_extent,
+            } => {
+                if x_extent == 0 || y_extent == 0 {
+                    pointer.post_error(
+                        zwlr_virtual_pointer_v1::Error::InvalidAxis,
+                        "absolute motion extent must be nonzero",
+                    );
+                    return;
+                }
+
+                let position =
+                    Point::from((x as f64 / x_extent as f64, y as f64 / y_extent as f64));
+
+                state.virtual_pointer_motion_absolute(time, position, data.output.as_ref());
+            }
+            zwlr_virtual_pointer_v1::Request::Button {
+                time,
+                button,
+                state: button_state,
+            } => {
+                let button_state = match button_state {
+                    WEnum::Value(wl_pointer::ButtonState::Pressed) => ButtonState::Pressed,
+                    _ => ButtonState::Released,
+                };
+                state.virtual_pointer_button(time, button, button_state);
+            }
+            zwlr_virtual_pointer_v1::Request::Axis { time, axis, value } => {
+                let Some(axis) = convert_axis(pointer, axis) else {
+                    return;
+                };
+                let mut pending = data.pending_axis.lock().expect("mutex poisoned");
+                pending.time = time;
+                pending.set_amount(axis, value);
+            }
+            zwlr_virtual_pointer_v1::Request::AxisSource { axis_source } => {
+                let source = match axis_source {
+                    WEnum::Value(wl_pointer::AxisSource::Wheel) => AxisSource::Wheel,
+                    WEnum::Value(wl_pointer::AxisSource::Finger) => AxisSource::Finger,
+                    WEnum::Value(wl_pointer::AxisSource::Continuous) => AxisSource::Continuous,
+                    WEnum::Value(wl_pointer::AxisSource::WheelTilt) => AxisSource::WheelTilt,
+                    _ => {
+                        pointer.post_error(
+                            zwlr_virtual_pointer_v1::Error::InvalidAxisSource,
+                            "invalid axis source",
+                        );
+                        return;
+                    }
+                };
+                data.pending_axis.lock().expect("mutex poisoned").source = Some(source);
+            }
+            zwlr_virtual_pointer_v1::Request::AxisStop { time, axis } => {
+                let Some(axis) = convert_axis(pointer, axis) else {
+                    return;
+                };
+                let mut pending = data.pending_axis.lock().expect("mutex poisoned");
+                pending.time = time;
+                pending.set_amount(axis, 0.0);
+            }
+            zwlr_virtual_pointer_v1::Request::AxisDiscrete {
+                time,
+                axis,
+                value,
+                discrete,
+            } => {
+                let Some(axis) = convert_axis(pointer, axis) else {
+                    return;
+                };
+                let mut pending = data.pending_axis.lock().expect("mutex poisoned");
+                pending.time = time;
+                pending.set_amount(axis, value);
+                pending.set_v120(axis, discrete as f64 * 120.0);
+            }
+            zwlr_virtual_pointer_v1::Request::Frame => {
+                let frame = std::mem::take(&mut *data.pending_axis.lock().expect("mutex poisoned"));
+                if !frame.is_empty() {
+                    state.virtual_pointer_axis(frame);
+                }
+            }
+            zwlr_virtual_pointer_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn convert_axis(pointer: &ZwlrVirtualPointerV1, axis: WEnum<wl_pointer::Axis>) -> Option<Axis> {
+    match axis {
+        WEnum::Value(wl_pointer::Axis::HorizontalScroll) => Some(Axis::Horizontal),
+        WEnum::Value(wl_pointer::Axis::VerticalScroll) => Some(Axis::Vertical),
+        _ => {
+            pointer.post_error(zwlr_virtual_pointer_v1::Error::InvalidAxis, "invalid axis");
+            None
+        }
+    }
+}
+
+pub trait VirtualPointerHandler {
+    /// A virtual pointer moved by `delta`.
+    ///
+    /// `time` is in milliseconds.
+    fn virtual_pointer_motion(&mut self, time: u32, delta: Point<f64, Logical>);
+    /// A virtual pointer moved to an absolute position.
+    ///
+    /// `position` is normalized to the range 0.0..=1.0 on each axis and is relative to
+    /// `output` if the pointer was created with one, or the whole output layout otherwise.
+    fn virtual_pointer_motion_absolute(
+        &mut self,
+        time: u32,
+        position: Point<f64, Logical>,
+        output: Option<&Output>,
+    );
+    /// A virtual pointer pressed or released a button.
+    fn virtual_pointer_button(&mut self, time: u32, button: u32, state: ButtonState);
+    /// A virtual pointer finished a frame of axis events.
+    fn virtual_pointer_axis(&mut self, frame: VirtualPointerAxisFrame);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! delegate_virtual_pointer {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::virtual_pointer::v1::server::zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1: $crate::protocol::virtual_pointer::VirtualPointerManagerGlobalData
+        ] => $crate::protocol::virtual_pointer::VirtualPointerManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::virtual_pointer::v1::server::zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1: ()
+        ] => $crate::protocol::virtual_pointer::VirtualPointerManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols_wlr::virtual_pointer::v1::server::zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1: $crate::protocol::virtual_pointer::VirtualPointerData
+        ] => $crate::protocol::virtual_pointer::VirtualPointerManagerState);
+    };
+}
@@ -5,15 +5,17 @@ use std::{ops::Deref, sync::Mutex};
 use smithay::{
     backend::renderer::{
         element::{
+            solid::{SolidColorBuffer, SolidColorRenderElement},
             surface::WaylandSurfaceRenderElement,
             utils::{CropRenderElement, RelocateRenderElement, RescaleRenderElement},
-            AsRenderElements, RenderElementStates, Wrap,
+            AsRenderElements, Kind, RenderElementStates, Wrap,
         },
         ImportAll, ImportMem, Renderer, Texture,
     },
     desktop::{
         layer_map_for_output,
         space::SpaceElement,
+        space::SurfaceTree,
         utils::{
             surface_presentation_feedback_flags_from_states, surface_primary_scanout_output,
             OutputPresentationFeedback,
@@ -24,18 +26,19 @@ use smithay::{
     output::Output,
     reexports::wayland_server::protocol::wl_surface::WlSurface,
     render_elements,
-    utils::{Logical, Physical, Point, Scale},
+    utils::{Logical, Physical, Point, Rectangle, Scale},
     wayland::{compositor, shell::wlr_layer},
 };
 
 use crate::{
     backend::Backend,
     state::{State, WithState},
-    window::WindowElement,
+    window::{group::WindowGroup, WindowElement},
 };
 
 use self::pointer::{PointerElement, PointerRenderElement};
 
+pub mod capture;
 pub mod pointer;
 
 render_elements! {
@@ -51,6 +54,7 @@ render_elements! {
     Surface = WaylandSurfaceRenderElement<R>,
     Pointer = PointerRenderElement<R>,
     Transform = TransformRenderElement<R, E>,
+    Solid = SolidColorRenderElement,
 }
 
 impl<R> AsRenderElements<R> for WindowElement
@@ -125,8 +129,294 @@ where
     }
 }
 
+/// The minimum width of the border drawn around server-side decorated windows,
+/// in logical pixels.
+pub const SSD_BORDER_WIDTH: i32 = 2;
+
+/// The widest border that can be set, in logical pixels.
+pub const MAX_BORDER_WIDTH: u32 = 100;
+
+/// The borders drawn around windows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Borders {
+    /// The width of borders in logical pixels.
+    ///
+    /// Server-side decorated windows always have a border at least [`SSD_BORDER_WIDTH`] wide.
+    /// This is at most [`MAX_BORDER_WIDTH`].
+    pub width: i32,
+    /// The border color of the focused window.
+    pub focused: [f32; 4],
+    /// The border color of unfocused windows.
+    pub unfocused: [f32; 4],
+    /// The border color of unfocused windows that need attention.
+    pub urgent: [f32; 4],
+}
+
+impl Default for Borders {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            focused: [0.4, 0.6, 0.9, 1.0],
+            unfocused: [0.3, 0.3, 0.3, 1.0],
+            urgent: [0.9, 0.4, 0.3, 1.0],
+        }
+    }
+}
+
+impl Borders {
+    /// Get the width of the border drawn around `window`.
+    ///
+    /// Fullscreen and override-redirect windows have no border.
+    pub fn width_for(&self, window: &WindowElement) -> i32 {
+        if window.is_x11_override_redirect()
+            || window.with_state(|state| state.fullscreen_or_maximized.is_fullscreen())
+        {
+            return 0;
+        }
+
+        if window.is_server_side_decorated() {
+            self.width.max(SSD_BORDER_WIDTH)
+        } else {
+            self.width
+        }
+    }
+}
+
+/// The tallest tab strip that can be set, in logical pixels.
+pub const MAX_TAB_BAR_HEIGHT: u32 = 100;
+
+/// The tab strip drawn above window groups.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TabBar {
+    /// The height of the tab strip in logical pixels.
+    ///
+    /// This is at most [`MAX_TAB_BAR_HEIGHT`].
+    pub height: i32,
+    /// The tab color of a group's shown window when it's focused.
+    pub focused: [f32; 4],
+    /// The tab color of a group's shown window when it isn't focused.
+    pub unfocused: [f32; 4],
+    /// The tab color of a group's hidden windows.
+    pub inactive: [f32; 4],
+    /// The tab color of a group's hidden windows that need attention.
+    pub urgent: [f32; 4],
+}
+
+impl Default for TabBar {
+    fn default() -> Self {
+        Self {
+            height: 8,
+            focused: [0.4, 0.6, 0.9, 1.0],
+            unfocused: [0.3, 0.3, 0.3, 1.0],
+            inactive: [0.18, 0.18, 0.18, 1.0],
+            urgent: [0.9, 0.4, 0.3, 1.0],
+        }
+    }
+}
+
+/// The opacity windows are drawn with when they don't have their own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowOpacity {
+    /// The opacity of the focused window.
+    pub focused: f32,
+    /// The opacity of unfocused windows.
+    pub unfocused: f32,
+}
+
+impl Default for WindowOpacity {
+    fn default() -> Self {
+        Self {
+            focused: 1.0,
+            unfocused: 1.0,
+        }
+    }
+}
+
+/// Options for how [`output_render_elements`] draws an output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Whether the session is locked, in which case only lock surfaces are drawn.
+    pub locked: bool,
+    /// The borders drawn around windows.
+    pub borders: Borders,
+    /// The opacity of windows without their own.
+    pub window_opacity: WindowOpacity,
+    /// The tab strip drawn above window groups.
+    pub tab_bar: TabBar,
+    /// Whether the elements are for a screen capture.
+    ///
+    /// Capture-hidden windows are replaced with a placeholder when this is true.
+    pub for_capture: bool,
+}
+
+/// Get render elements for the border around a window.
+///
+/// `geo` is the window's geometry relative to the output. The border is drawn outside of it.
+fn border_render_elements(
+    window: &WindowElement,
+    geo: Rectangle<i32, Logical>,
+    width: i32,
+    color: [f32; 4],
+    scale: Scale<f64>,
+    alpha: f32,
+) -> Vec<SolidColorRenderElement> {
+    let sides = [
+        // top
+        Rectangle::from_loc_and_size(
+            (geo.loc.x - width, geo.loc.y - width),
+            (geo.size.w + width * 2, width),
+        ),
+        // bottom
+        Rectangle::from_loc_and_size(
+            (geo.loc.x - width, geo.loc.y + geo.size.h),
+            (geo.size.w + width * 2, width),
+        ),
+        // left
+        Rectangle::from_loc_and_size((geo.loc.x - width, geo.loc.y), (width, geo.size.h)),
+        // right
+        Rectangle::from_loc_and_size((geo.loc.x + geo.size.w, geo.loc.y), (width, geo.size.h)),
+    ];
+
+    // Reuse the same buffers across frames so damage tracking only picks up actual changes
+    let buffers = window.with_state_mut(|state| {
+        let buffers = state.border_buffers.get_or_insert_with(|| {
+            sides.map(|side: Rectangle<i32, Logical>| SolidColorBuffer::new(side.size, color))
+        });
+        for (buffer, side) in buffers.iter_mut().zip(sides) {
+            buffer.update(side.size, color);
+        }
+        buffers.clone()
+    });
+
+    buffers
+        .iter()
+        .zip(sides)
+        .map(|(buffer, side)| {
+            SolidColorRenderElement::from_buffer(
+                buffer,
+                side.loc.to_physical_precise_round(scale),
+                scale,
+                alpha,
+                Kind::Unspecified,
+            )
+        })
+        .collect()
+}
+
+/// Get render elements for the tab strip above a window group's shown window.
+///
+/// `geo` is the shown window's geometry relative to the output, and `border_width` is the
+/// width of its border. The strip is drawn above the top border.
+fn tab_bar_render_elements(
+    group: &WindowGroup,
+    geo: Rectangle<i32, Logical>,
+    border_width: i32,
+    tab_bar: &TabBar,
+    is_focused: bool,
+    scale: Scale<f64>,
+    alpha: f32,
+) -> Vec<SolidColorRenderElement> {
+    if tab_bar.height == 0 {
+        return Vec::new();
+    }
+
+    let members = group.members();
+    let active = group.active_index();
+
+    let strip_x = geo.loc.x - border_width;
+    let strip_y = geo.loc.y - border_width - tab_bar.height;
+    let strip_width = geo.size.w + border_width * 2;
+
+    let count = members.len() as i32;
+
+    let tabs = members
+        .iter()
+        .enumerate()
+        .map(|(i, win)| {
+            let i = i as i32;
+            let x = strip_width * i / count;
+            let width = strip_width * (i + 1) / count - x;
+
+            let color = if i as usize == active {
+                if is_focused {
+                    tab_bar.focused
+                } else {
+                    tab_bar.unfocused
+                }
+            } else if win.with_state(|state| state.urgent) {
+                tab_bar.urgent
+            } else {
+                tab_bar.inactive
+            };
+
+            let rect =
+                Rectangle::from_loc_and_size((strip_x + x, strip_y), (width, tab_bar.height));
+
+            (rect, color)
+        })
+        .collect::<Vec<_>>();
+
+    let buffers = group.tab_buffers(
+        &tabs
+            .iter()
+            .map(|(rect, color)| (rect.size, *color))
+            .collect::<Vec<_>>(),
+    );
+
+    buffers
+        .iter()
+        .zip(tabs)
+        .map(|(buffer, (rect, _))| {
+            SolidColorRenderElement::from_buffer(
+                buffer,
+                rect.loc.to_physical_precise_round(scale),
+                scale,
+                alpha,
+                Kind::Unspecified,
+            )
+        })
+        .collect()
+}
+
+/// The color of the placeholder drawn over capture-hidden windows in screen captures.
+const CAPTURE_PLACEHOLDER_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// Get the render element for the placeholder drawn over a capture-hidden window.
+///
+/// `geo` is the window's geometry relative to the output.
+fn capture_placeholder_render_element(
+    window: &WindowElement,
+    geo: Rectangle<i32, Logical>,
+    scale: Scale<f64>,
+) -> SolidColorRenderElement {
+    let buffer = window.with_state_mut(|state| {
+        let buffer = state
+            .capture_placeholder
+            .get_or_insert_with(|| SolidColorBuffer::new(geo.size, CAPTURE_PLACEHOLDER_COLOR));
+        buffer.update(geo.size, CAPTURE_PLACEHOLDER_COLOR);
+        buffer.clone()
+    });
+
+    SolidColorRenderElement::from_buffer(
+        &buffer,
+        geo.loc.to_physical_precise_round(scale),
+        scale,
+        1.0,
+        Kind::Unspecified,
+    )
+}
+
 /// Get render elements for windows on active tags.
 ///
+/// Windows are drawn with their own opacity, or the focused or unfocused one
+/// from `window_opacity` if they don't have one.
+///
+/// Windows will additionally have a border drawn around them if the borders have a width
+/// or they use server-side decorations.
+///
+/// If rendering for a capture, capture-hidden windows and their popups are replaced
+/// with a placeholder.
+///
 /// ret.1 contains render elements for the windows at and above the first fullscreen window.
 /// ret.2 contains the rest.
 #[allow(clippy::type_complexity)]
@@ -136,6 +426,7 @@ fn window_render_elements<R>(
     space: &Space<WindowElement>,
     renderer: &mut R,
     scale: Scale<f64>,
+    options: &RenderOptions,
 ) -> (
     Vec<OutputRenderElement<R, WaylandSurfaceRenderElement<R>>>,
     Vec<OutputRenderElement<R, WaylandSurfaceRenderElement<R>>>,
@@ -146,27 +437,90 @@ where
 {
     let mut last_fullscreen_split_at = 0;
 
+    let focused_window = output.with_state(|state| state.focus_stack.current_focus());
+
     let mut fullscreen_and_up = windows
         .iter()
         .rev() // rev because I treat the focus stack backwards vs how the renderer orders it
         .filter(|win| win.is_on_active_tag())
         .enumerate()
         .flat_map(|(i, win)| {
-            if win.with_state(|state| state.fullscreen_or_maximized.is_fullscreen()) {
+            let is_fullscreen =
+                win.with_state(|state| state.fullscreen_or_maximized.is_fullscreen());
+
+            if is_fullscreen {
                 last_fullscreen_split_at = i + 1;
             }
 
+            let win_loc = space.element_location(win).unwrap_or((0, 0).into())
+                - output.current_location();
+
             // subtract win.geometry().loc to align decorations correctly
-            let loc = (
-                space.element_location(win) .unwrap_or((0, 0).into())
-                    - win.geometry().loc
-                    - output.current_location()
-                )
-                .to_physical_precise_round(scale);
-
-            win.render_elements::<WaylandSurfaceRenderElement<R>>(renderer, loc, scale, 1.0)
-                .into_iter()
-                .map(OutputRenderElement::from)
+            let loc = (win_loc - win.geometry().loc).to_physical_precise_round(scale);
+
+            let is_focused = focused_window.as_ref() == Some(win);
+
+            let alpha = win.with_state(|state| state.opacity).unwrap_or(if is_focused {
+                options.window_opacity.focused
+            } else {
+                options.window_opacity.unfocused
+            });
+
+            let mut elements = if options.for_capture && win.with_state(|state| state.capture_hidden) {
+                let geo = Rectangle::from_loc_and_size(win_loc, win.geometry().size);
+                vec![OutputRenderElement::from(
+                    capture_placeholder_render_element(win, geo, scale),
+                )]
+            } else {
+                win.render_elements::<WaylandSurfaceRenderElement<R>>(renderer, loc, scale, alpha)
+                    .into_iter()
+                    .map(OutputRenderElement::from)
+                    .collect::<Vec<_>>()
+            };
+
+            let border_width = options.borders.width_for(win);
+
+            if border_width > 0 {
+                let color = if is_focused {
+                    options.borders.focused
+                } else if win.with_state(|state| state.urgent) {
+                    options.borders.urgent
+                } else {
+                    options.borders.unfocused
+                };
+
+                let geo = Rectangle::from_loc_and_size(win_loc, win.geometry().size);
+
+                elements.extend(
+                    border_render_elements(win, geo, border_width, color, scale, alpha)
+                        .into_iter()
+                        .map(OutputRenderElement::from),
+                );
+            }
+
+            if let Some(group) = win.group().filter(|_| {
+                win.with_state(|state| {
+                    state.fullscreen_or_maximized.is_neither() && state.floating_or_tiled.is_tiled()
+                })
+            }) {
+                let geo = Rectangle::from_loc_and_size(win_loc, win.geometry().size);
+
+                elements.extend(
+                    tab_bar_render_elements(
+                        &group,
+                        geo,
+                        border_width,
+                        &options.tab_bar,
+                        is_focused,
+                        scale,
+                        alpha,
+                    )
+                    .into_iter()
+                    .map(OutputRenderElement::from),
+                );
+            }
+
+            elements
         }).collect::<Vec<_>>();
 
     let rest = fullscreen_and_up.split_off(last_fullscreen_split_at);
@@ -233,15 +587,66 @@ where
     output_render_elements
 }
 
+/// Get render elements for a locked output.
+///
+/// This is the output's lock surface, if any, over an opaque backdrop so that
+/// nothing underneath is visible even if the locker has died.
+fn lock_render_elements<R>(
+    output: &Output,
+    renderer: &mut R,
+    space: &Space<WindowElement>,
+    scale: Scale<f64>,
+) -> Vec<OutputRenderElement<R, WaylandSurfaceRenderElement<R>>>
+where
+    R: Renderer + ImportAll + ImportMem,
+    <R as Renderer>::TextureId: Clone + 'static,
+{
+    let mut output_render_elements = Vec::new();
+
+    let Some(output_geo) = space.output_geometry(output) else {
+        return output_render_elements;
+    };
+
+    let (lock_surface, backdrop) = output.with_state_mut(|state| {
+        let backdrop = state
+            .lock_backdrop
+            .get_or_insert_with(|| SolidColorBuffer::new(output_geo.size, LOCK_BACKDROP_COLOR));
+        backdrop.update(output_geo.size, LOCK_BACKDROP_COLOR);
+        (state.lock_surface.clone(), backdrop.clone())
+    });
+
+    if let Some(lock_surface) = lock_surface {
+        output_render_elements.extend(AsRenderElements::render_elements(
+            &SurfaceTree::from_surface(lock_surface.wl_surface()),
+            renderer,
+            (0, 0).into(),
+            scale,
+            1.0,
+        ));
+    }
+
+    output_render_elements.push(OutputRenderElement::from(
+        SolidColorRenderElement::from_buffer(&backdrop, (0, 0), scale, 1.0, Kind::Unspecified),
+    ));
+
+    output_render_elements
+}
+
+/// The color outputs are blanked to while the session is locked.
+const LOCK_BACKDROP_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
 /// Generate render elements for the given output.
 ///
 /// Render elements will be pulled from the provided windows,
 /// with the first window being at the top and subsequent ones beneath.
+///
+/// If the session is locked, only the output's lock surface will be rendered.
 pub fn output_render_elements<R, T>(
     output: &Output,
     renderer: &mut R,
     space: &Space<WindowElement>,
     windows: &[WindowElement],
+    options: &RenderOptions,
 ) -> Vec<OutputRenderElement<R, WaylandSurfaceRenderElement<R>>>
 where
     R: Renderer<TextureId = T> + ImportAll + ImportMem,
@@ -250,6 +655,10 @@ where
 {
     let scale = Scale::from(output.current_scale().fractional_scale());
 
+    if options.locked {
+        return lock_render_elements(output, renderer, space, scale);
+    }
+
     let mut output_render_elements: Vec<OutputRenderElement<_, _>> = Vec::new();
 
     let (windows, override_redirect_windows) = windows
@@ -257,22 +666,6 @@ where
         .cloned()
         .partition::<Vec<_>, _>(|win| !win.is_x11_override_redirect());
 
-    // // draw input method surface if any
-    // let rectangle = input_method.coordinates();
-    // let position = Point::from((
-    //     rectangle.loc.x + rectangle.size.w,
-    //     rectangle.loc.y + rectangle.size.h,
-    // ));
-    // input_method.with_surface(|surface| {
-    //     custom_render_elements.extend(AsRenderElements::<R>::render_elements(
-    //         &SurfaceTree::from_surface(surface),
-    //         renderer,
-    //         position.to_physical_precise_round(scale),
-    //         scale,
-    //         1.0,
-    //     ));
-    // });
-
     let o_r_elements = override_redirect_windows.iter().flat_map(|surf| {
         surf.render_elements::<WaylandSurfaceRenderElement<R>>(
             renderer,
@@ -297,7 +690,7 @@ where
     } = layer_render_elements(output, renderer, scale);
 
     let (fullscreen_and_up_elements, rest_of_window_elements) =
-        window_render_elements::<R>(output, &windows, space, renderer, scale);
+        window_render_elements::<R>(output, &windows, space, renderer, scale, options);
 
     // Elements render from top to bottom
 
@@ -346,8 +739,32 @@ pub fn take_presentation_feedback(
 }
 
 impl State {
+    /// Get the options outputs are currently rendered with.
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            locked: self.lock_state.is_locked(),
+            borders: self.config.borders,
+            window_opacity: self.config.window_opacity,
+            tab_bar: self.config.tab_bar,
+            for_capture: false,
+        }
+    }
+
+    /// Returns whether any capture-hidden windows are visible on the given output.
+    pub fn has_capture_hidden_windows(&self, output: &Output) -> bool {
+        self.space.elements().any(|win| {
+            win.with_state(|state| state.capture_hidden)
+                && win.is_on_active_tag()
+                && self.space.outputs_for_element(win).contains(output)
+        })
+    }
+
     /// Schedule a new render. This does nothing on the winit backend.
     pub fn schedule_render(&mut self, output: &Output) {
+        if self.disabled_outputs.contains(output) {
+            return;
+        }
+
         if let Backend::Udev(udev) = &mut self.backend {
             udev.schedule_render(&self.loop_handle, output);
         }
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Offscreen capture of single windows.
+//!
+//! Windows are rendered on their own instead of being copied out of an output's
+//! framebuffer, so windows that aren't currently visible can still be captured.
+
+use anyhow::ensure;
+use smithay::{
+    backend::{
+        allocator::Fourcc,
+        renderer::{
+            damage::OutputDamageTracker,
+            element::{surface::WaylandSurfaceRenderElement, AsRenderElements},
+            gles::{GlesRenderbuffer, GlesRenderer},
+            sync::SyncPoint,
+            Bind, ExportMem, Offscreen,
+        },
+    },
+    reexports::{
+        calloop::{self, generic::Generic, Interest, PostAction},
+        wayland_server::protocol::{wl_buffer::WlBuffer, wl_shm},
+    },
+    utils::{Physical, Rectangle, Scale, Size, Transform},
+    wayland::{dmabuf::get_dmabuf, shm},
+};
+use tracing::{error, trace, warn};
+
+use crate::{
+    protocol::toplevel_export::ToplevelExport,
+    state::{State, WithState},
+    window::WindowElement,
+};
+
+/// Get the physical size of a capture of `window` at the given scale.
+///
+/// Captures contain the window along with its popups and anything else it draws outside
+/// of its geometry, like client-side shadows.
+pub fn window_capture_size(window: &WindowElement, scale: Scale<f64>) -> Size<i32, Physical> {
+    window
+        .bbox_with_popups()
+        .size
+        .to_physical_precise_round(scale)
+}
+
+/// Render `window` and its popups into the currently bound framebuffer.
+fn render_window(
+    renderer: &mut GlesRenderer,
+    window: &WindowElement,
+    scale: Scale<f64>,
+) -> anyhow::Result<SyncPoint> {
+    let size = window_capture_size(window, scale);
+    let location = (-window.bbox_with_popups().loc).to_physical_precise_round(scale);
+
+    let elements: Vec<WaylandSurfaceRenderElement<GlesRenderer>> =
+        AsRenderElements::render_elements(window, renderer, location, scale, 1.0);
+
+    let mut damage_tracker = OutputDamageTracker::new(size, scale, Transform::Normal);
+    let result = damage_tracker.render_output(renderer, 0, &elements, [0.0, 0.0, 0.0, 0.0])?;
+
+    Ok(result.sync)
+}
+
+/// Render `window` offscreen and read the pixels back in the given format.
+fn render_window_to_memory(
+    renderer: &mut GlesRenderer,
+    window: &WindowElement,
+    scale: Scale<f64>,
+    format: Fourcc,
+) -> anyhow::Result<Vec<u8>> {
+    let size = window_capture_size(window, scale);
+    let buffer_size = size.to_logical(1).to_buffer(1, Transform::Normal);
+
+    let offscreen: GlesRenderbuffer = renderer.create_buffer(Fourcc::Abgr8888, buffer_size)?;
+    renderer.bind(offscreen)?;
+
+    render_window(renderer, window, scale)?;
+
+    let mapping =
+        renderer.copy_framebuffer(Rectangle::from_loc_and_size((0, 0), buffer_size), format)?;
+    let bytes = renderer.map_texture(&mapping)?;
+
+    Ok(bytes.to_vec())
+}
+
+/// Capture `window` into a client's shm or dmabuf buffer.
+///
+/// The buffer must be `Argb8888` and [`window_capture_size`] large.
+pub fn capture_window_into_buffer(
+    renderer: &mut GlesRenderer,
+    window: &WindowElement,
+    scale: Scale<f64>,
+    buffer: &WlBuffer,
+) -> anyhow::Result<Option<SyncPoint>> {
+    let size = window_capture_size(window, scale);
+
+    if let Ok(dmabuf) = get_dmabuf(buffer) {
+        ensure!(
+            dmabuf.format().code == Fourcc::Argb8888
+                && dmabuf.width() == size.w as u32
+                && dmabuf.height() == size.h as u32,
+            "invalid buffer format or size"
+        );
+
+        renderer.bind(dmabuf.clone())?;
+        return Ok(Some(render_window(renderer, window, scale)?));
+    }
+
+    shm::with_buffer_contents_mut(buffer, |shm_ptr, shm_len, buffer_data| {
+        ensure!(
+            buffer_data.format == wl_shm::Format::Argb8888
+                && buffer_data.stride == size.w * 4
+                && buffer_data.height == size.h
+                && shm_len as i32 == buffer_data.stride * buffer_data.height,
+            "invalid buffer format or size"
+        );
+
+        let bytes = render_window_to_memory(renderer, window, scale, Fourcc::Argb8888)?;
+
+        ensure!(bytes.len() == shm_len, "mapped buffer has wrong length");
+
+        // SAFETY: `shm_ptr` points to `shm_len` bytes, and `bytes` has the same length
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), shm_ptr, shm_len);
+        }
+
+        Ok(None)
+    })?
+}
+
+/// Capture `window` as RGBA8 pixels, returning the size of the image with them.
+pub fn capture_window(
+    renderer: &mut GlesRenderer,
+    window: &WindowElement,
+    scale: Scale<f64>,
+) -> anyhow::Result<(Size<i32, Physical>, Vec<u8>)> {
+    let size = window_capture_size(window, scale);
+    ensure!(size.w > 0 && size.h > 0, "window has no size");
+
+    // `Abgr8888` is little-endian, so the bytes are in RGBA order
+    let bytes = render_window_to_memory(renderer, window, scale, Fourcc::Abgr8888)?;
+
+    Ok((size, bytes))
+}
+
+impl State {
+    /// Get the scale `window` is captured at.
+    ///
+    /// This is the scale of the output the window is on so captures match what's on screen.
+    pub fn window_capture_scale(&self, window: &WindowElement) -> Scale<f64> {
+        let scale = window
+            .output(self)
+            .or_else(|| self.focused_output().cloned())
+            .map(|output| output.current_scale().fractional_scale())
+            .unwrap_or(1.0);
+
+        Scale::from(scale)
+    }
+
+    /// Capture `window` as RGBA8 pixels.
+    pub fn capture_window(
+        &mut self,
+        window: &WindowElement,
+    ) -> anyhow::Result<(Size<i32, Physical>, Vec<u8>)> {
+        let scale = self.window_capture_scale(window);
+        self.backend
+            .with_gles_renderer(|renderer| capture_window(renderer, window, scale))
+            .unwrap_or_else(|| Err(anyhow::anyhow!("no renderer available")))
+    }
+
+    /// Copy `window` into a toplevel export's buffer and submit it.
+    ///
+    /// If the window changed size since the export was requested
+    /// or has been hidden from capture, it fails.
+    pub fn submit_toplevel_export(&mut self, window: &WindowElement, mut export: ToplevelExport) {
+        if window.with_state(|state| state.capture_hidden) {
+            trace!("Window is capture-hidden, failing toplevel export");
+            return;
+        }
+
+        let scale = self.window_capture_scale(window);
+        let size = window_capture_size(window, scale);
+        if size != export.size() {
+            trace!("Window was resized, failing toplevel export");
+            return;
+        }
+
+        let sync_point = self.backend.with_gles_renderer(|renderer| {
+            capture_window_into_buffer(renderer, window, scale, export.buffer())
+        });
+
+        let sync_point = match sync_point {
+            Some(Ok(sync_point)) => sync_point,
+            Some(Err(err)) => {
+                warn!("Failed to capture window: {err}");
+                return;
+            }
+            None => return,
+        };
+
+        export.damage(&[Rectangle::from_loc_and_size((0, 0), size)]);
+
+        match sync_point {
+            Some(sync_point) if !sync_point.is_reached() => {
+                let Some(sync_fd) = sync_point.export() else {
+                    export.submit(false);
+                    return;
+                };
+                let mut export = Some(export);
+                let source = Generic::new(sync_fd, Interest::READ, calloop::Mode::OneShot);
+                let res = self.loop_handle.insert_source(source, move |_, _, _| {
+                    let Some(export) = export.take() else {
+                        unreachable!("This source is removed after one run");
+                    };
+                    export.submit(false);
+                    Ok(PostAction::Remove)
+                });
+                if res.is_err() {
+                    error!("Failed to schedule toplevel export submission");
+                }
+            }
+            _ => export.submit(false),
+        }
+    }
+}
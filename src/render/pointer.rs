@@ -17,6 +17,8 @@ use smithay::{
 
 pub struct PointerElement<T: Texture> {
     texture: Option<TextureBuffer<T>>,
+    /// The hotspot of the named cursor's texture
+    hotspot: Point<i32, Physical>,
     status: CursorImageStatus,
     kind: element::Kind,
 }
@@ -25,6 +27,7 @@ impl<T: Texture> Default for PointerElement<T> {
     fn default() -> Self {
         Self {
             texture: Default::default(),
+            hotspot: Default::default(),
             status: CursorImageStatus::default_named(),
             kind: element::Kind::Cursor,
         }
@@ -44,6 +47,10 @@ impl<T: Texture> PointerElement<T> {
         self.texture = Some(texture);
     }
 
+    pub fn set_hotspot(&mut self, hotspot: Point<i32, Physical>) {
+        self.hotspot = hotspot;
+    }
+
     pub fn set_element_kind(&mut self, kind: element::Kind) {
         self.kind = kind;
     }
@@ -75,7 +82,7 @@ where
                 if let Some(texture) = self.texture.as_ref() {
                     vec![PointerRenderElement::<R>::from(
                         TextureRenderElement::from_texture_buffer(
-                            location.to_f64(),
+                            (location - self.hotspot).to_f64(),
                             texture,
                             None,
                             None,
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Access control for privileged globals.
+//!
+//! Clients that connect through a security context from `wp-security-context-v1`, like
+//! Flatpak apps, are sandboxed. Sandboxed clients can't see any privileged global unless the
+//! config allows their sandbox engine or app id to.
+//!
+//! Clients that connect directly to the Wayland socket can see everything.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+use smithay::{reexports::wayland_server::Client, wayland::security_context::SecurityContext};
+
+use crate::state::ClientState;
+
+/// A global that sandboxed clients can't see unless allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrivilegedGlobal {
+    Screencopy,
+    ToplevelExport,
+    GammaControl,
+    DataControl,
+    LayerShell,
+    VirtualKeyboard,
+    VirtualPointer,
+    InputMethod,
+    SessionLock,
+    ForeignToplevel,
+    ExtWorkspace,
+    OutputManagement,
+}
+
+/// A rule that allows matching sandboxed clients to see some privileged globals.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxRule {
+    /// The sandbox engine to match, like `flatpak`, or `None` to match any engine
+    pub sandbox_engine: Option<String>,
+    /// The app id to match, or `None` to match any app
+    pub app_id: Option<String>,
+    /// The globals matching clients can see
+    pub globals: HashSet<PrivilegedGlobal>,
+}
+
+impl SandboxRule {
+    fn matches(&self, context: &SecurityContext) -> bool {
+        let engine_matches = self.sandbox_engine.as_ref().map_or(true, |engine| {
+            context.sandbox_engine.as_ref() == Some(engine)
+        });
+        let app_id_matches = self
+            .app_id
+            .as_ref()
+            .map_or(true, |app_id| context.app_id.as_ref() == Some(app_id));
+
+        engine_matches && app_id_matches
+    }
+}
+
+/// The rules deciding which privileged globals sandboxed clients can see.
+///
+/// This is shared with the filters of every privileged global, so changes apply to clients
+/// that connect afterwards. Clients that already bound a global keep it.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxAllowlist {
+    rules: Arc<RwLock<Vec<SandboxRule>>>,
+}
+
+impl SandboxAllowlist {
+    pub fn add_rule(&self, rule: SandboxRule) {
+        self.rules
+            .write()
+            .expect("sandbox allowlist lock was poisoned")
+            .push(rule);
+    }
+
+    pub fn clear(&self) {
+        self.rules
+            .write()
+            .expect("sandbox allowlist lock was poisoned")
+            .clear();
+    }
+
+    /// Returns whether a client in the given security context can see `global`.
+    pub fn allows(&self, context: &SecurityContext, global: PrivilegedGlobal) -> bool {
+        self.rules
+            .read()
+            .expect("sandbox allowlist lock was poisoned")
+            .iter()
+            .any(|rule| rule.matches(context) && rule.globals.contains(&global))
+    }
+
+    /// Create a global filter that only shows `global` to unsandboxed clients and
+    /// sandboxed clients that are allowed to see it.
+    pub fn filter(
+        &self,
+        global: PrivilegedGlobal,
+    ) -> impl Fn(&Client) -> bool + Send + Sync + 'static {
+        let allowlist = self.clone();
+        move |client| match security_context(client) {
+            Some(context) => allowlist.allows(context, global),
+            None => true,
+        }
+    }
+}
+
+/// Get the security context a client connected through, if any.
+pub fn security_context(client: &Client) -> Option<&SecurityContext> {
+    client
+        .get_data::<ClientState>()
+        .and_then(|client_state| client_state.security_context.as_ref())
+}
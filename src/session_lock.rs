@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use smithay::{
+    output::Output,
+    utils::{IsAlive, SERIAL_COUNTER},
+    wayland::session_lock::LockSurface,
+};
+
+use crate::{
+    focus::keyboard::KeyboardFocusTarget,
+    state::{State, WithState},
+};
+
+/// Whether or not the session is locked.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LockState {
+    #[default]
+    Unlocked,
+    /// The session is locked.
+    ///
+    /// This stays set until the locker explicitly unlocks, so if the locker crashes
+    /// outputs will remain blanked until a new locker takes over.
+    Locked,
+}
+
+impl LockState {
+    pub fn is_locked(&self) -> bool {
+        matches!(self, LockState::Locked)
+    }
+}
+
+impl State {
+    /// Get the live lock surface on `output`, if any.
+    pub fn lock_surface_for_output(&self, output: &Output) -> Option<LockSurface> {
+        output
+            .with_state(|state| state.lock_surface.clone())
+            .filter(|surface| surface.wl_surface().alive())
+    }
+
+    /// Returns whether or not a lock client currently has a live lock surface on any output.
+    pub fn has_live_locker(&self) -> bool {
+        self.space
+            .outputs()
+            .any(|op| self.lock_surface_for_output(op).is_some())
+    }
+
+    /// Give keyboard focus to the lock surface on the focused output,
+    /// or any other output if that one doesn't have one.
+    pub fn focus_lock_surface(&mut self) {
+        let lock_surface = self
+            .focused_output()
+            .and_then(|op| self.lock_surface_for_output(op))
+            .or_else(|| {
+                self.space
+                    .outputs()
+                    .find_map(|op| self.lock_surface_for_output(op))
+            });
+
+        let keyboard = self.seat.get_keyboard().expect("Seat has no keyboard");
+
+        let focus = lock_surface.map(KeyboardFocusTarget::LockSurface);
+
+        if keyboard.current_focus() != focus {
+            keyboard.set_focus(self, focus, SERIAL_COUNTER.next_serial());
+        }
+    }
+
+    /// Resize the lock surface on `output`, if any, to the output's new size.
+    pub fn reconfigure_lock_surface(&self, output: &Output) {
+        let Some(lock_surface) = self.lock_surface_for_output(output) else {
+            return;
+        };
+
+        let Some(geo) = self.space.output_geometry(output) else {
+            return;
+        };
+
+        lock_surface.with_pending_state(|state| {
+            state.size = Some((geo.size.w as u32, geo.size.h as u32).into());
+        });
+        lock_surface.send_configure();
+    }
+}
@@ -7,15 +7,24 @@ use crate::{
     cursor::Cursor,
     focus::OutputFocusStack,
     grab::resize_grab::ResizeSurfaceState,
+    idle::IdleState,
     layout::LayoutState,
-    protocol::{gamma_control::GammaControlManagerState, screencopy::ScreencopyManagerState},
-    window::WindowElement,
+    protocol::{
+        ext_workspace::ExtWorkspaceManagerState, foreign_toplevel::ForeignToplevelManagerState,
+        gamma_control::GammaControlManagerState, output_management::OutputManagementManagerState,
+        screencopy::ScreencopyManagerState, toplevel_export::ToplevelExportManagerState,
+        virtual_keyboard::VirtualKeyboardManagerState, virtual_pointer::VirtualPointerManagerState,
+    },
+    security::{self, PrivilegedGlobal},
+    session_lock::LockState,
+    window::{group::WindowGroup, WindowElement},
 };
 use anyhow::Context;
 use pinnacle_api_defs::pinnacle::v0alpha1::ShutdownWatchResponse;
 use smithay::{
     desktop::{PopupManager, Space},
     input::{keyboard::XkbConfig, pointer::CursorImageStatus, Seat, SeatState},
+    output::Output,
     reexports::{
         calloop::{generic::Generic, Interest, LoopHandle, LoopSignal, Mode, PostAction},
         wayland_server::{
@@ -24,25 +33,38 @@ use smithay::{
             Display, DisplayHandle,
         },
     },
-    utils::{Clock, Monotonic, Point, Size},
+    utils::{Clock, Monotonic},
     wayland::{
         compositor::{self, CompositorClientState, CompositorState},
+        cursor_shape::CursorShapeManagerState,
         dmabuf::DmabufFeedback,
         fractional_scale::FractionalScaleManagerState,
+        idle_inhibit::IdleInhibitManagerState,
+        idle_notify::IdleNotifierState,
+        input_method::InputMethodManagerState,
+        keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitState,
         output::OutputManagerState,
+        pointer_constraints::PointerConstraintsState,
         relative_pointer::RelativePointerManagerState,
+        security_context::{SecurityContext, SecurityContextState},
         selection::{
             data_device::DataDeviceState, primary_selection::PrimarySelectionState,
             wlr_data_control::DataControlState,
         },
-        shell::{wlr_layer::WlrLayerShellState, xdg::XdgShellState},
+        session_lock::SessionLockManagerState,
+        shell::{
+            wlr_layer::WlrLayerShellState,
+            xdg::{decoration::XdgDecorationState, XdgShellState},
+        },
         shm::ShmState,
         socket::ListeningSocketSource,
+        text_input::TextInputManagerState,
         viewporter::ViewporterState,
+        xdg_activation::{XdgActivationState, XdgActivationToken},
     },
     xwayland::{X11Wm, XWayland, XWaylandEvent},
 };
-use std::{cell::RefCell, path::PathBuf, sync::Arc, time::Duration};
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, sync::Arc};
 use sysinfo::{ProcessRefreshKind, RefreshKind};
 use tracing::{error, info, warn};
 use xdg::BaseDirectories;
@@ -77,11 +99,41 @@ pub struct State {
     pub layer_shell_state: WlrLayerShellState,
     pub data_control_state: DataControlState,
     pub screencopy_manager_state: ScreencopyManagerState,
+    pub toplevel_export_manager_state: ToplevelExportManagerState,
     pub gamma_control_manager_state: GammaControlManagerState,
     pub relative_pointer_manager_state: RelativePointerManagerState,
+    pub pointer_constraints_state: PointerConstraintsState,
+    pub keyboard_shortcuts_inhibit_state: KeyboardShortcutsInhibitState,
+    pub input_method_manager_state: InputMethodManagerState,
+    pub text_input_manager_state: TextInputManagerState,
+    pub virtual_keyboard_manager_state: VirtualKeyboardManagerState,
+    pub virtual_pointer_manager_state: VirtualPointerManagerState,
+    pub session_lock_manager_state: SessionLockManagerState,
+    pub foreign_toplevel_manager_state: ForeignToplevelManagerState,
+    pub ext_workspace_manager_state: ExtWorkspaceManagerState,
+    pub xdg_decoration_state: XdgDecorationState,
+    pub output_management_manager_state: OutputManagementManagerState,
+    pub security_context_state: SecurityContextState,
+    pub idle_notifier_state: IdleNotifierState<Self>,
+    pub idle_inhibit_manager_state: IdleInhibitManagerState,
+    pub xdg_activation_state: XdgActivationState,
+    /// The focused output when each activation token for a spawned process was created
+    pub activation_token_outputs: HashMap<XdgActivationToken, Output>,
+    /// Activation requests from windows that haven't mapped yet, with the output
+    /// they were launched from
+    pub pending_activations: HashMap<WlSurface, Option<Output>>,
+    /// Outputs disabled by an output management client.
+    ///
+    /// These are unmapped from the space but still advertised so they can be enabled again.
+    pub disabled_outputs: Vec<Output>,
+
+    /// Whether or not the session is locked
+    pub lock_state: LockState,
 
     /// The state of key and mousebinds along with libinput settings
     pub input_state: InputState,
+    /// The compositor's idle timeout and idle inhibitors
+    pub idle_state: IdleState,
 
     pub output_focus_stack: OutputFocusStack,
     pub z_index_stack: Vec<WindowElement>,
@@ -89,11 +141,18 @@ pub struct State {
     pub popup_manager: PopupManager,
 
     pub cursor_status: CursorImageStatus,
+    /// The xcursor theme used for named cursors
+    pub cursor: Cursor,
+    pub cursor_shape_manager_state: CursorShapeManagerState,
     pub dnd_icon: Option<WlSurface>,
 
     /// The main window vec
     pub windows: Vec<WindowElement>,
     pub new_windows: Vec<WindowElement>,
+    /// Tabbed window groups.
+    ///
+    /// Windows only hold weak references to their group, so this keeps groups alive.
+    pub window_groups: Vec<WindowGroup>,
 
     pub config: Config,
 
@@ -198,18 +257,10 @@ impl State {
                     )
                     .expect("failed to attach x11wm");
 
-                    let cursor = Cursor::load();
-                    let image = cursor.get_image(1, Duration::ZERO);
-                    wm.set_cursor(
-                        &image.pixels_rgba,
-                        Size::from((image.width as u16, image.height as u16)),
-                        Point::from((image.xhot as u16, image.yhot as u16)),
-                    )
-                    .expect("failed to set xwayland default cursor");
-
                     tracing::debug!("setting xwm and xdisplay");
 
                     state.xwm = Some(wm);
+                    state.update_xwayland_cursor();
                     state.xdisplay = Some(display);
 
                     std::env::set_var("DISPLAY", format!(":{display}"));
@@ -231,14 +282,20 @@ impl State {
         };
         tracing::debug!("xwayland set up");
 
+        let config = Config::new(no_config, config_dir);
+        let allowlist = config.sandbox_allowlist.clone();
+
         let primary_selection_state = PrimarySelectionState::new::<Self>(&display_handle);
 
         let data_control_state = DataControlState::new::<Self, _>(
             &display_handle,
             Some(&primary_selection_state),
-            |_| true,
+            allowlist.filter(PrivilegedGlobal::DataControl),
         );
 
+        let idle_notifier_state =
+            IdleNotifierState::<Self>::new(&display_handle, loop_handle.clone());
+
         let state = Self {
             backend,
             loop_signal,
@@ -251,6 +308,8 @@ impl State {
             shm_state: ShmState::new::<Self>(&display_handle, vec![]),
             space: Space::<WindowElement>::default(),
             cursor_status: CursorImageStatus::default_named(),
+            cursor: Cursor::load(),
+            cursor_shape_manager_state: CursorShapeManagerState::new::<Self>(&display_handle),
             output_manager_state: OutputManagerState::new_with_xdg_output::<Self>(&display_handle),
             xdg_shell_state: XdgShellState::new::<Self>(&display_handle),
             viewporter_state: ViewporterState::new::<Self>(&display_handle),
@@ -258,26 +317,81 @@ impl State {
                 &display_handle,
             ),
             primary_selection_state,
-            layer_shell_state: WlrLayerShellState::new::<Self>(&display_handle),
+            layer_shell_state: WlrLayerShellState::new_with_filter::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::LayerShell),
+            ),
             data_control_state,
             screencopy_manager_state: ScreencopyManagerState::new::<Self, _>(
                 &display_handle,
-                |_| true,
+                allowlist.filter(PrivilegedGlobal::Screencopy),
+            ),
+            toplevel_export_manager_state: ToplevelExportManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::ToplevelExport),
             ),
             gamma_control_manager_state: GammaControlManagerState::new::<Self, _>(
                 &display_handle,
-                |_| true,
+                allowlist.filter(PrivilegedGlobal::GammaControl),
             ),
             relative_pointer_manager_state: RelativePointerManagerState::new::<Self>(
                 &display_handle,
             ),
+            pointer_constraints_state: PointerConstraintsState::new::<Self>(&display_handle),
+            keyboard_shortcuts_inhibit_state: KeyboardShortcutsInhibitState::new::<Self>(
+                &display_handle,
+            ),
+            input_method_manager_state: InputMethodManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::InputMethod),
+            ),
+            text_input_manager_state: TextInputManagerState::new::<Self>(&display_handle),
+            virtual_keyboard_manager_state: VirtualKeyboardManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::VirtualKeyboard),
+            ),
+            virtual_pointer_manager_state: VirtualPointerManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::VirtualPointer),
+            ),
+            session_lock_manager_state: SessionLockManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::SessionLock),
+            ),
+            foreign_toplevel_manager_state: ForeignToplevelManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::ForeignToplevel),
+            ),
+            ext_workspace_manager_state: ExtWorkspaceManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::ExtWorkspace),
+            ),
+            xdg_decoration_state: XdgDecorationState::new::<Self>(&display_handle),
+            output_management_manager_state: OutputManagementManagerState::new::<Self, _>(
+                &display_handle,
+                allowlist.filter(PrivilegedGlobal::OutputManagement),
+            ),
+            // Sandboxed clients must not be able to create nested security contexts
+            security_context_state: SecurityContextState::new::<Self, _>(
+                &display_handle,
+                |client| security::security_context(client).is_none(),
+            ),
+            idle_notifier_state,
+            idle_inhibit_manager_state: IdleInhibitManagerState::new::<Self>(&display_handle),
+            xdg_activation_state: XdgActivationState::new::<Self>(&display_handle),
+            activation_token_outputs: HashMap::new(),
+            pending_activations: HashMap::new(),
+            disabled_outputs: Vec::new(),
+
+            lock_state: LockState::default(),
 
             input_state: InputState::new(),
+            idle_state: IdleState::default(),
 
             output_focus_stack: OutputFocusStack::default(),
             z_index_stack: Vec::new(),
 
-            config: Config::new(no_config, config_dir),
+            config,
 
             seat,
 
@@ -287,6 +401,7 @@ impl State {
 
             windows: Vec::new(),
             new_windows: Vec::new(),
+            window_groups: Vec::new(),
 
             xwayland,
             xwm: None,
@@ -343,6 +458,8 @@ impl State {
 #[derive(Default)]
 pub struct ClientState {
     pub compositor_state: CompositorClientState,
+    /// The security context this client connected through, if it's sandboxed
+    pub security_context: Option<SecurityContext>,
 }
 
 impl ClientData for ClientState {
@@ -7,7 +7,7 @@ use std::{
     sync::atomic::{AtomicU32, Ordering},
 };
 
-use smithay::output::Output;
+use smithay::output::{Output, WeakOutput};
 
 use crate::state::{State, WithState};
 
@@ -49,6 +49,8 @@ struct TagInner {
     name: String,
     /// Whether this tag is active or not.
     active: bool,
+    /// The output this tag was last added to.
+    output: Option<WeakOutput>,
 }
 
 impl PartialEq for TagInner {
@@ -100,9 +102,29 @@ impl Tag {
             id: TagId::next(),
             name,
             active: false,
+            output: None,
         })))
     }
 
+    /// Record that this tag is on `output`.
+    pub fn set_output(&self, output: &Output) {
+        self.0.borrow_mut().output = Some(output.downgrade());
+    }
+
+    /// Returns whether the output this tag is on has any active tags.
+    ///
+    /// RefCell Safety: This uses `with_state` on the tag's output.
+    pub fn output_has_active_tag(&self) -> bool {
+        let output = self
+            .0
+            .borrow()
+            .output
+            .as_ref()
+            .and_then(WeakOutput::upgrade);
+        output
+            .is_some_and(|output| output.with_state(|state| state.focused_tags().next().is_some()))
+    }
+
     /// Get the output this tag is on.
     ///
     /// RefCell Safety: This uses RefCells on every mapped output.
@@ -114,3 +136,47 @@ impl Tag {
             .cloned()
     }
 }
+
+impl State {
+    /// Activate or deactivate `tag`, then update its output.
+    pub fn set_tag_active(&mut self, tag: &Tag, active: bool) {
+        tag.set_active(active, self);
+
+        let Some(output) = tag.output(self) else {
+            return;
+        };
+
+        self.fixup_xwayland_internal_z_indices();
+
+        self.request_layout(&output);
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+
+    /// Returns whether any window on `tag` needs attention.
+    pub fn is_tag_urgent(&self, tag: &Tag) -> bool {
+        self.windows
+            .iter()
+            .any(|win| win.with_state(|state| state.urgent && state.tags.contains(tag)))
+    }
+
+    /// Activate `tag` and deactivate all other tags on its output.
+    pub fn switch_to_tag(&mut self, tag: &Tag) {
+        let Some(output) = tag.output(self) else {
+            return;
+        };
+
+        output.with_state_mut(|op_state| {
+            for op_tag in op_state.tags.iter_mut() {
+                op_tag.set_active(false, self);
+            }
+            tag.set_active(true, self);
+        });
+
+        self.fixup_xwayland_internal_z_indices();
+
+        self.request_layout(&output);
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+}
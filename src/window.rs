@@ -1,20 +1,33 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+pub mod group;
 pub mod rules;
+pub mod scratchpad;
+pub mod swallow;
 
 use std::{cell::RefCell, ops::Deref};
 
+use pinnacle_api_defs::pinnacle::signal::v0alpha1::WindowMinimizedResponse;
 use smithay::{
     desktop::{space::SpaceElement, Window, WindowSurface},
     output::Output,
-    reexports::wayland_server::protocol::wl_surface::WlSurface,
+    reexports::{
+        wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1,
+        wayland_server::protocol::wl_surface::WlSurface,
+    },
     utils::{IsAlive, Logical, Point, Rectangle},
-    wayland::{compositor, seat::WaylandFocus, shell::xdg::XdgToplevelSurfaceData},
+    wayland::{
+        compositor,
+        seat::WaylandFocus,
+        shell::xdg::{ToplevelSurface, XdgToplevelSurfaceData},
+    },
 };
 
+use tracing::{error, warn};
+
 use crate::state::{State, WithState};
 
-use self::window_state::WindowElementState;
+use self::window_state::{DecorationMode, WindowElementState};
 
 pub mod window_state;
 
@@ -101,17 +114,30 @@ impl WindowElement {
     /// Get the output this window is on.
     ///
     /// This method gets the first tag the window has and returns its output.
+    /// Scratchpad windows return the output they are shown on.
     ///
     /// RefCell Safety: This method uses a [`RefCell`] on this window and every mapped output.
     pub fn output(&self, state: &State) -> Option<Output> {
-        self.with_state(|st| st.tags.first().and_then(|tag| tag.output(state)))
+        self.scratchpad_output()
+            .or_else(|| self.with_state(|st| st.tags.first().and_then(|tag| tag.output(state))))
     }
 
     /// Returns whether or not this window has an active tag.
     ///
-    /// RefCell Safety: This calls `with_state` on `self`.
+    /// Shown scratchpad windows are always considered to be on an active tag.
+    /// Sticky windows are considered to be on an active tag as long as their output
+    /// has an active tag.
+    ///
+    /// RefCell Safety: This calls `with_state` on `self` and its tags' outputs.
     pub fn is_on_active_tag(&self) -> bool {
-        self.with_state(|state| state.tags.iter().any(|tag| tag.active()))
+        self.with_state(|state| {
+            state.tags.iter().any(|tag| tag.active())
+                || (state.sticky && state.tags.iter().any(|tag| tag.output_has_active_tag()))
+                || state
+                    .scratchpad
+                    .as_ref()
+                    .is_some_and(|scratchpad| scratchpad.shown_on.is_some())
+        })
     }
 
     /// Place this window on the given output, giving it the output's focused tags.
@@ -141,6 +167,63 @@ impl WindowElement {
     pub fn is_x11_override_redirect(&self) -> bool {
         matches!(self.x11_surface(), Some(surface) if surface.is_override_redirect())
     }
+
+    /// Ask this window to close.
+    ///
+    /// Override redirect windows will not be closed.
+    pub fn close(&self) {
+        match self.0.underlying_surface() {
+            WindowSurface::Wayland(toplevel) => toplevel.send_close(),
+            WindowSurface::X11(surface) => {
+                if !surface.is_override_redirect() {
+                    if let Err(err) = surface.close() {
+                        error!("failed to close x11 window: {err}");
+                    }
+                } else {
+                    warn!("tried to close OR window");
+                }
+            }
+        }
+    }
+
+    /// Force this window to use the given decoration mode.
+    ///
+    /// This does nothing to xwayland windows.
+    ///
+    /// RefCell Safety: This method uses a [`RefCell`] on this window.
+    pub fn set_decoration_mode(&self, mode: DecorationMode) {
+        self.with_state_mut(|state| state.decoration_mode = Some(mode));
+
+        if let Some(toplevel) = self.toplevel() {
+            toplevel.with_pending_state(|state| {
+                state.decoration_mode = Some(mode.into());
+            });
+            if initial_configure_sent(toplevel) {
+                toplevel.send_pending_configure();
+            }
+        }
+    }
+
+    /// Returns whether or not the compositor should draw this window's decorations.
+    pub fn is_server_side_decorated(&self) -> bool {
+        self.toplevel().is_some_and(|toplevel| {
+            toplevel.current_state().decoration_mode
+                == Some(zxdg_toplevel_decoration_v1::Mode::ServerSide)
+        })
+    }
+}
+
+/// Returns whether or not `toplevel` has been sent its initial configure.
+pub fn initial_configure_sent(toplevel: &ToplevelSurface) -> bool {
+    compositor::with_states(toplevel.wl_surface(), |states| {
+        states
+            .data_map
+            .get::<XdgToplevelSurfaceData>()
+            .expect("XdgToplevelSurfaceData wasn't in surface's data map")
+            .lock()
+            .expect("Failed to lock Mutex<XdgToplevelSurfaceData>")
+            .initial_configure_sent
+    })
 }
 
 impl SpaceElement for WindowElement {
@@ -223,6 +306,68 @@ impl State {
             .cloned()
     }
 
+    /// Minimize or restore `window`.
+    ///
+    /// Minimized windows are unmapped, so they aren't laid out, rendered, or focused
+    /// until they are restored.
+    pub fn set_window_minimized(&mut self, window: &WindowElement, minimized: bool) {
+        if window.is_x11_override_redirect() {
+            return;
+        }
+
+        let was_minimized =
+            window.with_state_mut(|state| std::mem::replace(&mut state.minimized, minimized));
+
+        if was_minimized == minimized {
+            return;
+        }
+
+        // xdg-shell has no minimized state to tell the client about
+        if let Some(surface) = window.x11_surface() {
+            if let Err(err) = surface.set_hidden(minimized) {
+                warn!("Failed to set xwayland window hidden: {err}");
+            }
+        }
+
+        if minimized {
+            // Don't let a pending commit map the window again
+            window.with_state_mut(|state| state.target_loc = None);
+            self.space.unmap_elem(window);
+        }
+
+        let window_id = Some(window.with_state(|state| state.id.0));
+
+        self.signal_state.window_minimized.signal(|buffer| {
+            buffer.push_back(WindowMinimizedResponse {
+                window_id,
+                minimized: Some(minimized),
+            })
+        });
+
+        let Some(output) = window.output(self) else {
+            return;
+        };
+
+        self.request_layout(&output);
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+
+    /// Make `window` sticky or not.
+    ///
+    /// Sticky windows are shown on every active tag of their output.
+    pub fn set_window_sticky(&mut self, window: &WindowElement, sticky: bool) {
+        window.with_state_mut(|state| state.sticky = sticky);
+
+        let Some(output) = window.output(self) else {
+            return;
+        };
+
+        self.request_layout(&output);
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+
     /// `window_for_surface` but for windows that haven't commited a buffer yet.
     ///
     /// Currently only used in `ensure_initial_configure` in [`handlers`][crate::handlers].
@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Tabbed window groups.
+//!
+//! Windows in a group share one tiled slot. Only the group's active window is laid out and
+//! mapped; the others stay unmapped until the group is cycled to them. A tab strip with
+//! one tab per window is drawn above the active window.
+//!
+//! The active window always holds the group's place in [`State::windows`], so the slot
+//! doesn't move around in the layout when the group is cycled.
+//!
+//! Groups are owned by [`State::window_groups`]. Windows only hold a [`WeakWindowGroup`],
+//! as a group holding its windows that hold the group would never be freed.
+
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use smithay::{
+    backend::renderer::element::solid::SolidColorBuffer,
+    utils::{Logical, Size},
+};
+
+use crate::state::{State, WithState};
+
+use super::{window_state::FullscreenOrMaximized, WindowElement};
+
+#[derive(Debug)]
+struct WindowGroupInner {
+    /// The windows in this group, in tab order.
+    members: Vec<WindowElement>,
+    /// The index of the window that is shown.
+    active: usize,
+    /// The buffers for each tab, reused across frames.
+    tab_buffers: Vec<SolidColorBuffer>,
+}
+
+/// A group of windows sharing one tiled slot.
+///
+/// Groups always have at least two windows; a group that is left with one is dissolved.
+#[derive(Debug, Clone)]
+pub struct WindowGroup(Rc<RefCell<WindowGroupInner>>);
+
+impl PartialEq for WindowGroup {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A weak reference to a [`WindowGroup`].
+#[derive(Debug, Clone)]
+pub struct WeakWindowGroup(Weak<RefCell<WindowGroupInner>>);
+
+impl WeakWindowGroup {
+    /// Get the group, if it still exists.
+    pub fn upgrade(&self) -> Option<WindowGroup> {
+        self.0.upgrade().map(WindowGroup)
+    }
+}
+
+// RefCell Safety: These methods never call into windows while the group is borrowed.
+impl WindowGroup {
+    fn new(window: WindowElement) -> Self {
+        Self(Rc::new(RefCell::new(WindowGroupInner {
+            members: vec![window],
+            active: 0,
+            tab_buffers: Vec::new(),
+        })))
+    }
+
+    /// Get a weak reference to this group.
+    pub fn downgrade(&self) -> WeakWindowGroup {
+        WeakWindowGroup(Rc::downgrade(&self.0))
+    }
+
+    /// Get the windows in this group, in tab order.
+    pub fn members(&self) -> Vec<WindowElement> {
+        self.0.borrow().members.clone()
+    }
+
+    /// Get the window that is shown.
+    pub fn active(&self) -> WindowElement {
+        let inner = self.0.borrow();
+        inner.members[inner.active].clone()
+    }
+
+    /// Get the index of the window that is shown.
+    pub fn active_index(&self) -> usize {
+        self.0.borrow().active
+    }
+
+    /// Get the buffers for this group's tabs, resized and recolored.
+    pub fn tab_buffers(&self, tabs: &[(Size<i32, Logical>, [f32; 4])]) -> Vec<SolidColorBuffer> {
+        let mut inner = self.0.borrow_mut();
+        inner.tab_buffers.truncate(tabs.len());
+        for (i, (size, color)) in tabs.iter().enumerate() {
+            match inner.tab_buffers.get_mut(i) {
+                Some(buffer) => buffer.update(*size, *color),
+                None => inner.tab_buffers.push(SolidColorBuffer::new(*size, *color)),
+            }
+        }
+        inner.tab_buffers.clone()
+    }
+}
+
+impl WindowElement {
+    /// Get the group this window is in, if any.
+    ///
+    /// RefCell Safety: This calls `with_state` on `self`.
+    pub fn group(&self) -> Option<WindowGroup> {
+        self.with_state(|state| state.group.as_ref().and_then(WeakWindowGroup::upgrade))
+    }
+
+    /// Returns whether or not this window is in a group but isn't the one shown.
+    ///
+    /// RefCell Safety: This calls `with_state` on `self` and borrows its group.
+    pub fn is_inactive_in_group(&self) -> bool {
+        self.group().is_some_and(|group| &group.active() != self)
+    }
+}
+
+impl State {
+    /// Add `window` to `target`'s group, creating one if `target` isn't in a group.
+    ///
+    /// Both windows are tiled, and `window` takes on `target`'s tags and becomes the
+    /// shown window of the group.
+    pub fn group_window_with(&mut self, window: &WindowElement, target: &WindowElement) {
+        if window == target
+            || window.is_x11_override_redirect()
+            || target.is_x11_override_redirect()
+            || window
+                .group()
+                .is_some_and(|group| Some(group) == target.group())
+        {
+            return;
+        }
+
+        let old_output = window.output(self);
+
+        self.remove_from_group(window);
+        self.remove_from_scratchpad(window);
+
+        for win in [window, target] {
+            match win.with_state(|state| state.fullscreen_or_maximized) {
+                FullscreenOrMaximized::Fullscreen => win.toggle_fullscreen(),
+                FullscreenOrMaximized::Maximized => win.toggle_maximized(),
+                FullscreenOrMaximized::Neither => (),
+            }
+
+            if win.with_state(|state| state.floating_or_tiled.is_floating()) {
+                win.toggle_floating();
+            }
+        }
+
+        let group = target.group().unwrap_or_else(|| {
+            let group = WindowGroup::new(target.clone());
+            target.with_state_mut(|state| state.group = Some(group.downgrade()));
+            self.window_groups.push(group.clone());
+            group
+        });
+
+        let (tags, sticky) = target.with_state(|state| (state.tags.clone(), state.sticky));
+        window.with_state_mut(|state| {
+            state.tags = tags;
+            state.sticky = sticky;
+            state.group = Some(group.downgrade());
+        });
+
+        let prev_active = group.active();
+
+        {
+            let mut inner = group.0.borrow_mut();
+            inner.members.push(window.clone());
+            inner.active = inner.members.len() - 1;
+        }
+
+        self.take_group_slot(window, &prev_active);
+
+        if let Some(output) = old_output.filter(|op| Some(op) != target.output(self).as_ref()) {
+            self.request_layout(&output);
+            self.update_focus(&output);
+            self.schedule_render(&output);
+        }
+
+        if let Some(output) = target.output(self) {
+            self.request_layout(&output);
+        }
+
+        self.activate_window(window.clone());
+    }
+
+    /// Take `window` out of its group and tile it on its own.
+    pub fn ungroup_window(&mut self, window: &WindowElement) {
+        if window.group().is_none() {
+            return;
+        }
+
+        self.remove_from_group(window);
+
+        let Some(output) = window.output(self) else {
+            return;
+        };
+
+        self.request_layout(&output);
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+
+    /// Show the window `delta` tabs away from the shown one in `window`'s group and focus it.
+    pub fn cycle_group(&mut self, window: &WindowElement, delta: i32) {
+        let Some(group) = window.group() else {
+            return;
+        };
+
+        let next_active = {
+            let inner = group.0.borrow();
+            let len = inner.members.len() as i64;
+            let next = (inner.active as i64 + delta as i64).rem_euclid(len) as usize;
+            inner.members[next].clone()
+        };
+
+        if next_active == group.active() {
+            return;
+        }
+
+        self.activate_window(next_active);
+    }
+
+    /// Make `window` the shown window of its group without focusing it.
+    pub fn show_in_group(&mut self, window: &WindowElement) {
+        let Some(group) = window.group() else {
+            return;
+        };
+
+        let prev_active = group.active();
+
+        if &prev_active == window {
+            return;
+        }
+
+        {
+            let mut inner = group.0.borrow_mut();
+            let Some(index) = inner.members.iter().position(|win| win == window) else {
+                return;
+            };
+            inner.active = index;
+        }
+
+        self.take_group_slot(window, &prev_active);
+
+        if let Some(output) = window.output(self) {
+            self.request_layout(&output);
+        }
+    }
+
+    /// Take `window` out of its group without laying anything out.
+    ///
+    /// If `window` was shown, the next window in the group takes its place.
+    /// If only one window is left, the group is dissolved.
+    pub fn remove_from_group(&mut self, window: &WindowElement) {
+        let Some(group) = window.group() else {
+            return;
+        };
+
+        window.with_state_mut(|state| state.group = None);
+
+        let was_active = &group.active() == window;
+
+        let (next_active, remaining) = {
+            let mut inner = group.0.borrow_mut();
+            let Some(index) = inner.members.iter().position(|win| win == window) else {
+                return;
+            };
+            inner.members.remove(index);
+            if index < inner.active || inner.active >= inner.members.len() {
+                inner.active = inner.active.saturating_sub(1);
+            }
+            let next_active = inner.members.get(inner.active).cloned();
+            (next_active, inner.members.clone())
+        };
+
+        if let [last] = remaining.as_slice() {
+            last.with_state_mut(|state| state.group = None);
+            self.window_groups.retain(|grp| grp != &group);
+        }
+
+        if let Some(next_active) = next_active.filter(|_| was_active) {
+            self.take_group_slot(&next_active, window);
+        }
+    }
+
+    /// Give `window` the place of `prev_active` in the window list and hide `prev_active`.
+    fn take_group_slot(&mut self, window: &WindowElement, prev_active: &WindowElement) {
+        let window_pos = self.windows.iter().position(|win| win == window);
+        let prev_pos = self.windows.iter().position(|win| win == prev_active);
+
+        if let (Some(window_pos), Some(prev_pos)) = (window_pos, prev_pos) {
+            self.windows.swap(window_pos, prev_pos);
+        }
+
+        if prev_active.is_inactive_in_group() {
+            // Don't let a pending commit map the window again
+            prev_active.with_state_mut(|state| state.target_loc = None);
+            self.space.unmap_elem(prev_active);
+        }
+    }
+}
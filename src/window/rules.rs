@@ -12,7 +12,11 @@ use super::WindowElement;
 
 use std::num::NonZeroU32;
 
-use crate::{output::OutputName, tag::TagId, window::window_state::FullscreenOrMaximized};
+use crate::{
+    output::OutputName,
+    tag::TagId,
+    window::window_state::{DecorationMode, FullscreenOrMaximized},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, Default)]
 pub struct WindowRuleCondition {
@@ -158,6 +162,21 @@ pub struct WindowRule {
     /// when set to floating.
     #[serde(default)]
     pub location: Option<(i32, i32)>,
+    /// Force the window to use client-side or server-side decorations.
+    #[serde(default)]
+    pub decoration_mode: Option<DecorationMode>,
+    /// Replace the window with a placeholder in screen captures.
+    #[serde(default)]
+    pub capture_hidden: Option<bool>,
+    /// Show the window on every active tag of its output.
+    #[serde(default)]
+    pub sticky: Option<bool>,
+    /// Draw the window with this opacity.
+    #[serde(default)]
+    pub opacity: Option<f32>,
+    /// Let the window be swallowed by windows its process starts.
+    #[serde(default)]
+    pub can_swallow: Option<bool>,
 }
 
 // TODO: just skip serializing fields on the other FloatingOrTiled
@@ -179,6 +198,11 @@ impl State {
                     fullscreen_or_maximized,
                     size,
                     location,
+                    decoration_mode,
+                    capture_hidden,
+                    sticky,
+                    opacity,
+                    can_swallow,
                 } = rule;
 
                 // TODO: If both `output` and `tags` are specified, `tags` will apply over
@@ -245,6 +269,26 @@ impl State {
                     }
                 }
 
+                if let Some(mode) = decoration_mode {
+                    window.set_decoration_mode(*mode);
+                }
+
+                if let Some(hidden) = capture_hidden {
+                    window.with_state_mut(|state| state.capture_hidden = *hidden);
+                }
+
+                if let Some(sticky) = sticky {
+                    window.with_state_mut(|state| state.sticky = *sticky);
+                }
+
+                if let Some(opacity) = opacity {
+                    window.with_state_mut(|state| state.opacity = Some(opacity.clamp(0.0, 1.0)));
+                }
+
+                if let Some(can_swallow) = can_swallow {
+                    window.with_state_mut(|state| state.can_swallow = *can_swallow);
+                }
+
                 if let Some(loc) = location {
                     match window.with_state(|state| state.floating_or_tiled) {
                         window_state::FloatingOrTiled::Floating(mut rect) => {
@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Named scratchpads.
+//!
+//! Windows sent to a scratchpad are detached from their tags and float above whatever
+//! tag is active on the output they're shown on. Hiding a scratchpad window only unmaps it
+//! from the space, so the client keeps running as if nothing happened.
+
+use smithay::{
+    desktop::layer_map_for_output,
+    output::{Output, WeakOutput},
+    utils::Rectangle,
+};
+
+use crate::state::{State, WithState};
+
+use super::{
+    window_state::{FloatingOrTiled, FullscreenOrMaximized},
+    WindowElement,
+};
+
+/// The scratchpad a window is in.
+#[derive(Debug, Clone)]
+pub struct Scratchpad {
+    /// The name of the scratchpad
+    pub name: String,
+    /// The output the window is currently shown on, or `None` if it's hidden
+    pub shown_on: Option<WeakOutput>,
+}
+
+impl WindowElement {
+    /// Get the name of the scratchpad this window is in, if any.
+    ///
+    /// RefCell Safety: This calls `with_state` on `self`.
+    pub fn scratchpad_name(&self) -> Option<String> {
+        self.with_state(|state| {
+            state
+                .scratchpad
+                .as_ref()
+                .map(|scratchpad| scratchpad.name.clone())
+        })
+    }
+
+    /// Get the output this scratchpad window is shown on.
+    ///
+    /// Returns `None` if this window isn't in a scratchpad or is hidden.
+    ///
+    /// RefCell Safety: This calls `with_state` on `self`.
+    pub fn scratchpad_output(&self) -> Option<Output> {
+        self.with_state(|state| {
+            state
+                .scratchpad
+                .as_ref()
+                .and_then(|scratchpad| scratchpad.shown_on.as_ref())
+                .and_then(|output| output.upgrade())
+        })
+    }
+}
+
+impl State {
+    /// Detach `window` from its tags and put it in the scratchpad called `name`.
+    ///
+    /// The window is made floating and hidden until the scratchpad is toggled.
+    pub fn send_to_scratchpad(&mut self, window: &WindowElement, name: String) {
+        if window.is_x11_override_redirect() {
+            return;
+        }
+
+        let output = window.output(self);
+
+        self.remove_from_group(window);
+
+        match window.with_state(|state| state.fullscreen_or_maximized) {
+            FullscreenOrMaximized::Fullscreen => window.toggle_fullscreen(),
+            FullscreenOrMaximized::Maximized => window.toggle_maximized(),
+            FullscreenOrMaximized::Neither => (),
+        }
+
+        if window.with_state(|state| state.floating_or_tiled.is_tiled()) {
+            window.toggle_floating();
+        }
+
+        self.hide_scratchpad_window(window);
+
+        window.with_state_mut(|state| {
+            state.tags.clear();
+            state.scratchpad = Some(Scratchpad {
+                name,
+                shown_on: None,
+            });
+        });
+
+        if let Some(output) = output {
+            self.request_layout(&output);
+            self.update_focus(&output);
+            self.schedule_render(&output);
+        }
+    }
+
+    /// Take `window` out of its scratchpad.
+    ///
+    /// This doesn't give the window any tags; callers should do that.
+    pub fn remove_from_scratchpad(&mut self, window: &WindowElement) {
+        window.with_state_mut(|state| state.scratchpad.take());
+    }
+
+    /// Show or hide the windows in the scratchpad called `name`.
+    ///
+    /// If they're shown on the focused output, they are hidden. Otherwise, they are
+    /// shown centered on the focused output and focused.
+    pub fn toggle_scratchpad(&mut self, name: &str) {
+        let Some(output) = self.focused_output().cloned() else {
+            return;
+        };
+
+        let windows = self
+            .windows
+            .iter()
+            .filter(|win| win.scratchpad_name().as_deref() == Some(name))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let shown_here = windows
+            .iter()
+            .any(|win| win.scratchpad_output().as_ref() == Some(&output));
+
+        for window in windows.iter() {
+            let old_output = window.scratchpad_output();
+
+            self.hide_scratchpad_window(window);
+
+            if let Some(old_output) = old_output.filter(|op| op != &output) {
+                self.update_focus(&old_output);
+                self.schedule_render(&old_output);
+            }
+
+            if !shown_here {
+                self.show_scratchpad_window(window, &output);
+            }
+        }
+
+        self.update_focus(&output);
+        self.schedule_render(&output);
+    }
+
+    /// Float `window` in the center of `output` and focus it.
+    fn show_scratchpad_window(&mut self, window: &WindowElement, output: &Output) {
+        let Some(output_geo) = self.space.output_geometry(output) else {
+            return;
+        };
+
+        self.set_window_minimized(window, false);
+
+        let non_exclusive_geo = layer_map_for_output(output).non_exclusive_zone();
+
+        let size = match window.with_state(|state| state.floating_or_tiled) {
+            FloatingOrTiled::Floating(rect) => rect.size,
+            FloatingOrTiled::Tiled(_) => window.geometry().size,
+        };
+
+        let mut loc = output_geo.loc + non_exclusive_geo.loc;
+        loc.x += (non_exclusive_geo.size.w - size.w) / 2;
+        loc.y += (non_exclusive_geo.size.h - size.h) / 2;
+
+        let geo = Rectangle::from_loc_and_size(loc, size);
+
+        window.with_state_mut(|state| {
+            state.floating_or_tiled = FloatingOrTiled::Floating(geo);
+            if let Some(scratchpad) = state.scratchpad.as_mut() {
+                scratchpad.shown_on = Some(output.downgrade());
+            }
+        });
+
+        window.change_geometry(geo);
+        window.with_state_mut(|state| state.target_loc.take());
+        self.space.map_element(window.clone(), geo.loc, true);
+
+        if let Some(toplevel) = window.toplevel() {
+            toplevel.send_pending_configure();
+        }
+
+        output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
+        self.raise_window(window.clone(), true);
+    }
+
+    /// Unmap `window` and remove it from every output's focus stack.
+    fn hide_scratchpad_window(&mut self, window: &WindowElement) {
+        window.with_state_mut(|state| {
+            state.target_loc.take();
+            if let Some(scratchpad) = state.scratchpad.as_mut() {
+                scratchpad.shown_on = None;
+            }
+        });
+
+        self.space.unmap_elem(window);
+
+        for output in self.space.outputs() {
+            output.with_state_mut(|state| state.focus_stack.stack.retain(|win| win != window));
+        }
+    }
+}
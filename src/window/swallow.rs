@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Window swallowing.
+//!
+//! When a window opens from a process started by another window that allows swallowing,
+//! like an image viewer launched from a terminal, the parent window is hidden and the new
+//! window takes its place in the tiling order. The parent is restored when the new window
+//! closes.
+
+use smithay::{desktop::WindowSurface, reexports::wayland_server::Resource};
+use sysinfo::{Pid, ProcessRefreshKind};
+
+use crate::state::{State, WithState};
+
+use super::WindowElement;
+
+impl WindowElement {
+    /// Get the id of the process this window belongs to.
+    pub fn pid(&self, state: &State) -> Option<u32> {
+        match self.underlying_surface() {
+            WindowSurface::Wayland(toplevel) => {
+                let client = toplevel.wl_surface().client()?;
+                let credentials = client.get_credentials(&state.display_handle).ok()?;
+                u32::try_from(credentials.pid).ok()
+            }
+            WindowSurface::X11(surface) => surface.pid(),
+        }
+    }
+}
+
+impl State {
+    /// Hide the window that started `window`'s process, if it allows swallowing,
+    /// and give `window` its place in the tiling order.
+    ///
+    /// This should be called on new windows after window rules are applied.
+    pub fn swallow_parent(&mut self, window: &WindowElement) {
+        if window.is_x11_override_redirect() {
+            return;
+        }
+
+        let Some(pid) = window.pid(self) else {
+            return;
+        };
+
+        let candidates = self
+            .windows
+            .iter()
+            .filter(|win| *win != window)
+            .filter(|win| !win.is_x11_override_redirect())
+            .filter(|win| {
+                win.with_state(|state| {
+                    state.can_swallow
+                        && !state.swallowed
+                        && !state.minimized
+                        && state.scratchpad.is_none()
+                        && state.group.is_none()
+                })
+            })
+            .filter_map(|win| Some((win.pid(self)?, win.clone())))
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        self.system_processes
+            .refresh_processes_specifics(ProcessRefreshKind::new());
+
+        let compositor_pid = std::process::id();
+
+        // Walk up the process tree until we hit a window that can swallow this one
+        let mut ancestor = self
+            .system_processes
+            .process(Pid::from_u32(pid))
+            .and_then(|proc| proc.parent());
+
+        let parent = loop {
+            let Some(ancestor_pid) = ancestor else {
+                return;
+            };
+
+            if ancestor_pid.as_u32() == compositor_pid {
+                return;
+            }
+
+            if let Some((_, parent)) = candidates
+                .iter()
+                .find(|(pid, _)| *pid == ancestor_pid.as_u32())
+            {
+                break parent.clone();
+            }
+
+            ancestor = self
+                .system_processes
+                .process(ancestor_pid)
+                .and_then(|proc| proc.parent());
+        };
+
+        self.swallow_window(window, &parent);
+    }
+
+    /// Hide `parent` and give `window` its tags and place in the tiling order.
+    ///
+    /// `parent` is restored when `window` is destroyed.
+    pub fn swallow_window(&mut self, window: &WindowElement, parent: &WindowElement) {
+        let (tags, sticky) = parent.with_state(|state| (state.tags.clone(), state.sticky));
+        window.with_state_mut(|state| {
+            state.tags = tags;
+            state.sticky = sticky;
+            state.swallowing = Some(parent.clone());
+        });
+
+        parent.with_state_mut(|state| {
+            state.swallowed = true;
+            // Don't let a pending commit map the window again
+            state.target_loc = None;
+        });
+        self.space.unmap_elem(parent);
+
+        self.windows.retain(|win| win != window);
+        if let Some(index) = self.windows.iter().position(|win| win == parent) {
+            self.windows.insert(index, window.clone());
+        } else {
+            self.windows.push(window.clone());
+        }
+
+        if let Some(output) = window.output(self) {
+            output.with_state_mut(|state| state.focus_stack.set_focus(window.clone()));
+        }
+    }
+
+    /// Restore the window `window` swallowed, if any, in `window`'s place.
+    ///
+    /// This should be called when `window` is being destroyed, before it is removed
+    /// from the window list.
+    pub fn restore_swallowed(&mut self, window: &WindowElement) {
+        let Some(parent) = window.with_state_mut(|state| state.swallowing.take()) else {
+            return;
+        };
+
+        // The parent may have closed while it was hidden
+        if !self.windows.contains(&parent) {
+            return;
+        }
+
+        parent.with_state_mut(|state| state.swallowed = false);
+
+        self.windows.retain(|win| win != &parent);
+        if let Some(index) = self.windows.iter().position(|win| win == window) {
+            self.windows.insert(index, parent.clone());
+        } else {
+            self.windows.push(parent.clone());
+        }
+
+        if let Some(output) = parent.output(self) {
+            output.with_state_mut(|state| state.focus_stack.set_focus(parent.clone()));
+        }
+    }
+}
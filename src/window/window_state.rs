@@ -3,17 +3,21 @@
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use smithay::{
+    backend::renderer::element::solid::SolidColorBuffer,
     desktop::{space::SpaceElement, WindowSurface},
-    reexports::wayland_protocols::xdg::shell::server::xdg_toplevel,
+    reexports::wayland_protocols::xdg::{
+        decoration::zv1::server::zxdg_toplevel_decoration_v1, shell::server::xdg_toplevel,
+    },
     utils::{Logical, Point, Rectangle},
 };
 
 use crate::{
+    protocol::toplevel_export::ToplevelExport,
     state::{State, WithState},
     tag::Tag,
 };
 
-use super::WindowElement;
+use super::{group::WeakWindowGroup, scratchpad::Scratchpad, WindowElement};
 
 /// A unique identifier for each window.
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -47,6 +51,42 @@ pub struct WindowElementState {
     pub floating_or_tiled: FloatingOrTiled,
     pub fullscreen_or_maximized: FullscreenOrMaximized,
     pub target_loc: Option<Point<i32, Logical>>,
+    /// The decoration mode forced on this window by a window rule or the API.
+    ///
+    /// If this is `None`, the window's client decides.
+    pub decoration_mode: Option<DecorationMode>,
+    /// The buffers for each side of this window's server-side border
+    pub border_buffers: Option<[SolidColorBuffer; 4]>,
+    /// Whether or not this window needs attention.
+    pub urgent: bool,
+    /// The scratchpad this window is in, if any.
+    ///
+    /// Scratchpad windows have no tags.
+    pub scratchpad: Option<Scratchpad>,
+    /// Whether or not this window is minimized.
+    ///
+    /// Minimized windows are unmapped until they are restored.
+    pub minimized: bool,
+    /// Whether or not this window is replaced by a placeholder in screen captures.
+    pub capture_hidden: bool,
+    /// Whether or not this window is shown on every active tag of its output.
+    pub sticky: bool,
+    /// The tabbed group this window is in, if any.
+    pub group: Option<WeakWindowGroup>,
+    /// Whether or not this window can be swallowed by windows its process starts.
+    pub can_swallow: bool,
+    /// Whether or not this window is hidden because a window it started swallowed it.
+    pub swallowed: bool,
+    /// The window this window swallowed, restored when this window closes.
+    pub swallowing: Option<WindowElement>,
+    /// The opacity of this window.
+    ///
+    /// If this is `None`, the configured focused or unfocused opacity is used.
+    pub opacity: Option<f32>,
+    /// The buffer for the placeholder drawn over this window in screen captures
+    pub capture_placeholder: Option<SolidColorBuffer>,
+    /// Toplevel exports waiting for this window to commit new content.
+    pub pending_toplevel_exports: Vec<ToplevelExport>,
 }
 
 impl WindowElement {
@@ -285,6 +325,22 @@ impl FullscreenOrMaximized {
     }
 }
 
+/// Whether a window draws its own decorations or the compositor draws them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DecorationMode {
+    ClientSide,
+    ServerSide,
+}
+
+impl From<DecorationMode> for zxdg_toplevel_decoration_v1::Mode {
+    fn from(value: DecorationMode) -> Self {
+        match value {
+            DecorationMode::ClientSide => zxdg_toplevel_decoration_v1::Mode::ClientSide,
+            DecorationMode::ServerSide => zxdg_toplevel_decoration_v1::Mode::ServerSide,
+        }
+    }
+}
+
 impl WindowElementState {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
@@ -295,6 +351,20 @@ impl WindowElementState {
             floating_or_tiled: FloatingOrTiled::Tiled(None),
             fullscreen_or_maximized: FullscreenOrMaximized::Neither,
             target_loc: None,
+            decoration_mode: None,
+            border_buffers: None,
+            urgent: false,
+            scratchpad: None,
+            minimized: false,
+            capture_hidden: false,
+            sticky: false,
+            group: None,
+            can_swallow: false,
+            swallowed: false,
+            swallowing: None,
+            opacity: None,
+            capture_placeholder: None,
+            pending_toplevel_exports: Vec::new(),
         }
     }
 }
@@ -0,0 +1,60 @@
+mod common;
+
+use pinnacle::{
+    backend::Backend,
+    render::{output_render_elements, OutputRenderElement, RenderOptions},
+    state::WithState,
+};
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, output_for_name, spawn_windows, test_api, window_for_class, with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn capture_hidden_window_is_only_hidden_from_captures() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["secret"]);
+
+        with_state(&sender, |state| {
+            let output = output_for_name(state, "Pinnacle Window");
+            let secret = window_for_class(state, "secret");
+            secret.with_state_mut(|state| state.capture_hidden = true);
+
+            let windows = state.windows.clone();
+            let render_options = state.render_options();
+
+            let Backend::Dummy(dummy) = &mut state.backend else {
+                unreachable!()
+            };
+
+            let mut render = |for_capture| {
+                output_render_elements(
+                    &output,
+                    &mut dummy.renderer,
+                    &state.space,
+                    &windows,
+                    &RenderOptions {
+                        for_capture,
+                        ..render_options
+                    },
+                )
+            };
+
+            let is_surface =
+                |elem: &OutputRenderElement<_, _>| matches!(elem, OutputRenderElement::Surface(_));
+
+            // The output itself shows the window
+            assert!(render(false).iter().any(is_surface));
+
+            // Captures get a placeholder instead
+            let captured = render(true);
+            assert!(!captured.iter().any(is_surface));
+            assert!(captured
+                .iter()
+                .any(|elem| matches!(elem, OutputRenderElement::Solid(_))));
+        });
+    })
+}
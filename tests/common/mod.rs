@@ -1,6 +1,9 @@
+#![allow(dead_code)]
+
 use std::{panic::UnwindSafe, time::Duration};
 
-use pinnacle::{backend::dummy::setup_dummy, state::State};
+use pinnacle::{backend::dummy::setup_dummy, state::State, window::WindowElement};
+use pinnacle_api::ApiModules;
 use smithay::{
     output::Output,
     reexports::calloop::{
@@ -9,11 +12,10 @@ use smithay::{
     },
 };
 
-#[allow(clippy::type_complexity)]
-pub fn with_state(
-    sender: &Sender<Box<dyn FnOnce(&mut State) + Send>>,
-    with_state: impl FnOnce(&mut State) + Send + 'static,
-) {
+/// A closure run on the compositor's event loop.
+pub type StateFn = Box<dyn FnOnce(&mut State) + Send>;
+
+pub fn with_state(sender: &Sender<StateFn>, with_state: impl FnOnce(&mut State) + Send + 'static) {
     sender.send(Box::new(with_state)).unwrap();
 }
 
@@ -22,11 +24,11 @@ pub fn sleep_secs(secs: u64) {
 }
 
 pub fn test_api(
-    test: impl FnOnce(Sender<Box<dyn FnOnce(&mut State) + Send>>) + Send + UnwindSafe + 'static,
+    test: impl FnOnce(Sender<StateFn>) + Send + UnwindSafe + 'static,
 ) -> anyhow::Result<()> {
     let (mut state, mut event_loop) = setup_dummy(true, None)?;
 
-    let (sender, recv) = calloop::channel::channel::<Box<dyn FnOnce(&mut State) + Send>>();
+    let (sender, recv) = calloop::channel::channel::<StateFn>();
 
     event_loop
         .handle()
@@ -84,3 +86,54 @@ pub fn output_for_name(state: &State, name: &str) -> Output {
         .unwrap()
         .clone()
 }
+
+#[tokio::main]
+async fn run_rust_inner(run: impl FnOnce(ApiModules) + Send + 'static) {
+    let (api, _recv) = pinnacle_api::connect().await.unwrap();
+
+    run(api.clone());
+}
+
+pub fn run_rust(run: impl FnOnce(ApiModules) + Send + 'static) {
+    std::thread::spawn(|| {
+        run_rust_inner(run);
+    })
+    .join()
+    .unwrap();
+}
+
+/// Add a tag to the focused output and activate it so new windows are shown.
+pub fn add_active_tag(name: &'static str) {
+    run_rust(move |api| {
+        let output = api.output.get_focused().unwrap();
+        api.tag.add(&output, [name])[0].set_active(true);
+    });
+}
+
+/// Open a `foot` window with each app id.
+///
+/// Each window is given time to open before the next is spawned so they end up
+/// in this order.
+pub fn spawn_windows(app_ids: &[&str]) {
+    for app_id in app_ids {
+        let args = ["foot".to_string(), format!("--app-id={app_id}")];
+        run_rust(move |api| api.process.spawn(args));
+        sleep_secs(1);
+    }
+}
+
+pub fn window_for_class(state: &State, class: &str) -> WindowElement {
+    state
+        .windows
+        .iter()
+        .find(|win| win.class().as_deref() == Some(class))
+        .unwrap()
+        .clone()
+}
+
+pub fn window_classes<'a>(windows: impl IntoIterator<Item = &'a WindowElement>) -> Vec<String> {
+    windows
+        .into_iter()
+        .map(|win| win.class().unwrap_or_default())
+        .collect()
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use test_log::test;
+
+use crate::common::{run_rust, test_api, with_state};
+
+#[tokio::main]
+#[self::test]
+async fn set_xcursor_is_passed_on_to_spawned_clients() -> anyhow::Result<()> {
+    test_api(|sender| {
+        run_rust(|api| {
+            api.input.set_xcursor_theme("Adwaita");
+            api.input.set_xcursor_size(48);
+        });
+
+        with_state(&sender, |_state| {
+            assert_eq!(std::env::var("XCURSOR_THEME").as_deref(), Ok("Adwaita"));
+            assert_eq!(std::env::var("XCURSOR_SIZE").as_deref(), Ok("48"));
+        });
+
+        // Only the size changes
+        run_rust(|api| api.input.set_xcursor_size(32));
+
+        with_state(&sender, |_state| {
+            assert_eq!(std::env::var("XCURSOR_THEME").as_deref(), Ok("Adwaita"));
+            assert_eq!(std::env::var("XCURSOR_SIZE").as_deref(), Ok("32"));
+        });
+    })
+}
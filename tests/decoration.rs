@@ -0,0 +1,43 @@
+mod common;
+
+use pinnacle_api::window::DecorationMode;
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, run_rust, sleep_secs, spawn_windows, test_api, window_for_class, with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn set_decoration_mode_switches_between_client_and_server_side() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["decorated"]);
+
+        run_rust(|api| {
+            api.window
+                .get_focused()
+                .unwrap()
+                .set_decoration_mode(DecorationMode::ClientSide);
+        });
+        sleep_secs(1);
+
+        with_state(&sender, |state| {
+            let window = window_for_class(state, "decorated");
+            assert!(!window.is_server_side_decorated());
+        });
+
+        run_rust(|api| {
+            api.window
+                .get_focused()
+                .unwrap()
+                .set_decoration_mode(DecorationMode::ServerSide);
+        });
+        sleep_secs(1);
+
+        with_state(&sender, |state| {
+            let window = window_for_class(state, "decorated");
+            assert!(window.is_server_side_decorated());
+        });
+    })
+}
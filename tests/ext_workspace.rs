@@ -0,0 +1,38 @@
+mod common;
+
+use pinnacle::{protocol::ext_workspace::ExtWorkspaceHandler, state::WithState};
+use test_log::test;
+
+use crate::common::{run_rust, test_api, with_state};
+
+#[tokio::main]
+#[self::test]
+async fn activate_and_deactivate_workspace() -> anyhow::Result<()> {
+    test_api(|sender| {
+        run_rust(|api| {
+            let output = api.output.get_focused().unwrap();
+            api.tag.add(&output, ["1", "2", "3"])[0].set_active(true);
+        });
+
+        with_state(&sender, |state| {
+            let output = state.focused_output().unwrap().clone();
+            let tags = output.with_state(|state| state.tags.clone());
+            let active = |tags: &[pinnacle::tag::Tag]| {
+                tags.iter().map(|tag| tag.active()).collect::<Vec<_>>()
+            };
+
+            assert_eq!(active(&tags), [true, false, false]);
+
+            // Activating switches to only that tag
+            state.activate_workspace(tags[1].id().0);
+            assert_eq!(active(&tags), [false, true, false]);
+
+            state.deactivate_workspace(tags[1].id().0);
+            assert_eq!(active(&tags), [false, false, false]);
+
+            // Unknown ids are ignored
+            state.activate_workspace(u32::MAX);
+            assert_eq!(active(&tags), [false, false, false]);
+        });
+    })
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use pinnacle::{
+    protocol::foreign_toplevel::ForeignToplevelHandler, session_lock::LockState, state::WithState,
+};
+use smithay::wayland::seat::WaylandFocus;
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, run_rust, spawn_windows, test_api, window_for_class, with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn activate_is_ignored_while_locked() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["first", "second"]);
+
+        with_state(&sender, |state| {
+            let output = state.focused_output().unwrap().clone();
+            let first = window_for_class(state, "first");
+            let second = window_for_class(state, "second");
+
+            assert_eq!(state.focused_window(&output), Some(second.clone()));
+
+            state.lock_state = LockState::Locked;
+            ForeignToplevelHandler::activate(state, first.wl_surface().unwrap());
+
+            assert_eq!(state.focused_window(&output), Some(second));
+
+            state.lock_state = LockState::Unlocked;
+            ForeignToplevelHandler::activate(state, first.wl_surface().unwrap());
+
+            assert_eq!(state.focused_window(&output), Some(first));
+        });
+    })
+}
+
+#[tokio::main]
+#[self::test]
+async fn activate_switches_to_the_windows_tag() -> anyhow::Result<()> {
+    test_api(|sender| {
+        run_rust(|api| {
+            let output = api.output.get_focused().unwrap();
+            api.tag.add(&output, ["1", "2"])[0].set_active(true);
+        });
+        spawn_windows(&["first"]);
+
+        with_state(&sender, |state| {
+            let output = state.focused_output().unwrap().clone();
+            let tags = output.with_state(|state| state.tags.clone());
+            let first = window_for_class(state, "first");
+
+            state.switch_to_tag(&tags[1]);
+            assert!(!first.is_on_active_tag());
+
+            ForeignToplevelHandler::activate(state, first.wl_surface().unwrap());
+
+            assert!(tags[0].active());
+            assert!(!tags[1].active());
+            assert_eq!(state.focused_window(&output), Some(first));
+        });
+    })
+}
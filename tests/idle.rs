@@ -0,0 +1,63 @@
+mod common;
+
+use std::time::Duration;
+
+use smithay::wayland::seat::WaylandFocus;
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, sleep_secs, spawn_windows, test_api, window_for_class, with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn seat_becomes_idle_after_timeout_and_activity_ends_it() -> anyhow::Result<()> {
+    test_api(|sender| {
+        with_state(&sender, |state| {
+            state.set_idle_timeout(Some(Duration::from_millis(100)));
+        });
+
+        sleep_secs(1);
+
+        with_state(&sender, |state| {
+            assert!(state.idle_state.is_idle());
+
+            state.notify_activity();
+
+            assert!(!state.idle_state.is_idle());
+        });
+    })
+}
+
+#[tokio::main]
+#[self::test]
+async fn visible_inhibitor_prevents_idle() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["inhibitor"]);
+
+        with_state(&sender, |state| {
+            let window = window_for_class(state, "inhibitor");
+            state.idle_state.add_inhibitor(window.wl_surface().unwrap());
+            state.refresh_idle_inhibit();
+
+            assert!(state.idle_state.is_inhibited());
+
+            state.set_idle_timeout(Some(Duration::from_millis(100)));
+        });
+
+        sleep_secs(1);
+
+        with_state(&sender, |state| {
+            assert!(!state.idle_state.is_idle());
+
+            let window = window_for_class(state, "inhibitor");
+            state
+                .idle_state
+                .remove_inhibitor(&window.wl_surface().unwrap());
+            state.refresh_idle_inhibit();
+
+            assert!(!state.idle_state.is_inhibited());
+        });
+    })
+}
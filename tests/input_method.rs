@@ -0,0 +1,233 @@
+mod common;
+
+use std::{
+    os::{fd::AsFd, unix::net::UnixStream},
+    sync::Arc,
+    time::Duration,
+};
+
+use pinnacle::state::ClientState;
+use test_log::test;
+use wayland_client::{
+    delegate_noop,
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{
+        wl_buffer::WlBuffer,
+        wl_compositor::WlCompositor,
+        wl_registry::{self, WlRegistry},
+        wl_seat::WlSeat,
+        wl_shm,
+        wl_shm::WlShm,
+        wl_shm_pool::WlShmPool,
+        wl_surface::WlSurface,
+    },
+    Connection, Dispatch, EventQueue, QueueHandle,
+};
+use wayland_protocols::{
+    wp::text_input::zv3::client::{
+        zwp_text_input_manager_v3::ZwpTextInputManagerV3,
+        zwp_text_input_v3::{self, ZwpTextInputV3},
+    },
+    xdg::shell::client::{
+        xdg_surface::{self, XdgSurface},
+        xdg_toplevel::XdgToplevel,
+        xdg_wm_base::{self, XdgWmBase},
+    },
+};
+use wayland_protocols_misc::zwp_input_method_v2::client::{
+    zwp_input_method_manager_v2::ZwpInputMethodManagerV2,
+    zwp_input_method_v2::{self, ZwpInputMethodV2},
+};
+
+use crate::common::{add_active_tag, test_api, with_state};
+
+/// A client that has both a text input on a mapped window and the input method.
+#[derive(Default)]
+struct TestClient {
+    configured: bool,
+    text_input_entered: bool,
+    committed_text: Option<String>,
+    input_method_active: bool,
+    input_method_done_count: u32,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for TestClient {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgWmBase, ()> for TestClient {
+    fn event(
+        _state: &mut Self,
+        wm_base: &XdgWmBase,
+        event: xdg_wm_base::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<XdgSurface, ()> for TestClient {
+    fn event(
+        state: &mut Self,
+        xdg_surface: &XdgSurface,
+        event: xdg_surface::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let xdg_surface::Event::Configure { serial } = event {
+            xdg_surface.ack_configure(serial);
+            state.configured = true;
+        }
+    }
+}
+
+impl Dispatch<ZwpTextInputV3, ()> for TestClient {
+    fn event(
+        state: &mut Self,
+        _text_input: &ZwpTextInputV3,
+        event: zwp_text_input_v3::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwp_text_input_v3::Event::Enter { .. } => state.text_input_entered = true,
+            zwp_text_input_v3::Event::Leave { .. } => state.text_input_entered = false,
+            zwp_text_input_v3::Event::CommitString { text } => state.committed_text = text,
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwpInputMethodV2, ()> for TestClient {
+    fn event(
+        state: &mut Self,
+        _input_method: &ZwpInputMethodV2,
+        event: zwp_input_method_v2::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwp_input_method_v2::Event::Activate => state.input_method_active = true,
+            zwp_input_method_v2::Event::Deactivate => state.input_method_active = false,
+            zwp_input_method_v2::Event::Done => state.input_method_done_count += 1,
+            _ => (),
+        }
+    }
+}
+
+delegate_noop!(TestClient: WlCompositor);
+delegate_noop!(TestClient: ignore WlSurface);
+delegate_noop!(TestClient: ignore WlShm);
+delegate_noop!(TestClient: WlShmPool);
+delegate_noop!(TestClient: ignore WlBuffer);
+delegate_noop!(TestClient: ignore WlSeat);
+delegate_noop!(TestClient: ignore XdgToplevel);
+delegate_noop!(TestClient: ZwpTextInputManagerV3);
+delegate_noop!(TestClient: ZwpInputMethodManagerV2);
+
+/// Dispatch events until `done` returns true, panicking if that takes too long.
+fn dispatch_until(
+    queue: &mut EventQueue<TestClient>,
+    client: &mut TestClient,
+    done: impl Fn(&TestClient) -> bool,
+) {
+    for _ in 0..50 {
+        queue.roundtrip(client).unwrap();
+        if done(client) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    panic!("timed out waiting for the compositor");
+}
+
+#[tokio::main]
+#[self::test]
+async fn input_method_commits_text_to_text_input() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+
+        let (client_stream, server_stream) = UnixStream::pair().unwrap();
+
+        with_state(&sender, move |state| {
+            state
+                .display_handle
+                .insert_client(server_stream, Arc::new(ClientState::default()))
+                .unwrap();
+        });
+
+        let conn = Connection::from_socket(client_stream).unwrap();
+        let (globals, mut queue) = registry_queue_init::<TestClient>(&conn).unwrap();
+        let qh = queue.handle();
+        let mut client = TestClient::default();
+
+        let compositor: WlCompositor = globals.bind(&qh, 1..=4, ()).unwrap();
+        let shm: WlShm = globals.bind(&qh, 1..=1, ()).unwrap();
+        let wm_base: XdgWmBase = globals.bind(&qh, 1..=3, ()).unwrap();
+        let seat: WlSeat = globals.bind(&qh, 1..=7, ()).unwrap();
+        let text_input_manager: ZwpTextInputManagerV3 = globals.bind(&qh, 1..=1, ()).unwrap();
+        let input_method_manager: ZwpInputMethodManagerV2 = globals.bind(&qh, 1..=1, ()).unwrap();
+
+        let text_input = text_input_manager.get_text_input(&seat, &qh, ());
+        let input_method = input_method_manager.get_input_method(&seat, &qh, ());
+
+        let surface = compositor.create_surface(&qh, ());
+        let xdg_surface = wm_base.get_xdg_surface(&surface, &qh, ());
+        let toplevel = xdg_surface.get_toplevel(&qh, ());
+        toplevel.set_app_id("text-input".to_string());
+        surface.commit();
+
+        dispatch_until(&mut queue, &mut client, |client| client.configured);
+
+        let (width, height) = (100, 100);
+        let file = tempfile::tempfile().unwrap();
+        file.set_len((width * height * 4) as u64).unwrap();
+        let pool = shm.create_pool(file.as_fd(), width * height * 4, &qh, ());
+        let buffer = pool.create_buffer(
+            0,
+            width,
+            height,
+            width * 4,
+            wl_shm::Format::Argb8888,
+            &qh,
+            (),
+        );
+        surface.attach(Some(&buffer), 0, 0);
+        surface.commit();
+
+        // The window gets keyboard focus once it maps
+        dispatch_until(&mut queue, &mut client, |client| client.text_input_entered);
+
+        text_input.enable();
+        text_input.commit();
+
+        dispatch_until(&mut queue, &mut client, |client| {
+            client.input_method_active && client.input_method_done_count > 0
+        });
+
+        input_method.commit_string("pinnacle".to_string());
+        input_method.commit(client.input_method_done_count);
+
+        dispatch_until(&mut queue, &mut client, |client| {
+            client.committed_text.is_some()
+        });
+
+        assert_eq!(client.committed_text.as_deref(), Some("pinnacle"));
+    })
+}
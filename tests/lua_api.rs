@@ -357,6 +357,160 @@ mod window {
                 });
             })
         }
+
+        #[tokio::main]
+        #[self::test]
+        async fn set_minimized() -> anyhow::Result<()> {
+            test_api(|sender| {
+                run_lua! { |Pinnacle|
+                    Pinnacle.tag.add(Pinnacle.output.get_focused(), "1")[1]:set_active(true)
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                }
+
+                sleep_secs(1);
+
+                run_lua! { |Pinnacle|
+                    Pinnacle.window.get_all()[1]:set_minimized(true)
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let tiled = state.tiled_windows(&op);
+
+                    assert!(state.windows[0].with_state(|st| st.minimized));
+                    assert_eq!(tiled.len(), 1);
+                    assert_eq!(tiled[0], state.windows[1]);
+                });
+
+                run_lua! { |Pinnacle|
+                    assert(Pinnacle.window.get_all()[1]:minimized())
+                    Pinnacle.window.get_all()[1]:set_minimized(false)
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert!(!state.windows[0].with_state(|st| st.minimized));
+                    assert_eq!(state.tiled_windows(&op).len(), 2);
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn move_in_stack() -> anyhow::Result<()> {
+            test_api(|sender| {
+                run_lua! { |Pinnacle|
+                    Pinnacle.tag.add(Pinnacle.output.get_focused(), "1")[1]:set_active(true)
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                }
+
+                sleep_secs(1);
+
+                run_lua! { |Pinnacle|
+                    local windows = Pinnacle.window.get_all()
+                    windows[1]:move_in_stack(1)
+
+                    local moved = Pinnacle.window.get_all()
+                    assert(moved[1].id == windows[2].id)
+                    assert(moved[2].id == windows[1].id)
+                    assert(moved[3].id == windows[3].id)
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    assert_eq!(state.tiled_windows(&op), state.windows);
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn group_with() -> anyhow::Result<()> {
+            test_api(|sender| {
+                run_lua! { |Pinnacle|
+                    Pinnacle.tag.add(Pinnacle.output.get_focused(), "1")[1]:set_active(true)
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                }
+
+                sleep_secs(1);
+
+                run_lua! { |Pinnacle|
+                    local windows = Pinnacle.window.get_all()
+                    windows[3]:group_with(windows[2])
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let tiled = state.tiled_windows(&op);
+
+                    assert_eq!(tiled.len(), 2);
+                    assert_eq!(tiled[1], state.windows[1]);
+                    assert_eq!(state.windows[1].group(), state.windows[2].group());
+                    assert!(state.windows[2].is_inactive_in_group());
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn close_restores_swallowed_window() -> anyhow::Result<()> {
+            test_api(|sender| {
+                run_lua! { |Pinnacle|
+                    Pinnacle.tag.add(Pinnacle.output.get_focused(), "1")[1]:set_active(true)
+                    Pinnacle.window.add_window_rule({
+                        cond = { classes = { "foot" } },
+                        rule = { can_swallow = true },
+                    })
+                    Pinnacle.process.spawn("foot")
+                    Pinnacle.process.spawn("foot")
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    assert!(state
+                        .windows
+                        .iter()
+                        .all(|win| win.with_state(|st| st.can_swallow)));
+
+                    // Swallowing needs the new window's process to be started by the parent's,
+                    // so set it up directly
+                    let parent = state.windows[0].clone();
+                    let child = state.windows[1].clone();
+                    state.swallow_window(&child, &parent);
+                });
+
+                sleep_secs(1);
+
+                run_lua! { |Pinnacle|
+                    Pinnacle.window.get_all()[1]:close()
+                }
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert_eq!(state.windows.len(), 1);
+                    assert!(!state.windows[0].with_state(|st| st.swallowed));
+                    assert_eq!(state.tiled_windows(&op), state.windows);
+                });
+            })
+        }
     }
 }
 
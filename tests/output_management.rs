@@ -0,0 +1,132 @@
+mod common;
+
+use std::collections::HashMap;
+
+use pinnacle::protocol::output_management::OutputConfiguration;
+use smithay::{output::Mode, utils::Transform};
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, output_for_name, run_rust, spawn_windows, test_api, window_for_class,
+    with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn output_configuration_applies_and_tests() -> anyhow::Result<()> {
+    test_api(|sender| {
+        with_state(&sender, |state| {
+            let output = output_for_name(state, "Pinnacle Window");
+
+            let config = HashMap::from([(
+                output.clone(),
+                OutputConfiguration::Enabled {
+                    mode: Some(Mode {
+                        size: (1920, 1080).into(),
+                        refresh: 0,
+                    }),
+                    position: Some((500, 250).into()),
+                    transform: Some(Transform::Normal),
+                    scale: Some(2.0),
+                    adaptive_sync: None,
+                },
+            )]);
+
+            // Testing a configuration must not change anything
+            assert!(state.apply_output_configuration(config.clone(), true));
+            assert_eq!(output.current_scale().fractional_scale(), 1.0);
+            assert_eq!(output.current_transform(), Transform::Flipped180);
+
+            assert!(state.apply_output_configuration(config, false));
+            assert_eq!(output.current_scale().fractional_scale(), 2.0);
+            assert_eq!(output.current_transform(), Transform::Normal);
+            assert_eq!(output.current_location(), (500, 250).into());
+        });
+
+        with_state(&sender, |state| {
+            let output = output_for_name(state, "Pinnacle Window");
+
+            let unsupported_mode = HashMap::from([(
+                output.clone(),
+                OutputConfiguration::Enabled {
+                    mode: Some(Mode {
+                        size: (1234, 567).into(),
+                        refresh: 60_000,
+                    }),
+                    position: None,
+                    transform: None,
+                    scale: None,
+                    adaptive_sync: None,
+                },
+            )]);
+
+            assert!(!state.apply_output_configuration(unsupported_mode, false));
+
+            // The only output can't be disabled
+            let disabled = HashMap::from([(output.clone(), OutputConfiguration::Disabled)]);
+
+            assert!(!state.apply_output_configuration(disabled, true));
+        });
+    })
+}
+
+#[tokio::main]
+#[self::test]
+async fn disabling_an_output_moves_its_windows() -> anyhow::Result<()> {
+    test_api(|sender| {
+        with_state(&sender, |state| {
+            state.new_output("Second", (1920, 1080).into());
+        });
+
+        add_active_tag("1");
+        run_rust(|api| {
+            let second = api.output.get_by_name("Second").unwrap();
+            api.tag.add(&second, ["2"])[0].set_active(true);
+        });
+        spawn_windows(&["moved"]);
+
+        let enabled = || OutputConfiguration::Enabled {
+            mode: None,
+            position: None,
+            transform: None,
+            scale: None,
+            adaptive_sync: None,
+        };
+
+        with_state(&sender, |state| {
+            let first = output_for_name(state, "Pinnacle Window");
+            let second = output_for_name(state, "Second");
+
+            assert_eq!(
+                window_for_class(state, "moved").output(state),
+                Some(first.clone())
+            );
+
+            let config = HashMap::from([
+                (first.clone(), OutputConfiguration::Disabled),
+                (second.clone(), enabled()),
+            ]);
+
+            assert!(state.apply_output_configuration(config, false));
+            assert!(!state.space.outputs().any(|op| op == &first));
+            assert_eq!(state.disabled_outputs, vec![first.clone()]);
+            assert_eq!(
+                window_for_class(state, "moved").output(state),
+                Some(second.clone())
+            );
+
+            // Disabling an output that is already disabled is fine
+            let config = HashMap::from([(first.clone(), OutputConfiguration::Disabled)]);
+            assert!(state.apply_output_configuration(config, false));
+
+            // but disabling every output isn't
+            let config = HashMap::from([(second.clone(), OutputConfiguration::Disabled)]);
+            assert!(!state.apply_output_configuration(config, true));
+
+            let config = HashMap::from([(first.clone(), enabled())]);
+            assert!(state.apply_output_configuration(config, false));
+            assert!(state.space.outputs().any(|op| op == &first));
+            assert!(state.disabled_outputs.is_empty());
+        });
+    })
+}
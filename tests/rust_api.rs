@@ -6,22 +6,7 @@ use pinnacle_api::ApiModules;
 use test_log::test;
 
 use crate::common::output_for_name;
-use crate::common::{sleep_secs, test_api, with_state};
-
-#[tokio::main]
-async fn run_rust_inner(run: impl FnOnce(ApiModules) + Send + 'static) {
-    let (api, _recv) = pinnacle_api::connect().await.unwrap();
-
-    run(api.clone());
-}
-
-fn run_rust(run: impl FnOnce(ApiModules) + Send + 'static) {
-    std::thread::spawn(|| {
-        run_rust_inner(run);
-    })
-    .join()
-    .unwrap();
-}
+use crate::common::{run_rust, sleep_secs, test_api, with_state};
 
 #[tokio::main]
 async fn setup_rust_inner(run: impl FnOnce(ApiModules) + Send + 'static) {
@@ -352,3 +337,505 @@ mod output {
         }
     }
 }
+
+mod window {
+    use pinnacle::state::WithState;
+    use pinnacle_api::{
+        signal::WindowSignal,
+        window::rules::{WindowRule, WindowRuleCondition},
+    };
+
+    use crate::common::{add_active_tag, spawn_windows, window_classes, window_for_class};
+
+    use super::*;
+
+    mod handle {
+        use super::*;
+
+        #[tokio::main]
+        #[self::test]
+        async fn set_minimized() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second"]);
+
+                run_rust(|api| {
+                    api.window.get_all()[0].set_minimized(true);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let first = window_for_class(state, "first");
+
+                    assert!(first.with_state(|st| st.minimized));
+                    // Minimized windows are left out of the layout and unmapped
+                    assert_eq!(window_classes(&state.tiled_windows(&op)), ["second"]);
+                    assert!(!state.space.elements().any(|win| win == &first));
+                });
+
+                run_rust(|api| {
+                    api.window.get_all()[0].set_minimized(false);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let first = window_for_class(state, "first");
+
+                    assert!(!first.with_state(|st| st.minimized));
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["first", "second"]
+                    );
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn set_opacity_is_clamped() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first"]);
+
+                run_rust(|api| {
+                    api.window.get_all()[0].set_opacity(1.5);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let first = window_for_class(state, "first");
+                    assert_eq!(first.with_state(|st| st.opacity), Some(1.0));
+                });
+
+                run_rust(|api| {
+                    api.window.get_all()[0].set_opacity(-0.5);
+                    api.window.set_default_opacity(2.0, 0.5);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let first = window_for_class(state, "first");
+                    assert_eq!(first.with_state(|st| st.opacity), Some(0.0));
+                    assert_eq!(state.config.window_opacity.focused, 1.0);
+                    assert_eq!(state.config.window_opacity.unfocused, 0.5);
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn move_in_stack() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second", "third"]);
+
+                run_rust(|api| {
+                    api.window.get_all()[2].move_in_stack(-2);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert_eq!(window_classes(&state.windows), ["third", "first", "second"]);
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["third", "first", "second"]
+                    );
+                });
+
+                // Moving past the end of the stack clamps to it
+                run_rust(|api| {
+                    api.window.get_all()[0].move_in_stack(5);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    assert_eq!(window_classes(&state.windows), ["first", "second", "third"]);
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn group_with() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second", "third"]);
+
+                run_rust(|api| {
+                    let windows = api.window.get_all();
+                    windows[2].group_with(&windows[0]);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    // The new group member takes the group's slot and hides the other one,
+                    // so only two windows are laid out
+                    assert_eq!(window_classes(&state.windows), ["third", "second", "first"]);
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["third", "second"]
+                    );
+                    assert!(window_for_class(state, "first").is_inactive_in_group());
+                    assert_eq!(state.window_groups.len(), 1);
+                });
+
+                run_rust(|api| {
+                    api.window.get_all()[0].cycle_group(1);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert_eq!(window_classes(&state.windows), ["first", "second", "third"]);
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["first", "second"]
+                    );
+                });
+
+                run_rust(|api| {
+                    api.window.get_all()[2].ungroup();
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert!(state.windows.iter().all(|win| win.group().is_none()));
+                    assert!(state.window_groups.is_empty());
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["first", "second", "third"]
+                    );
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn set_focused_shows_hidden_group_member() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second"]);
+
+                run_rust(|api| {
+                    let windows = api.window.get_all();
+                    windows[1].group_with(&windows[0]);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    assert!(window_for_class(state, "first").is_inactive_in_group());
+                });
+
+                run_rust(|api| {
+                    api.window
+                        .get_all()
+                        .into_iter()
+                        .find(|win| win.class().as_deref() == Some("first"))
+                        .unwrap()
+                        .set_focused(true);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let first = window_for_class(state, "first");
+                    let second = window_for_class(state, "second");
+
+                    assert_eq!(first.group().unwrap().active(), first);
+                    assert!(second.is_inactive_in_group());
+                    assert!(state.space.elements().any(|win| win == &first));
+                    assert!(!state.space.elements().any(|win| win == &second));
+                    assert_eq!(state.focused_window(&op), Some(first));
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn closing_group_member_dissolves_group() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second"]);
+
+                run_rust(|api| {
+                    let windows = api.window.get_all();
+                    windows[1].group_with(&windows[0]);
+                });
+
+                sleep_secs(1);
+
+                // Close the shown window so the hidden one has to take its place
+                run_rust(|api| {
+                    api.window
+                        .get_all()
+                        .into_iter()
+                        .find(|win| win.class().as_deref() == Some("second"))
+                        .unwrap()
+                        .close();
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+                    let first = window_for_class(state, "first");
+
+                    assert_eq!(window_classes(&state.windows), ["first"]);
+                    assert!(first.group().is_none());
+                    assert!(state.window_groups.is_empty());
+                    assert_eq!(window_classes(&state.tiled_windows(&op)), ["first"]);
+                    assert!(state.space.elements().any(|win| win == &first));
+                });
+            })
+        }
+
+        #[tokio::main]
+        #[self::test]
+        async fn close_restores_swallowed_window_in_place() -> anyhow::Result<()> {
+            test_api(|sender| {
+                add_active_tag("1");
+                spawn_windows(&["first", "second", "third"]);
+
+                // Swallowing needs the new window's process to be started by the parent's,
+                // so set it up directly
+                with_state(&sender, |state| {
+                    let parent = window_for_class(state, "first");
+                    let child = window_for_class(state, "third");
+                    state.swallow_window(&child, &parent);
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    assert_eq!(window_classes(&state.windows), ["third", "first", "second"]);
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["third", "second"]
+                    );
+                    assert!(window_for_class(state, "first").with_state(|st| st.swallowed));
+                });
+
+                run_rust(|api| {
+                    api.window.get_all()[0].close();
+                });
+
+                sleep_secs(1);
+
+                with_state(&sender, |state| {
+                    let op = state.focused_output().unwrap().clone();
+
+                    // The parent comes back in the child's place, not at the end
+                    assert_eq!(window_classes(&state.windows), ["first", "second"]);
+                    assert_eq!(
+                        window_classes(&state.tiled_windows(&op)),
+                        ["first", "second"]
+                    );
+                    assert!(!window_for_class(state, "first").with_state(|st| st.swallowed));
+                });
+            })
+        }
+    }
+
+    #[tokio::main]
+    #[self::test]
+    async fn urgent_signal() -> anyhow::Result<()> {
+        test_api(|sender| {
+            add_active_tag("1");
+            spawn_windows(&["first", "second"]);
+
+            let (urgent_sender, urgent_recv) = std::sync::mpsc::channel();
+
+            setup_rust(move |api| {
+                api.window
+                    .connect_signal(WindowSignal::Urgent(Box::new(move |win, urgent| {
+                        urgent_sender.send((win.class(), urgent)).unwrap();
+                    })));
+            });
+
+            sleep_secs(1);
+
+            with_state(&sender, |state| {
+                let first = window_for_class(state, "first");
+                state.set_window_urgent(&first);
+                // Windows that are already urgent don't signal again
+                state.set_window_urgent(&first);
+            });
+
+            sleep_secs(1);
+
+            // Focusing the window makes it stop being urgent
+            with_state(&sender, |state| {
+                let first = window_for_class(state, "first");
+                state.activate_window(first);
+            });
+
+            sleep_secs(1);
+
+            let first = Some("first".to_string());
+            assert_eq!(
+                urgent_recv.try_iter().collect::<Vec<_>>(),
+                [(first.clone(), true), (first, false)]
+            );
+        })
+    }
+
+    /// Open a `foot` window with app id `parent` that starts a `foot` window with app id
+    /// `child` from its shell a second later, after the parent has mapped.
+    fn spawn_parent_and_child() {
+        run_rust(|api| {
+            api.process.spawn([
+                "foot",
+                "--app-id=parent",
+                "sh",
+                "-c",
+                "sleep 1; foot --app-id=child",
+            ])
+        });
+        sleep_secs(3);
+    }
+
+    #[tokio::main]
+    #[self::test]
+    async fn matched_parent_is_swallowed() -> anyhow::Result<()> {
+        test_api(|sender| {
+            add_active_tag("1");
+            spawn_windows(&["other"]);
+
+            run_rust(|api| {
+                api.window.add_window_rule(
+                    WindowRuleCondition::new().classes(["parent"]),
+                    WindowRule::new().can_swallow(true),
+                );
+            });
+
+            spawn_parent_and_child();
+
+            with_state(&sender, |state| {
+                let op = state.focused_output().unwrap().clone();
+                let parent = window_for_class(state, "parent");
+                let child = window_for_class(state, "child");
+
+                assert!(parent.with_state(|st| st.swallowed));
+                assert_eq!(child.with_state(|st| st.swallowing.clone()), Some(parent));
+                // The child takes the parent's place instead of opening at the end
+                assert_eq!(window_classes(&state.windows), ["other", "child", "parent"]);
+                assert_eq!(
+                    window_classes(&state.tiled_windows(&op)),
+                    ["other", "child"]
+                );
+            });
+        })
+    }
+
+    #[tokio::main]
+    #[self::test]
+    async fn unmatched_parent_isnt_swallowed() -> anyhow::Result<()> {
+        test_api(|sender| {
+            add_active_tag("1");
+
+            run_rust(|api| {
+                api.window.add_window_rule(
+                    WindowRuleCondition::new().classes(["other"]),
+                    WindowRule::new().can_swallow(true),
+                );
+            });
+
+            spawn_parent_and_child();
+
+            with_state(&sender, |state| {
+                let op = state.focused_output().unwrap().clone();
+                let parent = window_for_class(state, "parent");
+                let child = window_for_class(state, "child");
+
+                assert!(!parent.with_state(|st| st.swallowed));
+                assert!(child.with_state(|st| st.swallowing.is_none()));
+                assert_eq!(
+                    window_classes(&state.tiled_windows(&op)),
+                    ["parent", "child"]
+                );
+            });
+        })
+    }
+}
+
+mod render {
+    use pinnacle::render::{MAX_BORDER_WIDTH, MAX_TAB_BAR_HEIGHT};
+
+    use super::*;
+
+    #[tokio::main]
+    #[self::test]
+    async fn set_borders_caps_width() -> anyhow::Result<()> {
+        test_api(|sender| {
+            let red = [1.0, 0.0, 0.0, 1.0];
+            let gray = [0.5, 0.5, 0.5, 1.0];
+
+            run_rust(move |api| api.render.set_borders(4, red, gray, red));
+
+            sleep_secs(1);
+
+            with_state(&sender, move |state| {
+                assert_eq!(state.config.borders.width, 4);
+                assert_eq!(state.config.borders.focused, red);
+                assert_eq!(state.config.borders.unfocused, gray);
+            });
+
+            run_rust(move |api| api.render.set_borders(u32::MAX, red, gray, red));
+
+            sleep_secs(1);
+
+            with_state(&sender, |state| {
+                assert_eq!(state.config.borders.width, MAX_BORDER_WIDTH as i32);
+            });
+        })
+    }
+
+    #[tokio::main]
+    #[self::test]
+    async fn set_tab_bar_caps_height() -> anyhow::Result<()> {
+        test_api(|sender| {
+            let red = [1.0, 0.0, 0.0, 1.0];
+            let gray = [0.5, 0.5, 0.5, 1.0];
+
+            run_rust(move |api| api.render.set_tab_bar(12, red, gray, gray, red));
+
+            sleep_secs(1);
+
+            with_state(&sender, move |state| {
+                assert_eq!(state.config.tab_bar.height, 12);
+                assert_eq!(state.config.tab_bar.focused, red);
+                assert_eq!(state.config.tab_bar.inactive, gray);
+            });
+
+            run_rust(move |api| api.render.set_tab_bar(u32::MAX, red, gray, gray, red));
+
+            sleep_secs(1);
+
+            with_state(&sender, |state| {
+                assert_eq!(state.config.tab_bar.height, MAX_TAB_BAR_HEIGHT as i32);
+            });
+        })
+    }
+}
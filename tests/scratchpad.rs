@@ -0,0 +1,61 @@
+mod common;
+
+use pinnacle::state::WithState;
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, output_for_name, spawn_windows, test_api, window_for_class, with_state,
+};
+
+#[tokio::main]
+#[self::test]
+async fn scratchpad_follows_the_focused_output() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["term"]);
+
+        with_state(&sender, |state| {
+            state.new_output("Second", (1920, 1080).into());
+
+            let second = output_for_name(state, "Second");
+            second.change_current_state(None, None, None, Some((10000, 0).into()));
+            state.space.map_output(&second, (10000, 0));
+
+            let first = output_for_name(state, "Pinnacle Window");
+            state.output_focus_stack.set_focus(first.clone());
+
+            let term = window_for_class(state, "term");
+
+            state.send_to_scratchpad(&term, "term".to_string());
+
+            assert!(term.with_state(|st| st.tags.is_empty()));
+            assert!(state.space.element_location(&term).is_none());
+
+            // Shown on the focused output
+            state.toggle_scratchpad("term");
+
+            assert_eq!(term.scratchpad_output(), Some(first.clone()));
+            assert!(term.with_state(|st| st.floating_or_tiled.is_floating()));
+            let first_geo = state.space.output_geometry(&first).unwrap();
+            assert!(first_geo.contains(state.space.element_location(&term).unwrap()));
+            assert_eq!(state.focused_window(&first), Some(term.clone()));
+
+            // Toggling from another output moves it there instead of hiding it
+            state.output_focus_stack.set_focus(second.clone());
+            state.toggle_scratchpad("term");
+
+            assert_eq!(term.scratchpad_output(), Some(second.clone()));
+            let second_geo = state.space.output_geometry(&second).unwrap();
+            assert!(second_geo.contains(state.space.element_location(&term).unwrap()));
+            assert_eq!(state.focused_window(&second), Some(term.clone()));
+            assert!(!first.with_state(|st| st.focus_stack.stack.contains(&term)));
+
+            // Toggling where it's shown hides it
+            state.toggle_scratchpad("term");
+
+            assert_eq!(term.scratchpad_output(), None);
+            assert!(state.space.element_location(&term).is_none());
+            assert_eq!(term.scratchpad_name().as_deref(), Some("term"));
+        });
+    })
+}
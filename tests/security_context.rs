@@ -0,0 +1,96 @@
+mod common;
+
+use std::{collections::HashSet, os::unix::net::UnixStream, sync::Arc};
+
+use pinnacle::{
+    security::{PrivilegedGlobal, SandboxRule},
+    state::ClientState,
+};
+use smithay::{reexports::calloop::channel::Sender, wayland::security_context::SecurityContext};
+use test_log::test;
+use wayland_client::{
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::wl_registry::{self, WlRegistry},
+    Connection, Dispatch, QueueHandle,
+};
+
+use crate::common::{test_api, with_state, StateFn};
+
+const SCREENCOPY: &str = "zwlr_screencopy_manager_v1";
+
+struct TestClient;
+
+impl Dispatch<WlRegistry, GlobalListContents> for TestClient {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+/// Returns whether a client connected through `stream` sees the global `interface`.
+fn sees_global(stream: UnixStream, interface: &str) -> bool {
+    let conn = Connection::from_socket(stream).unwrap();
+    let (globals, _queue) = registry_queue_init::<TestClient>(&conn).unwrap();
+    globals
+        .contents()
+        .with_list(|list| list.iter().any(|global| global.interface == interface))
+}
+
+/// Connect an unsandboxed client and a client sandboxed by `flatpak` as `app_id`.
+fn connect_clients(sender: &Sender<StateFn>, app_id: &'static str) -> (UnixStream, UnixStream) {
+    let (client_stream, server_stream) = UnixStream::pair().unwrap();
+    let (sandboxed_client_stream, sandboxed_server_stream) = UnixStream::pair().unwrap();
+
+    with_state(sender, move |state| {
+        let creator = state
+            .display_handle
+            .insert_client(server_stream, Arc::new(ClientState::default()))
+            .unwrap();
+
+        let client_state = ClientState {
+            security_context: Some(SecurityContext {
+                sandbox_engine: Some("flatpak".to_string()),
+                app_id: Some(app_id.to_string()),
+                instance_id: None,
+                creator_client_id: creator.id(),
+            }),
+            ..Default::default()
+        };
+        state
+            .display_handle
+            .insert_client(sandboxed_server_stream, Arc::new(client_state))
+            .unwrap();
+    });
+
+    (client_stream, sandboxed_client_stream)
+}
+
+#[tokio::main]
+#[self::test]
+async fn sandboxed_clients_only_see_allowed_globals() -> anyhow::Result<()> {
+    test_api(|sender| {
+        let (client, sandboxed) = connect_clients(&sender, "org.example.Recorder");
+
+        assert!(sees_global(client, SCREENCOPY));
+        assert!(!sees_global(sandboxed, SCREENCOPY));
+
+        with_state(&sender, |state| {
+            state.config.sandbox_allowlist.add_rule(SandboxRule {
+                sandbox_engine: Some("flatpak".to_string()),
+                app_id: Some("org.example.Recorder".to_string()),
+                globals: HashSet::from([PrivilegedGlobal::Screencopy]),
+            });
+        });
+
+        let (_, allowed) = connect_clients(&sender, "org.example.Recorder");
+        let (_, other_app) = connect_clients(&sender, "org.example.Other");
+
+        assert!(sees_global(allowed, SCREENCOPY));
+        assert!(!sees_global(other_app, SCREENCOPY));
+    })
+}
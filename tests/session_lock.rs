@@ -0,0 +1,44 @@
+mod common;
+
+use pinnacle::{
+    backend::Backend,
+    render::{output_render_elements, OutputRenderElement},
+    session_lock::LockState,
+};
+use test_log::test;
+
+use crate::common::{output_for_name, test_api, with_state};
+
+#[tokio::main]
+#[self::test]
+async fn locked_session_blanks_outputs_without_a_locker() -> anyhow::Result<()> {
+    test_api(|sender| {
+        with_state(&sender, |state| {
+            // Simulate a locker that locked then crashed before creating any lock surfaces
+            state.lock_state = LockState::Locked;
+
+            let output = output_for_name(state, "Pinnacle Window");
+            let windows = state.windows.clone();
+            let render_options = state.render_options();
+
+            let Backend::Dummy(dummy) = &mut state.backend else {
+                unreachable!()
+            };
+
+            let elements = output_render_elements(
+                &output,
+                &mut dummy.renderer,
+                &state.space,
+                &windows,
+                &render_options,
+            );
+
+            assert!(matches!(
+                elements.as_slice(),
+                [OutputRenderElement::Solid(_)]
+            ));
+
+            assert!(state.pointer_focus_target_under((50.0, 50.0)).is_none());
+        });
+    })
+}
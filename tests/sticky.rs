@@ -0,0 +1,46 @@
+mod common;
+
+use pinnacle::state::WithState;
+use test_log::test;
+
+use crate::common::{run_rust, spawn_windows, test_api, window_for_class, with_state};
+
+#[tokio::main]
+#[self::test]
+async fn sticky_windows_are_hidden_without_active_tags() -> anyhow::Result<()> {
+    test_api(|sender| {
+        run_rust(|api| {
+            let output = api.output.get_focused().unwrap();
+            api.tag.add(&output, ["1", "2"])[0].set_active(true);
+        });
+        spawn_windows(&["pip", "other"]);
+
+        with_state(&sender, |state| {
+            let output = state.focused_output().unwrap().clone();
+            let tags = output.with_state(|state| state.tags.clone());
+            let pip = window_for_class(state, "pip");
+            let other = window_for_class(state, "other");
+
+            state.set_window_sticky(&pip, true);
+
+            // Sticky windows follow the active tag
+            state.switch_to_tag(&tags[1]);
+
+            assert!(pip.is_on_active_tag());
+            assert!(!other.is_on_active_tag());
+            assert!(state.tiled_windows(&output).contains(&pip));
+
+            // But not onto an output with nothing shown
+            state.set_tag_active(&tags[1], false);
+
+            assert!(!pip.is_on_active_tag());
+            assert!(!state.tiled_windows(&output).contains(&pip));
+
+            // Non-sticky windows stay on their own tags
+            state.set_window_sticky(&pip, false);
+            state.switch_to_tag(&tags[1]);
+
+            assert!(!pip.is_on_active_tag());
+        });
+    })
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use pinnacle::{
+    focus::ActivationPolicy,
+    state::{State, WithState},
+    window::WindowElement,
+};
+use smithay::wayland::{
+    seat::WaylandFocus,
+    xdg_activation::{XdgActivationHandler, XdgActivationToken},
+};
+use test_log::test;
+
+use crate::common::{
+    add_active_tag, output_for_name, spawn_windows, test_api, window_for_class, with_state,
+};
+
+/// Have `window` request activation with a token created on the currently focused output.
+fn request_activation(state: &mut State, window: &WindowElement) {
+    let token = XdgActivationToken::from(state.create_spawn_activation_token());
+    let token_data = state
+        .xdg_activation_state
+        .data_for_token(&token)
+        .unwrap()
+        .clone();
+
+    state.request_activation(token, token_data, window.wl_surface().unwrap());
+}
+
+fn is_urgent(window: &WindowElement) -> bool {
+    window.with_state(|state| state.urgent)
+}
+
+#[tokio::main]
+#[self::test]
+async fn focus_policy_focuses_window() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["first", "second"]);
+
+        with_state(&sender, |state| {
+            state.config.activation_policy = ActivationPolicy::Focus;
+
+            let output = state.focused_output().unwrap().clone();
+            let first = window_for_class(state, "first");
+
+            request_activation(state, &first);
+
+            assert_eq!(state.focused_window(&output), Some(first.clone()));
+            assert!(!is_urgent(&first));
+        });
+    })
+}
+
+#[tokio::main]
+#[self::test]
+async fn urgent_policy_marks_window_urgent() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["first", "second"]);
+
+        with_state(&sender, |state| {
+            state.config.activation_policy = ActivationPolicy::Urgent;
+
+            let output = state.focused_output().unwrap().clone();
+            let first = window_for_class(state, "first");
+            let second = window_for_class(state, "second");
+
+            request_activation(state, &first);
+
+            assert_eq!(state.focused_window(&output), Some(second));
+            assert!(is_urgent(&first));
+        });
+    })
+}
+
+#[tokio::main]
+#[self::test]
+async fn focus_same_output_policy_checks_launch_output() -> anyhow::Result<()> {
+    test_api(|sender| {
+        add_active_tag("1");
+        spawn_windows(&["first", "second"]);
+
+        with_state(&sender, |state| {
+            state.config.activation_policy = ActivationPolicy::FocusSameOutput;
+            state.new_output("Second", (1920, 1080).into());
+
+            let output = output_for_name(state, "Pinnacle Window");
+            let other_output = output_for_name(state, "Second");
+            let first = window_for_class(state, "first");
+            let second = window_for_class(state, "second");
+
+            // Launched from another output, so only marked urgent
+            state.output_focus_stack.set_focus(other_output);
+            let token = XdgActivationToken::from(state.create_spawn_activation_token());
+            let token_data = state
+                .xdg_activation_state
+                .data_for_token(&token)
+                .unwrap()
+                .clone();
+            state.output_focus_stack.set_focus(output.clone());
+
+            state.request_activation(token, token_data, first.wl_surface().unwrap());
+
+            assert_eq!(state.focused_window(&output), Some(second.clone()));
+            assert!(is_urgent(&first));
+
+            // Launched from the focused output
+            request_activation(state, &first);
+
+            assert_eq!(state.focused_window(&output), Some(first.clone()));
+            assert!(!is_urgent(&first));
+        });
+    })
+}